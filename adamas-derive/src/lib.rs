@@ -0,0 +1,272 @@
+//! Adamas Derive
+//!
+//! `#[derive(Adamas)]` for structs with named fields, used through the
+//! `derive` feature of adamas. Generates `compress(&self)` and
+//! `decompress(&mut Accumulator)` methods that compress every field with the
+//! spec given by its `#[adamas(...)]` attribute:
+//!
+//! - `bool` fields need no attribute
+//! - `#[adamas(int_range(min = 0, max = 100))]` for integer fields
+//!   (`i8` to `i64` and `u8` to `u64`)
+//! - `#[adamas(charset = "lowercase_ascii", max_len = 20)]` for `String`
+//!   fields, or only the charset for `char` fields. The charset is the name of
+//!   one of the built-in CharSets
+//! - `#[adamas(enumeration("Red", "Green", "Blue"))]` for `String` fields
+//!
+//! Fields are compressed last to first so they decompress first to last, the
+//! same as a Schema.
+//!
+//! Invalid attributes are compile errors pointing at the offending field
+//! or option, see the UI tests in tests/ui.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, ExprLit, ExprUnary, Field, Fields, Lit, LitInt, LitStr, Token, Type, UnOp};
+
+/// Built-in CharSets that can be given by name
+const CHARSETS: &[&str] = &[
+    "lowercase_letter",
+    "lowercase_ascii",
+    "uppercase_letter",
+    "uppercase_ascii",
+    "ascii_printable",
+    "digits",
+    "hex_lower",
+    "hex_upper",
+    "base64url",
+    "alphanumeric",
+];
+
+#[proc_macro_derive(Adamas, attributes(adamas))]
+pub fn derive_adamas(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let message = "Adamas can only be derived for structs with named fields";
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            fields => return Err(Error::new_spanned(fields, message)),
+        },
+        Data::Enum(data) => return Err(Error::new_spanned(data.enum_token, message)),
+        Data::Union(data) => return Err(Error::new_spanned(data.union_token, message)),
+    };
+    let mut names = Vec::new();
+    let mut specs = Vec::new();
+    for field in fields {
+        names.push(field.ident.clone().expect("named field"));
+        specs.push(field_spec(field)?);
+    }
+    let labels: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+    let reversed = names.iter().zip(&specs).zip(&labels).rev().map(|((name, spec), label)| quote! {
+        #spec
+            .and_then(|spec| ::adamas::data::WideDataSpec::encode_into(&spec, &self.#name, &mut accum))
+            .map_err(|error| ::adamas::data::field_error(#label, error))?;
+    });
+    let decoded = names.iter().zip(&specs).zip(&labels).map(|((name, spec), label)| quote! {
+        let #name = match #spec.and_then(|spec| ::adamas::data::WideDataSpec::decode_from(&spec, accum)) {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(error) => {
+                *accum = snapshot;
+                return ::core::result::Result::Err(::adamas::data::field_error(#label, error));
+            },
+        };
+    });
+    // A struct without fields reads nothing from the accumulator
+    let snapshot = match names.is_empty() {
+        true => quote! { let _ = accum; },
+        false => quote! { let snapshot = accum.clone(); },
+    };
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #ident #type_generics #where_clause {
+
+            /// Compress every field into a new accumulator
+            pub fn compress(&self) -> ::core::result::Result<::adamas::accum::Accumulator, ::adamas::Error> {
+                let mut accum = ::adamas::accum::Accumulator::new();
+                #(#reversed)*
+                ::core::result::Result::Ok(accum)
+            }
+
+            /// Decompress every field from the accumulator, leaving it
+            /// unchanged on error
+            pub fn decompress(accum: &mut ::adamas::accum::Accumulator) -> ::core::result::Result<Self, ::adamas::Error> {
+                #snapshot
+                #(#decoded)*
+                ::core::result::Result::Ok(Self { #(#names),* })
+            }
+        }
+    })
+}
+
+/// Options of the `#[adamas(...)]` attribute of a field
+#[derive(Default)]
+struct FieldOptions {
+    int_range: Option<(Expr, Expr)>,
+    charset: Option<LitStr>,
+    max_len: Option<LitInt>,
+    enumeration: Option<Vec<LitStr>>,
+}
+
+fn field_options(field: &Field) -> Result<FieldOptions, Error> {
+    let mut options = FieldOptions::default();
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("adamas")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("int_range") {
+                let (mut min, mut max) = (None, None);
+                meta.parse_nested_meta(|bound| {
+                    if bound.path.is_ident("min") {
+                        min = Some(bound.value()?.parse::<Expr>()?);
+                    } else if bound.path.is_ident("max") {
+                        max = Some(bound.value()?.parse::<Expr>()?);
+                    } else {
+                        return Err(bound.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                match (min, max) {
+                    (Some(min), Some(max)) => options.int_range = Some((min, max)),
+                    _ => return Err(meta.error("int_range needs both `min` and `max`")),
+                }
+            } else if meta.path.is_ident("charset") {
+                let charset: LitStr = meta.value()?.parse()?;
+                if !CHARSETS.contains(&charset.value().as_str()) {
+                    return Err(Error::new_spanned(
+                        &charset,
+                        format!("unknown charset, expected one of: {}", CHARSETS.join(", ")),
+                    ));
+                }
+                options.charset = Some(charset);
+            } else if meta.path.is_ident("max_len") {
+                options.max_len = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("enumeration") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let values = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                if values.is_empty() {
+                    return Err(meta.error("enumeration needs at least one value"));
+                }
+                options.enumeration = Some(values.into_iter().collect());
+            } else {
+                return Err(meta.error("expected `int_range`, `charset`, `max_len` or `enumeration`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+/// Expression for the spec of a field as a Result, or an error pointing at
+/// the field if its type and attribute do not go together. Specs that can
+/// still be invalid at runtime (e.g. a range too wide for a 32 bit Digit) are
+/// built with `try_new` so the error is returned as a field error
+fn field_spec(field: &Field) -> Result<TokenStream2, Error> {
+    let options = field_options(field)?;
+    let ty = &field.ty;
+    let type_name = type_name(ty);
+    let is = |name: &str| type_name.as_deref() == Some(name);
+    let unsupported = |message: &str| Err(Error::new_spanned(ty, message));
+    match options {
+        FieldOptions { int_range: Some((min, max)), charset: None, max_len: None, enumeration: None } => {
+            let Some((type_min, type_max)) = type_name.as_deref().and_then(int_bounds) else {
+                return unsupported("int_range is only supported for i8 to i64 and u8 to u64 fields");
+            };
+            let bound = |expr: &Expr| -> Result<Option<i128>, Error> {
+                let value = int_literal(expr)?;
+                match value {
+                    Some(value) if value < type_min || value > type_max => Err(Error::new_spanned(
+                        expr,
+                        format!("{} is out of range for a {} field", value, type_name.as_deref().unwrap_or_default()),
+                    )),
+                    _ => Ok(value),
+                }
+            };
+            if let (Some(low), Some(high)) = (bound(&min)?, bound(&max)?) {
+                if low >= high {
+                    return Err(Error::new_spanned(&max, "int_range min must be less than max"));
+                }
+                if high - low >= i128::from(u64::MAX) {
+                    return Err(Error::new_spanned(&max, "int_range has more values than fit in a Digit"));
+                }
+            }
+            Ok(quote! { ::adamas::data::IntRangeOf::<#ty>::try_new(#min, #max) })
+        },
+        FieldOptions { int_range: None, charset: Some(charset), max_len: Some(max_len), enumeration: None } => {
+            if !is("String") {
+                return unsupported("charset with max_len is only supported for String fields");
+            }
+            let charset = syn::Ident::new(&charset.value(), charset.span());
+            Ok(quote! {
+                ::core::result::Result::Ok(::adamas::data::StringSpec::new(
+                    ::adamas::data::CharSet::#charset(),
+                    ::adamas::data::SequenceLength::Variable(#max_len),
+                ))
+            })
+        },
+        FieldOptions { int_range: None, charset: Some(charset), max_len: None, enumeration: None } => {
+            if !is("char") {
+                return unsupported("charset without max_len is only supported for char fields");
+            }
+            let charset = syn::Ident::new(&charset.value(), charset.span());
+            Ok(quote! { ::core::result::Result::Ok(::adamas::data::CharSet::#charset()) })
+        },
+        FieldOptions { int_range: None, charset: None, max_len: None, enumeration: Some(values) } => {
+            if !is("String") {
+                return unsupported("enumeration is only supported for String fields");
+            }
+            for (index, value) in values.iter().enumerate() {
+                if values[..index].iter().any(|earlier| earlier.value() == value.value()) {
+                    return Err(Error::new_spanned(value, format!("repeated enumeration value {:?}", value.value())));
+                }
+            }
+            Ok(quote! { ::adamas::data::Enum::try_new(&[#(#values),*]) })
+        },
+        FieldOptions { int_range: None, charset: None, max_len: None, enumeration: None } => {
+            if !is("bool") {
+                return unsupported("unsupported field type, add an #[adamas(...)] attribute to give its spec");
+            }
+            Ok(quote! { ::core::result::Result::Ok(::adamas::data::Bool::new()) })
+        },
+        _ => Err(Error::new_spanned(field, "conflicting #[adamas(...)] options for a field")),
+    }
+}
+
+/// Last segment of the path of a type (e.g. `String` for
+/// `std::string::String`)
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last().map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Smallest and largest values of an integer type of an IntRangeOf
+fn int_bounds(name: &str) -> Option<(i128, i128)> {
+    match name {
+        "i8" => Some((i128::from(i8::MIN), i128::from(i8::MAX))),
+        "i16" => Some((i128::from(i16::MIN), i128::from(i16::MAX))),
+        "i32" => Some((i128::from(i32::MIN), i128::from(i32::MAX))),
+        "i64" => Some((i128::from(i64::MIN), i128::from(i64::MAX))),
+        "u8" => Some((0, i128::from(u8::MAX))),
+        "u16" => Some((0, i128::from(u16::MAX))),
+        "u32" => Some((0, i128::from(u32::MAX))),
+        "u64" => Some((0, i128::from(u64::MAX))),
+        _ => None,
+    }
+}
+
+/// Value of an integer literal (e.g. `7` or `-7`), or None for any other
+/// expression, which is only checked at runtime
+fn int_literal(expr: &Expr) -> Result<Option<i128>, Error> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) => lit.base10_parse().map(Some),
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), expr, .. }) => Ok(int_literal(expr)?.map(|value: i128| -value)),
+        _ => Ok(None),
+    }
+}
+
@@ -0,0 +1,115 @@
+use adamas::accum::Accumulator;
+use adamas::data::{Bool, DataSpec, IntRangeOf, WideDataSpec};
+use adamas::{Adamas, Error};
+
+#[derive(Adamas, Debug, Clone, PartialEq)]
+struct SensorReading {
+    active: bool,
+    #[adamas(int_range(min = -40, max = 125))]
+    temperature: i16,
+    #[adamas(int_range(min = 0, max = 100))]
+    humidity: u8,
+    #[adamas(charset = "lowercase_ascii", max_len = 20)]
+    location: String,
+    #[adamas(enumeration("Red", "Green", "Blue"))]
+    led: String,
+    #[adamas(charset = "uppercase_letter")]
+    grade: char,
+}
+
+#[derive(Adamas, Debug, PartialEq)]
+struct Empty {}
+
+#[derive(Adamas, Debug, PartialEq)]
+struct Tag {
+    #[adamas(charset = "digits", max_len = 1)]
+    tag: String,
+}
+
+fn reading() -> SensorReading {
+    SensorReading {
+        active: true,
+        temperature: -12,
+        humidity: 55,
+        location: String::from("greenhouse 2"),
+        led: String::from("Green"),
+        grade: 'B',
+    }
+}
+
+#[test]
+fn round_trip() {
+    let value = reading();
+    let mut accum = value.compress().unwrap();
+    assert_eq!(SensorReading::decompress(&mut accum).unwrap(), value);
+    assert!(accum.is_empty());
+    let value = SensorReading {
+        active: false,
+        temperature: 125,
+        humidity: 0,
+        location: String::new(),
+        led: String::from("Blue"),
+        grade: 'Z',
+    };
+    let mut accum = value.compress().unwrap();
+    assert_eq!(SensorReading::decompress(&mut accum).unwrap(), value);
+    let mut accum = Empty {}.compress().unwrap();
+    assert!(accum.is_empty());
+    assert_eq!(Empty::decompress(&mut accum), Ok(Empty {}));
+}
+
+#[test]
+fn first_field_decompresses_first() {
+    // Fields are compressed last to first, so the first field is on top
+    let mut accum = reading().compress().unwrap();
+    assert_eq!(Bool::new().decode_from(&mut accum), Ok(true));
+    assert_eq!(IntRangeOf::<i16>::new(-40, 125).decode(accum.div(166)), Ok(-12));
+    assert_eq!(IntRangeOf::<u8>::new(0, 100).decode_from(&mut accum), Ok(55));
+}
+
+#[test]
+fn field_errors() {
+    let mut value = reading();
+    value.led = String::from("Purple");
+    assert_eq!(value.compress().err(), Some(Error::Field {
+        name: String::from("led"),
+        error: Box::new(Error::UnknownEnumVariant(String::from("Purple"))),
+    }));
+    let mut value = reading();
+    value.humidity = 101;
+    assert!(matches!(value.compress(), Err(Error::Field { name, .. }) if name == "humidity"));
+    let mut value = reading();
+    value.location = String::from("Greenhouse");
+    assert!(matches!(value.compress(), Err(Error::Field { name, .. }) if name == "location"));
+    // Two digits where the tag has room for one
+    let mut accum = Accumulator::new();
+    accum.add(3);
+    accum.mul(11);
+    accum.add(4);
+    let bytes = accum.to_bytes();
+    assert_eq!(Tag::decompress(&mut accum), Err(Error::Field {
+        name: String::from("tag"),
+        error: Box::new(Error::UnterminatedSequence { max: 1 }),
+    }));
+    assert_eq!(accum.to_bytes(), bytes);
+}
+
+const LIMIT: u8 = 5;
+
+#[derive(Adamas, Debug, PartialEq)]
+struct Limited {
+    #[adamas(int_range(min = 10, max = LIMIT))]
+    value: u8,
+}
+
+#[test]
+fn invalid_spec_is_field_error() {
+    // Bounds that are not literals are only checked at runtime
+    let error = Error::Field {
+        name: String::from("value"),
+        error: Box::new(Error::InvalidRange { min: String::from("10"), max: String::from("5") }),
+    };
+    assert_eq!(Limited { value: 3 }.compress().err(), Some(error.clone()));
+    let mut accum = Accumulator::new();
+    assert_eq!(Limited::decompress(&mut accum).err(), Some(error));
+}
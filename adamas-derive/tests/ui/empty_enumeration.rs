@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(enumeration())]
+    led: String,
+}
+
+fn main() {}
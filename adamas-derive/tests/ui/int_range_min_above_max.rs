@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(int_range(min = 10, max = 0))]
+    value: u8,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(int_range(min = 0, max = 10))]
+    name: String,
+}
+
+fn main() {}
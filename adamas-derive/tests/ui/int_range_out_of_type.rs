@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(int_range(min = -1, max = 10))]
+    value: u16,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(int_range(min = 0, max = 18446744073709551615))]
+    value: u64,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+enum Reading {
+    On,
+    Off,
+}
+
+fn main() {}
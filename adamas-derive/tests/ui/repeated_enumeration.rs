@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(enumeration("Red", "Green", "Red"))]
+    led: String,
+}
+
+fn main() {}
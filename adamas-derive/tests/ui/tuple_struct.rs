@@ -0,0 +1,4 @@
+#[derive(adamas::Adamas)]
+struct Reading(bool);
+
+fn main() {}
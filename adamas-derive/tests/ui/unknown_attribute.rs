@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(bits = 4)]
+    value: u8,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    #[adamas(charset = "klingon", max_len = 4)]
+    name: String,
+}
+
+fn main() {}
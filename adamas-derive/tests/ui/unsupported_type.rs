@@ -0,0 +1,7 @@
+#[derive(adamas::Adamas)]
+struct Reading {
+    active: bool,
+    value: f64,
+}
+
+fn main() {}
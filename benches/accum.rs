@@ -0,0 +1,85 @@
+//! Benchmarks for the accumulator math operations
+
+mod common;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use adamas::accum::{Accumulator, Digit, PreparedDivisor};
+
+use common::{random_accumulator, Rng};
+
+/// Accumulator sizes (in digits) used for all of the single operations
+const SIZES: [usize; 4] = [4, 64, 512, 1024];
+
+/// Multiplication of accumulators by a single digit
+fn mul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mul");
+    for digits in SIZES {
+        let mut rng = Rng::new();
+        let a = random_accumulator(&mut rng, digits);
+        let value = (rng.next() as Digit).max(2);
+        group.bench_with_input(BenchmarkId::from_parameter(digits), &digits, |b, _| {
+            b.iter_batched_ref(
+                || a.clone(),
+                |a| a.mul(black_box(value)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Division of accumulators by a single digit
+fn div(c: &mut Criterion) {
+    let mut group = c.benchmark_group("div");
+    for digits in SIZES {
+        let mut rng = Rng::new();
+        let a = random_accumulator(&mut rng, digits);
+        let value = (rng.next() as Digit).max(2);
+        group.bench_with_input(BenchmarkId::from_parameter(digits), &digits, |b, _| {
+            b.iter_batched_ref(
+                || a.clone(),
+                |a| a.div(black_box(value)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Division of accumulators by a single prepared divisor
+fn div_prepared(c: &mut Criterion) {
+    let mut group = c.benchmark_group("div_prepared");
+    for digits in SIZES {
+        let mut rng = Rng::new();
+        let a = random_accumulator(&mut rng, digits);
+        let divisor = PreparedDivisor::new((rng.next() as Digit).max(2));
+        group.bench_with_input(BenchmarkId::from_parameter(digits), &digits, |b, _| {
+            b.iter_batched_ref(
+                || a.clone(),
+                |a| a.div_prepared(black_box(&divisor)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Serialization of accumulators to and from bytes
+fn bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytes");
+    for digits in SIZES {
+        let a = random_accumulator(&mut Rng::new(), digits);
+        let bytes = a.to_bytes();
+        group.bench_with_input(BenchmarkId::new("to_bytes", digits), &digits, |b, _| {
+            b.iter(|| black_box(&a).to_bytes())
+        });
+        group.bench_with_input(BenchmarkId::new("from_bytes", digits), &digits, |b, _| {
+            b.iter(|| Accumulator::from_bytes(black_box(&bytes)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, mul, div, div_prepared, bytes);
+criterion_main!(benches);
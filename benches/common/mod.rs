@@ -0,0 +1,47 @@
+//! Shared helpers for the benchmarks
+//! 
+//! All inputs are generated from fixed seeds so that runs are comparable
+
+#![allow(dead_code)]
+
+use adamas::accum::{Accumulator, Digit};
+
+/// Seed used for every benchmark input
+pub const SEED: u64 = 0x9e3779b97f4a7c15;
+
+/// Simple deterministic pseudo random number generator (xorshift64*)
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+
+    /// Create a new generator from the shared seed
+    pub fn new() -> Self {
+        Self { state: SEED }
+    }
+
+    /// Get the next pseudo random number
+    pub fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    /// Get the next pseudo random number in the range [0, max)
+    pub fn below(&mut self, max: u64) -> u64 {
+        self.next() % max
+    }
+}
+
+/// Create an accumulator with exactly `digits` pseudo random digits
+pub fn random_accumulator(rng: &mut Rng, digits: usize) -> Accumulator {
+    let mut a = Accumulator::new();
+    a.add(Digit::MAX);
+    while a.len() < digits {
+        a.shl(Digit::BITS as usize);
+        a.add(rng.next() as Digit);
+    }
+    a
+}
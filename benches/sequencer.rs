@@ -0,0 +1,179 @@
+//! Benchmarks for compressing and decompressing sequences
+
+mod common;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+use adamas::accum::{Accumulator, PreparedDivisor, SignedDigit};
+use adamas::data::{Bool, CharSet, DataSpec, Enum, IntRange, SequenceLength, Sequencer, WideDataSpec};
+
+use common::{random_accumulator, Rng};
+
+/// Compress values into a new accumulator
+fn compressed<T>(sequencer: &Sequencer<T>, values: &[T]) -> Accumulator {
+    let mut a = Accumulator::new();
+    sequencer.compress(values, &mut a).unwrap();
+    a
+}
+
+/// Benchmark compression and decompression of values as both a fixed and a
+/// variable length sequence
+fn compress_decompress<T>(c: &mut Criterion, name: &str, spec: &dyn WideDataSpec<T>, values: &[T]) {
+    let fixed = Sequencer::new(spec, SequenceLength::Fixed(values.len()));
+    let variable = Sequencer::new(spec, SequenceLength::Variable(values.len()));
+    let mut group = c.benchmark_group(name);
+    for (label, sequencer) in [("fixed", &fixed), ("variable", &variable)] {
+        group.bench_function(format!("compress_{}", label), |b| {
+            b.iter(|| compressed(sequencer, black_box(values)))
+        });
+        let a = compressed(sequencer, values);
+        group.bench_function(format!("decompress_{}", label), |b| {
+            b.iter_batched_ref(
+                || a.clone(),
+                |a| sequencer.decompress(a).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Sequences of booleans (e.g. a set of feature flags)
+fn bool_sequence(c: &mut Criterion) {
+    let mut rng = Rng::new();
+    let values: Vec<bool> = (0..1000).map(|_| rng.below(2) == 1).collect();
+    compress_decompress(c, "bool_1000", &Bool::new(), &values);
+}
+
+/// Long sequences of booleans, shifted into the accumulator a Digit at a time
+/// or multiplied in one value at a time
+fn packed_bool_sequence(c: &mut Criterion) {
+    let mut rng = Rng::new();
+    let length = 10_000;
+    let values: Vec<bool> = (0..length).map(|_| rng.below(2) == 1).collect();
+    let spec = Bool::new();
+    let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(length));
+    let mut group = c.benchmark_group("bool_10k");
+    group.bench_function("compress_packed", |b| {
+        b.iter(|| compressed(&sequencer, black_box(&values)))
+    });
+    group.bench_function("compress_per_value", |b| {
+        b.iter(|| {
+            let mut a = Accumulator::new();
+            sequencer.compress_iter(black_box(&values), &mut a).unwrap();
+            a
+        })
+    });
+    let a = compressed(&sequencer, &values);
+    group.bench_function("decompress_packed", |b| {
+        b.iter_batched_ref(
+            || a.clone(),
+            |a| sequencer.decompress(a).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("decompress_per_value", |b| {
+        b.iter_batched_ref(
+            || a.clone(),
+            |a| sequencer.decompress_iter(a).count(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+/// Sequences of integers (e.g. sensor readings)
+fn int_range_sequence(c: &mut Criterion) {
+    let mut rng = Rng::new();
+    let values: Vec<SignedDigit> = (0..1000).map(|_| rng.below(2001) as SignedDigit - 1000).collect();
+    compress_decompress(c, "int_range_1000", &IntRange::new(-1000, 1000), &values);
+}
+
+/// Sequences of characters (e.g. a short message)
+fn charset_sequence(c: &mut Criterion) {
+    let mut rng = Rng::new();
+    let charset: Vec<char> = "abcdefghijklmnopqrstuvwxyz .!?0123456789".chars().collect();
+    let values: Vec<char> = (0..256)
+        .map(|_| charset[rng.below(charset.len() as u64) as usize])
+        .collect();
+    compress_decompress(c, "charset_256", &CharSet::lowercase_ascii(), &values);
+}
+
+/// Long sequences of a single permutation spec on top of existing data
+fn constant_spec(c: &mut Criterion) {
+    let spec = Enum::new(&["Constant"]);
+    let length = 10_000;
+    let values = vec![String::from("Constant"); length];
+    let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(length));
+    let a = random_accumulator(&mut Rng::new(), 64);
+    let mut group = c.benchmark_group("constant_spec");
+    group.bench_function("compress_10k", |b| {
+        b.iter_batched_ref(
+            || a.clone(),
+            |a| sequencer.compress(black_box(&values), a).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("decompress_10k", |b| {
+        b.iter_batched_ref(
+            || a.clone(),
+            |a| sequencer.decompress(a).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+/// Decoding a long sequence using plain and prepared division
+fn repeated_division(c: &mut Criterion) {
+    let mut rng = Rng::new();
+    let spec = IntRange::new(0, 1000);
+    let length = 10_000;
+    let values: Vec<SignedDigit> = (0..length).map(|_| rng.below(1001) as SignedDigit).collect();
+    let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(length));
+    let a = compressed(&sequencer, &values);
+    let permutations = spec.permutations();
+    let mut group = c.benchmark_group("repeated_division");
+    group.bench_function("div_10k", |b| {
+        b.iter_batched_ref(
+            || a.clone(),
+            |a| {
+                for _ in 0..length {
+                    black_box(a.div(permutations));
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("div_prepared_10k", |b| {
+        b.iter_batched_ref(
+            || a.clone(),
+            |a| {
+                let divisor = PreparedDivisor::new(permutations);
+                for _ in 0..length {
+                    black_box(a.div_prepared(&divisor));
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("decompress_10k", |b| {
+        b.iter_batched_ref(
+            || a.clone(),
+            |a| sequencer.decompress(a).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bool_sequence,
+    packed_bool_sequence,
+    int_range_sequence,
+    charset_sequence,
+    constant_spec,
+    repeated_division,
+);
+criterion_main!(benches);
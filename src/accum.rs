@@ -8,27 +8,86 @@
 //! 
 //! # Examples
 
-use std::vec::Vec;
+use alloc::vec;
+use alloc::vec::Vec;
 
+#[cfg(any(test, feature = "std"))]
+use alloc::format;
 #[cfg(test)]
-use std::string::String;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::error::Error;
 
 // Data types used as big digits
+#[cfg(not(feature = "digit32"))]
 pub type Digit = u64;
+#[cfg(not(feature = "digit32"))]
 pub type DoubleDigit = u128;
+#[cfg(not(feature = "digit32"))]
 pub type SignedDigit = i64;
+#[cfg(not(feature = "digit32"))]
 pub type SignedDoubleDigit = i128;
 
+// Smaller digits for targets without a native 64x64->128 bit multiply
+#[cfg(feature = "digit32")]
+pub type Digit = u32;
+#[cfg(feature = "digit32")]
+pub type DoubleDigit = u64;
+#[cfg(feature = "digit32")]
+pub type SignedDigit = i32;
+#[cfg(feature = "digit32")]
+pub type SignedDoubleDigit = i64;
+
+/// Number of bytes in a Digit
+const DIGIT_BYTES: usize = (Digit::BITS / 8) as usize;
+
 /// Accumulator struct, accumulates the results of chained math operations
+#[derive(Clone, Debug)]
 pub struct Accumulator {
     data: Vec<Digit>,
+    guarded: bool,
 }
 
 impl Accumulator {
 
     /// Create a new Accumulator struct
     pub fn new() -> Self {
-        Self {data: Vec::new()}
+        Self {data: Vec::new(), guarded: false}
+    }
+
+    /// Create a new guarded Accumulator, which starts with the value one 
+    /// instead of zero.
+    /// 
+    /// Compressed values are placed below the guard, so the accumulator 
+    /// holding only the guard means all of the data has been read. This lets 
+    /// `is_exhausted` tell the difference between no data and data which 
+    /// happens to be zero.
+    pub fn new_guarded() -> Self {
+        Self {data: vec![1], guarded: true}
+    }
+
+    /// Check if the accumulator was created with a guard
+    pub fn is_guarded(&self) -> bool {
+        self.guarded
+    }
+
+    /// Check if there is no data left in the accumulator. 
+    /// 
+    /// Without a guard this is the same as `is_empty`, which can not detect 
+    /// reading past the end of the data, since a value of zero is valid data.
+    pub fn is_exhausted(&self) -> bool {
+        self.data.is_empty() || (self.guarded && self.data == [1])
+    }
+
+    /// Check that all of the data in the accumulator has been read
+    pub fn assert_empty(&self) -> Result<(), Error> {
+        if self.is_exhausted() {
+            Ok(())
+        } else {
+            Err(Error::TrailingData { len: self.len() })
+        }
     }
 
     /// Get the length of the internal Digit array used to store the accumulated
@@ -37,6 +96,11 @@ impl Accumulator {
         self.data.len()
     }
 
+    /// Check if the accumulator holds the value zero (no digits)
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
     /// Add a value with a certain digit offset in the accumulator.
     /// 
     /// Example using the base 10 equivalent:
@@ -65,6 +129,7 @@ impl Accumulator {
                 vec_index += 1;
             }
         }
+        debug_assert!(self.is_normalized());
     }
 
     /// Add a value to the accumulator
@@ -74,26 +139,48 @@ impl Accumulator {
 
     /// Multiply the accumulator by a value
     pub fn mul(&mut self, value: Digit) {
+        self.checked_mul(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Multiply the accumulator by a value, or return an error if the value 
+    /// is zero
+    pub fn checked_mul(&mut self, value: Digit) -> Result<(), Error> {
         // Multiplication by zero is not allowed, multiplication by 1 does nothing
         if value < 1 {
-            panic!("Cannot multiply accumulator by zero!");
+            return Err(Error::MultiplyByZero);
         } else if value == 1 {
-            return;
+            return Ok(());
         }
-        // Multiply digit by digit starting with the most significant
-        for ii in (0..self.len()).rev() {
-            let result: DoubleDigit = (self.data[ii] as DoubleDigit) * (value as DoubleDigit);
+        // Multiply digit by digit starting with the least significant, 
+        // carrying the most significant half of each result to the next digit
+        let value = value as DoubleDigit;
+        let mut carry: Digit = 0;
+        for digit in self.data.iter_mut() {
+            let result: DoubleDigit = (*digit as DoubleDigit) * value + carry as DoubleDigit;
             let [lsb, msb]: [Digit; 2] = chop_digits(result);
-            self.add_at_place(msb, ii+1);
-            self.data[ii] = lsb;
+            *digit = lsb;
+            carry = msb;
         }
+        if carry > 0 {
+            self.data.push(carry);
+        }
+        debug_assert!(self.is_normalized());
+        Ok(())
     }
 
     /// Divide the accumulator by a value and return the remainder
     pub fn div(&mut self, value: Digit) -> Digit {
-        // Check for divide by zero and panic
+        self.checked_div(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Divide the accumulator by a value and return the remainder, or return 
+    /// an error if the value is zero
+    pub fn checked_div(&mut self, value: Digit) -> Result<Digit, Error> {
         if value == 0 {
-            panic!("Cannot divide by zero!");
+            return Err(Error::DivideByZero);
+        } else if value == 1 {
+            // Division by 1 does nothing and always has no remainder
+            return Ok(0);
         }
         let den: DoubleDigit = value as DoubleDigit;
         let mut rem: [Digit; 2] = [0; 2];
@@ -104,20 +191,52 @@ impl Accumulator {
             rem = chop_digits(num % den);
             self.data[ii] = result[0];
         }
-        // Remove most significant digit if it is 0
-        if let Some(msd) = self.data.last() {
-            if *msd == 0 {
-                self.data.pop();
+        self.normalize();
+        debug_assert!(self.is_normalized());
+        Ok(rem[0])
+    }
+
+    /// Divide the accumulator by a prepared divisor and return the remainder
+    /// 
+    /// Gives exactly the same results as `div`, but replaces the hardware 
+    /// division of each digit with multiplications by a precomputed 
+    /// reciprocal. Useful when dividing by the same value many times.
+    pub fn div_prepared(&mut self, divisor: &PreparedDivisor) -> Digit {
+        if divisor.value == 1 {
+            return 0;
+        }
+        // Divide (accumulator << shift) by (divisor << shift), the quotient is
+        // the same and the remainder needs to be shifted back at the end
+        let shift = divisor.shift;
+        let mut rem: Digit = match (shift, self.data.last()) {
+            (0, _) | (_, None) => 0,
+            (_, Some(msd)) => *msd >> (Digit::BITS - shift),
+        };
+        for ii in (0..self.len()).rev() {
+            let mut num = self.data[ii] << shift;
+            if shift > 0 && ii > 0 {
+                num |= self.data[ii-1] >> (Digit::BITS - shift);
             }
+            let (quotient, remainder) = divisor.div_digits([num, rem]);
+            self.data[ii] = quotient;
+            rem = remainder;
         }
-        rem[0]
+        self.normalize();
+        debug_assert!(self.is_normalized());
+        rem >> shift
     }
 
     /// Shift the accumulator to the left (multiply by a power of 2)
     pub fn shl(&mut self, shift: usize) {
+        self.checked_shl(shift).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Shift the accumulator to the left, or return an error if the shift is 
+    /// more than the number of bits in a Digit
+    pub fn checked_shl(&mut self, shift: usize) -> Result<(), Error> {
         // error if trying to shift more than the number of bits in Digit
         if shift > Digit::BITS as usize {
-            panic!("Can not apply shift to accumulator greater than 64");
+            return Err(Error::ShiftTooLarge { shift });
         }
         let mut carry: Digit = 0;
         // loop through digits and apply the shift
@@ -130,13 +249,35 @@ impl Accumulator {
         if carry > 0 {
             self.data.push(carry);
         }
+        debug_assert!(self.is_normalized());
+        Ok(())
+    }
+
+    /// Remove all of the most significant digits that are 0
+    fn normalize(&mut self) {
+        while let Some(0) = self.data.last() {
+            self.data.pop();
+        }
+    }
+
+    /// Check that the most significant digit (if any) is not 0
+    fn is_normalized(&self) -> bool {
+        !matches!(self.data.last(), Some(0))
     }
 
     /// Shift the accumulator to the right (divide by a factor of 2)
     pub fn shr(&mut self, shift: usize) -> Digit {
+        self.checked_shr(shift).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Shift the accumulator to the right and return the bits shifted out, or
+    /// return an error if the shift is more than the number of bits in a Digit
+    pub fn checked_shr(&mut self, shift: usize) -> Result<Digit, Error> {
         // error if trying to shift more than the number of bits in Digit
         if shift > Digit::BITS as usize {
-            panic!("Can not apply shift to accumulator greater than 64");
+            return Err(Error::ShiftTooLarge { shift });
+        } else if shift == 0 {
+            return Ok(0);
         }
         // loop through digits and apply the shift
         let mut carry: Digit = 0;
@@ -149,29 +290,187 @@ impl Accumulator {
             carry = lsb;
             self.data[ii] = msb;
         } 
-        // Remove most significant digit if it is 0
-        if let Some(msd) = self.data.last() {
-            if *msd == 0 {
-                self.data.pop();
+        self.normalize();
+        debug_assert!(self.is_normalized());
+        carry >>= Digit::BITS - shift as u32;
+        Ok(carry)
+    }
+
+    /// Get the contents of the accumulator as little endian bytes
+    /// 
+    /// Zero bytes at the end (most significant) are not included, so the 
+    /// output is the same for any Digit size
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(self.len() * DIGIT_BYTES);
+        for digit in self.data.iter() {
+            for ii in 0..DIGIT_BYTES {
+                bytes.push((digit >> (8 * ii)) as u8);
             }
         }
-        carry >>= Digit::BITS - shift as u32;
-        carry
+        while let Some(0) = bytes.last() {
+            bytes.pop();
+        }
+        bytes
+    }
+
+    /// Create an accumulator from little endian bytes (see `to_bytes`)
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut data: Vec<Digit> = Vec::with_capacity(bytes.len().div_ceil(DIGIT_BYTES));
+        for chunk in bytes.chunks(DIGIT_BYTES) {
+            let mut digit: Digit = 0;
+            for (ii, byte) in chunk.iter().enumerate() {
+                digit |= (*byte as Digit) << (8 * ii);
+            }
+            data.push(digit);
+        }
+        let mut accum = Self { data, guarded: false };
+        accum.normalize();
+        accum
+    }
+
+    /// Create a guarded accumulator (see `new_guarded`) from little endian 
+    /// bytes, the guard must already be part of the bytes
+    pub fn from_bytes_guarded(bytes: &[u8]) -> Self {
+        let mut accum = Self::from_bytes(bytes);
+        accum.guarded = true;
+        accum
+    }
+
+    /// Write the accumulator to a stream, returning the number of bytes 
+    /// written
+    /// 
+    /// The bytes from `to_bytes` are written after a prefix containing their 
+    /// length as an unsigned LEB128 variable length integer.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        let bytes = self.to_bytes();
+        let mut prefix: Vec<u8> = Vec::with_capacity(10);
+        let mut length = bytes.len();
+        loop {
+            let byte = (length & 0x7f) as u8;
+            length >>= 7;
+            if length == 0 {
+                prefix.push(byte);
+                break;
+            }
+            prefix.push(byte | 0x80);
+        }
+        w.write_all(&prefix)?;
+        w.write_all(&bytes)?;
+        Ok(prefix.len() + bytes.len())
+    }
+
+    /// Read an accumulator written by `write_to` from a stream
+    /// 
+    /// Fails if the length prefix is larger than `max_bytes`, so corrupt input
+    /// can not cause an unbounded allocation
+    #[cfg(feature = "std")]
+    pub fn read_from<R: io::Read>(r: &mut R, max_bytes: usize) -> io::Result<Self> {
+        let mut length: usize = 0;
+        let mut shift: u32 = 0;
+        loop {
+            let mut byte = [0u8; 1];
+            r.read_exact(&mut byte)?;
+            let bits = (byte[0] & 0x7f) as usize;
+            if shift >= usize::BITS || (bits << shift) >> shift != bits {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Accumulator length prefix is too large"));
+            }
+            length |= bits << shift;
+            shift += 7;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        if length > max_bytes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Accumulator length of {} bytes is more than the maximum of {}", length, max_bytes),
+            ));
+        }
+        let mut bytes: Vec<u8> = vec![0; length];
+        r.read_exact(&mut bytes)?;
+        Ok(Self::from_bytes(&bytes))
     }
 
     /// Retrieve the contents of the accumulator as a hex string
     #[cfg(test)]
-    fn to_hex_str(&self) -> String {
+    pub(crate) fn to_hex_str(&self) -> String {
         let mut s: String = String::from("");
         for digit in self.data.iter().rev() {
-            // formatting of this string is hardcoded to match the "Digit" type
-            s.push_str(format!("{:016x} ", digit).as_str());
+            // one hex character for every 4 bits of the "Digit" type
+            let width = (Digit::BITS / 4) as usize;
+            s.push_str(format!("{:0width$x} ", digit, width = width).as_str());
         }
         s.pop();
         s
     }
 }
 
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Divisor with a precomputed reciprocal for fast repeated division
+/// 
+/// Uses the method from Möller & Granlund, "Improved division by invariant 
+/// integers" (2011). The divisor is shifted so that its most significant bit 
+/// is set, and the reciprocal is floor((B^2 - 1) / divisor) - B where B is the
+/// Digit base.
+pub struct PreparedDivisor {
+    value: Digit,
+    normalized: Digit,
+    reciprocal: Digit,
+    shift: u32,
+}
+
+impl PreparedDivisor {
+
+    /// Create a new PreparedDivisor for a value
+    pub fn new(value: Digit) -> Self {
+        Self::try_new(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new PreparedDivisor for a value, or return an error if the 
+    /// value is zero
+    pub fn try_new(value: Digit) -> Result<Self, Error> {
+        if value == 0 {
+            return Err(Error::DivideByZero);
+        }
+        let shift = value.leading_zeros();
+        let normalized = value << shift;
+        let base: DoubleDigit = 1 << Digit::BITS;
+        let reciprocal = (DoubleDigit::MAX / normalized as DoubleDigit - base) as Digit;
+        Ok(Self { value, normalized, reciprocal, shift })
+    }
+
+    /// Get the value of the divisor
+    pub fn value(&self) -> Digit {
+        self.value
+    }
+
+    /// Divide a two digit number by the normalized divisor, returning the 
+    /// quotient and the remainder. The most significant digit must be less 
+    /// than the normalized divisor.
+    fn div_digits(&self, num: [Digit; 2]) -> (Digit, Digit) {
+        let d = self.normalized;
+        let product = (self.reciprocal as DoubleDigit) * (num[1] as DoubleDigit);
+        let [q0, q1] = chop_digits(product.wrapping_add(fuse_digits(num)));
+        let mut q1 = q1.wrapping_add(1);
+        let mut r = num[0].wrapping_sub(q1.wrapping_mul(d));
+        if r > q0 {
+            q1 = q1.wrapping_sub(1);
+            r = r.wrapping_add(d);
+        }
+        if r >= d {
+            q1 += 1;
+            r -= d;
+        }
+        (q1, r)
+    }
+}
+
 /// Combine two Digits into a DoubleDigit
 fn fuse_digits(digits: [Digit; 2]) -> DoubleDigit {
     // Note:
@@ -193,10 +492,33 @@ fn chop_digits(digits: DoubleDigit) -> [Digit; 2] {
 #[cfg(test)]
 mod tests {
 
+    use alloc::vec;
+    use alloc::vec::Vec;
+
     use crate::accum::Accumulator;
     use crate::accum::Digit;
+    use crate::accum::PreparedDivisor;
+    use crate::accum::{DoubleDigit, chop_digits};
+    use crate::error::Error;
+
+    /// Simple deterministic pseudo random number generator (xorshift64*)
+    fn random(state: &mut u64) -> Digit {
+        *state ^= *state >> 12;
+        *state ^= *state << 25;
+        *state ^= *state >> 27;
+        state.wrapping_mul(0x2545F4914F6CDD1D) as Digit
+    }
+
+    /// Create an accumulator with random contents and up to `digits` digits
+    fn random_accumulator(state: &mut u64, digits: usize) -> Accumulator {
+        let mut a = Accumulator::new();
+        a.data = (0..digits).map(|_| random(state)).collect();
+        a.normalize();
+        a
+    }
 
     #[test]
+    #[cfg(not(feature = "digit32"))]
     fn add_at_place() {
         // test some basic addition
         let mut a = Accumulator::new();
@@ -221,6 +543,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "digit32"))]
     fn add() {
         // test very basic addition and vector grows
         let mut a = Accumulator::new();
@@ -234,6 +557,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "digit32"))]
     fn mul() {
         let mut a = Accumulator::new();
         a.add_at_place(0xF000000000000000, 0);
@@ -249,7 +573,38 @@ mod tests {
         assert_eq!(a.to_hex_str(), "fffffffffffffffe ffffffffffffffff ffffffffffffffff 0000000000000001");
     }
 
+    /// Reference multiplication that carries through add_at_place
+    fn mul_reference(a: &mut Accumulator, value: Digit) {
+        for ii in (0..a.len()).rev() {
+            let result = (a.data[ii] as DoubleDigit) * (value as DoubleDigit);
+            let [lsb, msb]: [Digit; 2] = chop_digits(result);
+            a.add_at_place(msb, ii+1);
+            a.data[ii] = lsb;
+        }
+    }
+
+    #[test]
+    fn mul_differential() {
+        let mut state: u64 = 0x2f6b3a8e9c1d4057;
+        for ii in 0..5000 {
+            let digits = (random(&mut state) % 20) as usize;
+            let mut seed = state;
+            let mut a = random_accumulator(&mut state, digits);
+            let mut b = random_accumulator(&mut seed, digits);
+            // Mix in factors with some small and some large values
+            let shift = random(&mut state) % Digit::BITS as Digit;
+            let mut value = (random(&mut state) >> shift).max(2);
+            if ii % 10 == 0 {
+                value = Digit::MAX;
+            }
+            a.mul(value);
+            mul_reference(&mut b, value);
+            assert_eq!(a.to_hex_str(), b.to_hex_str());
+        }
+    }
+
     #[test]
+    #[cfg(not(feature = "digit32"))]
     fn div() {
         let mut a = Accumulator::new();
         a.add(0xff);
@@ -260,13 +615,13 @@ mod tests {
         let mut a = Accumulator::new();
         let encode: [Digit; 4] = [Digit::MAX-10000, Digit::MAX-1000, Digit::MAX-100, Digit::MAX-10];
         let mut decode: [Digit; 4] = [0; 4];
-        for ii in 0..encode.len() {
-            a.mul(encode[ii]+1);
-            a.add(encode[ii]);
+        for value in encode.iter() {
+            a.mul(value+1);
+            a.add(*value);
         }
         // test divide by 1
         let ahex = a.to_hex_str();
-        a.div(1);
+        assert_eq!(a.div(1), 0);
         assert_eq!(ahex, a.to_hex_str());
         for ii in (0..4).rev() {
             decode[ii] = a.div(encode[ii]+1);
@@ -275,6 +630,133 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "digit32"))]
+    fn div_normalizes() {
+        // Dividing must strip every leading zero digit, not just one
+        let mut a = Accumulator::new();
+        a.add_at_place(1, 3);
+        assert_eq!(a.len(), 4);
+        a.div(Digit::MAX);
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.to_hex_str(), "0000000000000001 0000000000000001 0000000000000001");
+        a.div(Digit::MAX);
+        assert_eq!(a.len(), 2);
+        assert_eq!(a.to_hex_str(), "0000000000000001 0000000000000002");
+    }
+
+    #[test]
+    fn guarded() {
+        let mut a = Accumulator::new_guarded();
+        assert!(a.is_guarded());
+        assert!(a.is_exhausted());
+        assert_eq!(a.assert_empty(), Ok(()));
+        a.mul(10);
+        assert!(!a.is_exhausted());
+        assert_eq!(a.assert_empty(), Err(Error::TrailingData { len: 1 }));
+        assert_eq!(a.div(10), 0);
+        assert!(a.is_exhausted());
+        // Unguarded accumulators are only exhausted when empty
+        let mut a = Accumulator::new();
+        assert!(!a.is_guarded());
+        assert!(a.is_exhausted());
+        a.add(1);
+        assert!(!a.is_exhausted());
+        let a = Accumulator::from_bytes_guarded(&Accumulator::new_guarded().to_bytes());
+        assert!(a.is_guarded());
+        assert!(a.is_exhausted());
+    }
+
+    #[test]
+    fn checked() {
+        let mut a = Accumulator::new();
+        a.add(12345);
+        let before = a.to_hex_str();
+        assert_eq!(a.checked_mul(0), Err(Error::MultiplyByZero));
+        assert_eq!(a.checked_div(0), Err(Error::DivideByZero));
+        assert_eq!(a.checked_shl(Digit::BITS as usize + 1), Err(Error::ShiftTooLarge { shift: Digit::BITS as usize + 1 }));
+        assert_eq!(a.checked_shr(usize::MAX), Err(Error::ShiftTooLarge { shift: usize::MAX }));
+        assert!(matches!(PreparedDivisor::try_new(0), Err(Error::DivideByZero)));
+        // Nothing changes on error
+        assert_eq!(a.to_hex_str(), before);
+        // Same results as the unchecked versions on valid input
+        let mut state = 0x2545F4914F6CDD1D;
+        for _ in 0..100 {
+            let digits = (random(&mut state) % 8) as usize;
+            let mut a = random_accumulator(&mut state, digits);
+            let mut b = a.clone();
+            let value = random(&mut state) | 1;
+            let shift = (random(&mut state) % (Digit::BITS + 1) as Digit) as usize;
+            a.mul(value);
+            b.checked_mul(value).unwrap();
+            assert_eq!(a.to_hex_str(), b.to_hex_str());
+            assert_eq!(a.div(value), b.checked_div(value).unwrap());
+            assert_eq!(a.to_hex_str(), b.to_hex_str());
+            a.shl(shift);
+            b.checked_shl(shift).unwrap();
+            assert_eq!(a.to_hex_str(), b.to_hex_str());
+            assert_eq!(a.shr(shift), b.checked_shr(shift).unwrap());
+            assert_eq!(a.to_hex_str(), b.to_hex_str());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Can not apply shift to accumulator greater than")]
+    fn shl_panics() {
+        Accumulator::new().shl(Digit::BITS as usize + 1);
+    }
+
+    #[test]
+    fn shift_zero() {
+        let mut a = Accumulator::new();
+        a.add(0xabc);
+        a.shl(0);
+        assert_eq!(a.shr(0), 0);
+        assert_eq!(a.div(0x1000), 0xabc);
+    }
+
+    #[test]
+    fn normalize() {
+        let mut a = Accumulator::new();
+        a.data = vec![5, 0, 0, 0];
+        assert!(!a.is_normalized());
+        a.normalize();
+        assert!(a.is_normalized());
+        assert_eq!(a.len(), 1);
+    }
+
+    #[test]
+    fn div_prepared() {
+        let mut a = Accumulator::new();
+        a.add(0xff);
+        let d = PreparedDivisor::new(2);
+        assert_eq!(d.value(), 2);
+        assert_eq!(a.div_prepared(&d), 1);
+        let mut b = Accumulator::new();
+        b.add(0x7f);
+        assert_eq!(a.to_hex_str(), b.to_hex_str());
+        // Differential test against plain division
+        let mut state: u64 = 0x853c49e6748fea9b;
+        let mut divisors: Vec<Digit> = vec![1, 2, 3, 10, 1 << (Digit::BITS / 2), Digit::MAX - 1, Digit::MAX];
+        for _ in 0..200 {
+            let shift = random(&mut state) % Digit::BITS as Digit;
+            divisors.push((random(&mut state) >> shift).max(1));
+        }
+        for divisor in divisors {
+            let prepared = PreparedDivisor::new(divisor);
+            for digits in [0, 1, 2, 5, 17] {
+                let mut seed = state;
+                let mut a = random_accumulator(&mut state, digits);
+                let mut b = random_accumulator(&mut seed, digits);
+                for _ in 0..20 {
+                    assert_eq!(a.div_prepared(&prepared), b.div(divisor));
+                    assert_eq!(a.to_hex_str(), b.to_hex_str());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "digit32"))]
     fn all_three() {
         let mut a = Accumulator::new();
         a.add(Digit::MAX);
@@ -284,6 +766,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "digit32"))]
     fn shift() {
         let mut a = Accumulator::new();
         a.add(0xa00000000000000b);
@@ -300,13 +783,150 @@ mod tests {
         let mut a = Accumulator::new();
         let encode: [Digit; 4] = [10, 20, 50, 250];
         let mut decode: [Digit; 4] = [0; 4];
-        for ii in 0..encode.len() {
+        for value in encode.iter() {
             a.shl(8);
-            a.add(encode[ii]);
+            a.add(*value);
         }
         for ii in (0..4).rev() {
             decode[ii] = a.shr(8);
         }
         assert_eq!(encode, decode);
     }
+
+    #[test]
+    fn bytes() {
+        let mut a = Accumulator::new();
+        assert_eq!(a.to_bytes(), Vec::<u8>::new());
+        a.add(0x0102);
+        assert_eq!(a.to_bytes(), vec![0x02, 0x01]);
+        a.shl(Digit::BITS as usize);
+        a.add(0xff);
+        let mut expected = vec![0u8; Digit::BITS as usize / 8];
+        expected[0] = 0xff;
+        expected.extend([0x02, 0x01]);
+        assert_eq!(a.to_bytes(), expected);
+        // Zeros at the end are ignored
+        let b = Accumulator::from_bytes(&[0x02, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(b.len(), 1);
+        assert_eq!(b.to_bytes(), vec![0x02, 0x01]);
+        // Round trip random accumulators
+        let mut state: u64 = 0x6a09e667f3bcc908;
+        for digits in 0..20 {
+            let a = random_accumulator(&mut state, digits);
+            let b = Accumulator::from_bytes(&a.to_bytes());
+            assert_eq!(a.to_hex_str(), b.to_hex_str());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stream() {
+        use std::io::{Cursor, ErrorKind};
+        // Round trip several accumulators through the same stream
+        let mut state: u64 = 0xbb67ae8584caa73b;
+        let accums: Vec<Accumulator> = [0, 1, 3, 40]
+            .iter()
+            .map(|digits| random_accumulator(&mut state, *digits))
+            .collect();
+        let mut cursor = Cursor::new(Vec::new());
+        let mut written = 0;
+        for a in accums.iter() {
+            written += a.write_to(&mut cursor).unwrap();
+        }
+        let bytes = cursor.into_inner();
+        assert_eq!(written, bytes.len());
+        let mut cursor = Cursor::new(bytes.clone());
+        for a in accums.iter() {
+            let b = Accumulator::read_from(&mut cursor, 1000).unwrap();
+            assert_eq!(a.to_hex_str(), b.to_hex_str());
+        }
+        // Length prefix is a variable length integer
+        let mut a = Accumulator::new();
+        a.add(0xaabb);
+        let mut out = Vec::new();
+        assert_eq!(a.write_to(&mut out).unwrap(), 3);
+        assert_eq!(out, vec![0x02, 0xbb, 0xaa]);
+        let long = Accumulator::from_bytes(&[0xff; 300]);
+        let mut out = Vec::new();
+        long.write_to(&mut out).unwrap();
+        assert_eq!(&out[..2], &[0xac, 0x02]);
+        // Truncated inputs
+        let err = Accumulator::read_from(&mut Cursor::new(Vec::new()), 1000).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = Accumulator::read_from(&mut Cursor::new(vec![0x80]), 1000).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let mut cursor = Cursor::new(&bytes[..bytes.len() - 1]);
+        for _ in 0..3 {
+            Accumulator::read_from(&mut cursor, 1000).unwrap();
+        }
+        let err = Accumulator::read_from(&mut cursor, 1000).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        // Length larger than the allowed maximum
+        let err = Accumulator::read_from(&mut Cursor::new(&out), 299).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(Accumulator::read_from(&mut Cursor::new(&out), 300).is_ok());
+        let err = Accumulator::read_from(&mut Cursor::new(vec![0xff; 20]), usize::MAX).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    #[cfg(feature = "digit32")]
+    fn add_at_place_digit32() {
+        let mut a = Accumulator::new();
+        a.add_at_place(3, 2);
+        a.add_at_place(2, 1);
+        a.add_at_place(1, 0);
+        assert_eq!(a.to_hex_str(), "00000003 00000002 00000001");
+        a.add_at_place(Digit::MAX, 1);
+        assert_eq!(a.to_hex_str(), "00000004 00000001 00000001");
+        let mut a = Accumulator::new();
+        a.add_at_place(Digit::MAX, 0);
+        a.add_at_place(Digit::MAX, 1);
+        a.add(1);
+        assert_eq!(a.to_hex_str(), "00000001 00000000 00000000");
+    }
+
+    #[test]
+    #[cfg(feature = "digit32")]
+    fn mul_digit32() {
+        let mut a = Accumulator::new();
+        a.add_at_place(0xF0000000, 0);
+        a.add_at_place(0xF0000000, 1);
+        a.mul(2);
+        assert_eq!(a.to_hex_str(), "00000001 e0000001 e0000000");
+        let mut a = Accumulator::new();
+        a.add(Digit::MAX);
+        a.mul(Digit::MAX);
+        assert_eq!(a.to_hex_str(), "fffffffe 00000001");
+        a.div(Digit::MAX);
+        assert_eq!(a.to_hex_str(), "ffffffff");
+    }
+
+    #[test]
+    #[cfg(feature = "digit32")]
+    fn div_normalizes_digit32() {
+        let mut a = Accumulator::new();
+        a.add_at_place(1, 3);
+        assert_eq!(a.len(), 4);
+        a.div(Digit::MAX);
+        assert_eq!(a.to_hex_str(), "00000001 00000001 00000001");
+        a.div(Digit::MAX);
+        assert_eq!(a.to_hex_str(), "00000001 00000002");
+    }
+
+    #[test]
+    #[cfg(feature = "digit32")]
+    fn shift_digit32() {
+        let mut a = Accumulator::new();
+        a.add(0xa000000b);
+        a.shl(4);
+        assert_eq!(a.to_hex_str(), "0000000a 000000b0");
+        a.shl(32);
+        assert_eq!(a.to_hex_str(), "0000000a 000000b0 00000000");
+        a.shr(28);
+        assert_eq!(a.to_hex_str(), "000000a0 00000b00");
+        let rem = a.shr(12);
+        assert_eq!(a.to_hex_str(), "0a000000");
+        assert_eq!(rem, 0xb00);
+    }
 }
@@ -1,393 +1,2599 @@
 //! Data / Datum definitions
 
-use std::result::Result;
-use std::collections::HashMap;
+use core::borrow::Borrow;
+use core::fmt;
+use core::result::Result;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
-use crate::accum::{Digit, SignedDigit, SignedDoubleDigit};
-use crate::accum::Accumulator;
+use crate::accum::{Digit, DoubleDigit, SignedDigit, SignedDoubleDigit};
+use crate::accum::{Accumulator, PreparedDivisor};
+use crate::error::Error;
+
+mod color;
+mod combinator;
+mod combinatorics;
+mod geo;
+mod locale;
+mod net;
+mod numeric;
+mod schema;
+mod text;
+mod time;
+
+pub use color::ColorSpec;
+pub use combinator::{DefaultShortcut, Either, EitherSpec, MappedSpec, OptionSpec, Tuple2Spec, Tuple3Spec};
+pub use combinator::ValidatedSpec;
+pub use combinatorics::{MultisetSpec, PermutationSpec, SetSize, SortedSetSpec};
+pub use geo::{AngleSpec, GeohashSpec, GridPositionSpec, LatLonSpec};
+pub use locale::{CountryCodeSpec, CurrencyAmountSpec, CurrencyCodeSpec, LanguageTagSpec, PhoneNumberSpec};
+pub use locale::PostalCodeSpec;
+pub use net::{Ipv4Spec, Ipv6Spec, MacAddressSpec, SemverSpec, UuidSpec};
+pub use schema::{field_error, Field, Fields, PushField, Schema};
+pub use numeric::{BiasedUInt, DecimalRange, LogScaleRange, Percent, RationalSpec, UnitInterval, VarUInt};
+pub use text::{BigramCharSet, CaseMaskString, EscapingCharSet, StringSpec, TextSequencer, UnicodeScalar};
+pub use text::{DictionaryOrSpelled, DictionaryWordSpec, EnumWithFallback, HexStringSpec, PatternStringSpec};
+pub use text::{LuhnDigitsSpec, RadixStringSpec, WeightedCharSet};
+pub use time::{DateSpec, DateTimeSpec, DurationSpec, IsoWeekSpec, MonotonicDeltaSpec, Month, OrdinalDateSpec};
+pub use time::{Resolution, TimeSpec, Weekday};
 
 /// SequenceLength: indicate a fixed length or a variable length with a maximum
+///
+/// A Variable length marks the end of the sequence with one more permutation
+/// for every element, a Prefixed length stores the number of elements once
+/// instead, which costs less for long sequences of elements with few
+/// permutations.
+#[derive(Clone, Copy)]
 pub enum SequenceLength {
     Fixed(usize),    // Parameter indicates total size
     Variable(usize), // Parameter indicates maximum size
+    Prefixed(usize), // Parameter indicates maximum size
 }
 
 /// Sequence type which defines a sequence of Datum which it knows how to 
 /// compress into an accumulator
+///
+/// The spec is either borrowed, or owned so the Sequencer can be returned
+/// from a function or stored next to other specs (`Sequencer<'static, T>` for
+/// a spec that does not borrow anything).
+///
+/// Either way the spec is used as a `dyn WideDataSpec<T>`, so every element
+/// is compressed with a dynamic call. An owned spec is also one allocation
+/// when the Sequencer is created.
 pub struct Sequencer<'a, T> {
-    spec: &'a dyn DataSpec<T>,
+    spec: SequencerSpec<'a, T>,
     length: SequenceLength,
+    padding: Option<Padding<T>>,
+}
+
+/// Value that fills a fixed length Sequencer after shorter sequences
+struct Padding<T> {
+    fill: T,
+    /// Comparison with the fill, if trailing fill values are removed when
+    /// decompressing
+    strip: Option<FillEq<T>>,
+}
+
+/// Comparison of a value with the fill value of a Sequencer
+type FillEq<T> = fn(&T, &T) -> bool;
+
+/// Spec of the elements of a Sequencer
+enum SequencerSpec<'a, T> {
+    Borrowed(&'a dyn WideDataSpec<T>),
+    Owned(Box<dyn WideDataSpec<T> + 'a>),
 }
 
 impl<'a, T> Sequencer<'a, T> {
 
-    pub fn new(spec: &'a dyn DataSpec<T>, length: SequenceLength) -> Self {
-        Self { spec, length }
+    pub fn new(spec: &'a dyn WideDataSpec<T>, length: SequenceLength) -> Self {
+        Self::borrowed(spec, length)
+    }
+
+    /// Create a new Sequencer that borrows its spec
+    pub fn borrowed(spec: &'a dyn WideDataSpec<T>, length: SequenceLength) -> Self {
+        Self { spec: SequencerSpec::Borrowed(spec), length, padding: None }
+    }
+
+    /// Create a new Sequencer that owns its spec, boxed the same as `boxed`
+    pub fn owned(spec: impl WideDataSpec<T> + 'a, length: SequenceLength) -> Self {
+        Self::boxed(Box::new(spec), length)
+    }
+
+    /// Create a new Sequencer that owns a boxed spec, for specs only known at
+    /// runtime
+    pub fn boxed(spec: Box<dyn WideDataSpec<T> + 'a>, length: SequenceLength) -> Self {
+        Self { spec: SequencerSpec::Owned(spec), length, padding: None }
+    }
+
+    /// Compress sequences shorter than a fixed length followed by the fill
+    /// value, sequences longer than the length are still an error. The fill
+    /// values are decompressed as they are unless `with_stripping` is set.
+    /// Variable and prefixed lengths are not padded
+    pub fn with_padding(mut self, fill: T) -> Self {
+        self.padding = Some(Padding { fill, strip: None });
+        self
+    }
+
+    /// Remove the fill values from the end of decompressed sequences, so
+    /// sequences that do not end with the fill value decompress as they were
+    /// compressed. Has no effect without `with_padding`
+    pub fn with_stripping(mut self) -> Self where T: PartialEq {
+        if let Some(padding) = &mut self.padding {
+            padding.strip = Some(T::eq);
+        }
+        self
+    }
+
+    /// Fill value of the sequence to strip from the end when decompressing
+    fn strip(&self) -> Option<(&T, FillEq<T>)> {
+        match (self.length, &self.padding) {
+            (SequenceLength::Fixed(_), Some(Padding { fill, strip: Some(eq) })) => Some((fill, *eq)),
+            _ => None,
+        }
+    }
+
+    fn spec(&self) -> &dyn WideDataSpec<T> {
+        match &self.spec {
+            SequencerSpec::Borrowed(spec) => *spec,
+            SequencerSpec::Owned(spec) => spec.as_ref(),
+        }
+    }
+
+    /// Compress values into the accumulator. If any value can not be 
+    /// compressed the accumulator is left unchanged and the error reports the
+    /// index of the offending element
+    pub fn compress(&self, values: &[T], accum: &mut Accumulator) -> Result<(), Error> {
+        if let (SequenceLength::Fixed(length), Some(_)) = (self.length, &self.padding) {
+            if values.len() < length {
+                return self.compress_iter(values, accum).map(|_| ());
+            }
+        }
+        let snapshot = accum.clone();
+        let result = match (self.length, self.spec().single()) {
+            (SequenceLength::Fixed(length), Some(spec)) => compress_fixed(spec, values, accum, length),
+            (SequenceLength::Variable(length), Some(spec)) => compress_variable(spec, values, accum, length),
+            (SequenceLength::Fixed(length), None) => self.compress_fixed_wide(values, accum, length),
+            (SequenceLength::Variable(length), None) => self.compress_variable_wide(values, accum, length),
+            (SequenceLength::Prefixed(length), _) => self.compress_prefixed(values, accum, length),
+        };
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    /// Compress the values of an iterator (of values or references) into the
+    /// accumulator and return how many there were, the same as compressing
+    /// them from a slice (including padding). Takes at most one value more
+    /// than the length, if there are too many or too few values the
+    /// accumulator is left unchanged
+    pub fn compress_iter<I>(&self, values: I, accum: &mut Accumulator) -> Result<usize, Error>
+    where I: IntoIterator, I::Item: Borrow<T> {
+        let snapshot = accum.clone();
+        let result = self.compress_iter_unchecked(values, accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
     }
 
-    pub fn compress(&self, values: &[T], accum: &mut Accumulator) {
+    fn compress_iter_unchecked<I>(&self, values: I, accum: &mut Accumulator) -> Result<usize, Error>
+    where I: IntoIterator, I::Item: Borrow<T> {
+        let (length, variable) = match self.length {
+            SequenceLength::Fixed(length) | SequenceLength::Prefixed(length) => (length, false),
+            SequenceLength::Variable(length) => (length, true),
+        };
+        let single = self.spec().single();
+        if variable {
+            // Zero to indicate end of sequence
+            match single {
+                Some(spec) => accum.checked_mul(variable_permutations(spec)?)?,
+                None => accum.checked_mul(2)?,
+            }
+        }
+        let mut count = 0;
+        for value in values {
+            if count == length {
+                return Err(match self.length {
+                    SequenceLength::Fixed(_) => Error::LengthMismatch { expected: length, actual: count + 1 },
+                    _ => Error::SequenceTooLong { len: count + 1, max: length },
+                });
+            }
+            self.compress_element(count, value.borrow(), variable, accum)?;
+            count += 1;
+        }
+        if let (SequenceLength::Fixed(_), Some(padding)) = (self.length, &self.padding) {
+            for index in count..length {
+                self.compress_element(index, &padding.fill, variable, accum)?;
+            }
+            return Ok(count);
+        }
         match self.length {
-            SequenceLength::Fixed(length) => self.compress_fixed(values, accum, length),
-            SequenceLength::Variable(length) => self.compress_variable(values, accum, length),
+            SequenceLength::Fixed(_) if count != length => {
+                return Err(Error::LengthMismatch { expected: length, actual: count });
+            },
+            SequenceLength::Prefixed(_) => {
+                accum.checked_mul(prefix_permutations(length)?)?;
+                accum.add(count as Digit);
+            },
+            _ => (),
+        }
+        Ok(count)
+    }
+
+    fn compress_element(&self, index: usize, value: &T, variable: bool, accum: &mut Accumulator) -> Result<(), Error> {
+        match self.spec().single() {
+            Some(spec) => {
+                let value = encode_element(spec, index, value)?;
+                match variable {
+                    true => {
+                        accum.checked_mul(spec.permutations() + 1)?;
+                        accum.add(value + 1);
+                    },
+                    false => {
+                        accum.checked_mul(spec.permutations())?;
+                        accum.add(value);
+                    },
+                }
+            },
+            None => {
+                self.spec().encode_into(value, accum).map_err(|error| element_error(index, error))?;
+                if variable {
+                    accum.checked_mul(2)?;
+                    accum.add(1);
+                }
+            },
         }
+        Ok(())
     }
 
-    fn compress_fixed(&self, values: &[T], accum: &mut Accumulator, length: usize) {
-        let permutations = self.spec.permutations();
-        for ii in 0..length {
-            accum.mul(permutations);
-            accum.add(self.spec.encode(&values[ii]).unwrap());
+    fn compress_fixed_wide(&self, values: &[T], accum: &mut Accumulator, length: usize) -> Result<(), Error> {
+        if values.len() != length {
+            return Err(Error::LengthMismatch { expected: length, actual: values.len() });
+        }
+        for (index, value) in values.iter().enumerate() {
+            self.spec().encode_into(value, accum).map_err(|error| element_error(index, error))?;
         }
+        Ok(())
     }
 
-    fn compress_variable(&self, values: &[T], accum: &mut Accumulator, max_length: usize) {
-        let permutations = self.spec.permutations() + 1;
+    /// Wide specs do not have a fixed number of permutations to extend with an
+    /// end of sequence marker, so each element is followed by a flag instead
+    fn compress_variable_wide(&self, values: &[T], accum: &mut Accumulator, max_length: usize) -> Result<(), Error> {
         let count = values.len();
         if count > max_length {
-            panic!("Value of length {} was not able to be compressed by Sequencer with max length {}", count, max_length);
+            return Err(Error::SequenceTooLong { len: count, max: max_length });
         }
-        accum.mul(permutations); // Zero to indicate end of sequence
-        for ii in 0..count {
-            accum.mul(permutations);
-            accum.add(self.spec.encode(&values[ii]).unwrap() + 1);
+        accum.checked_mul(2)?; // Zero to indicate end of sequence
+        for (index, value) in values.iter().enumerate() {
+            self.spec().encode_into(value, accum).map_err(|error| element_error(index, error))?;
+            accum.checked_mul(2)?;
+            accum.add(1);
         }
+        Ok(())
     }
 
-    pub fn decompress(&self, accum: &mut Accumulator) -> Vec<T> {
-        match self.length {
-            SequenceLength::Fixed(length) => self.decompress_fixed(accum, length),
-            SequenceLength::Variable(length) => self.decompress_variable(accum, length),
+    /// The values are compressed as a fixed length sequence, followed by the
+    /// number of values so it is decompressed first
+    fn compress_prefixed(&self, values: &[T], accum: &mut Accumulator, max_length: usize) -> Result<(), Error> {
+        let count = values.len();
+        if count > max_length {
+            return Err(Error::SequenceTooLong { len: count, max: max_length });
+        }
+        let permutations = prefix_permutations(max_length)?;
+        match self.spec().single() {
+            Some(spec) => compress_fixed(spec, values, accum, count)?,
+            None => self.compress_fixed_wide(values, accum, count)?,
+        }
+        accum.checked_mul(permutations)?;
+        accum.add(count as Digit);
+        Ok(())
+    }
+
+    /// Decompress values from the accumulator. If any value can not be 
+    /// decompressed the accumulator is left unchanged and the error reports 
+    /// the index of the offending element
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<Vec<T>, Error> {
+        let snapshot = accum.clone();
+        let result = match (self.length, self.spec().single()) {
+            (SequenceLength::Fixed(length), Some(spec)) => decompress_fixed(spec, accum, length),
+            (SequenceLength::Variable(length), Some(spec)) => decompress_variable(spec, accum, length),
+            (SequenceLength::Fixed(length), None) => self.decompress_fixed_wide(accum, length),
+            (SequenceLength::Variable(length), None) => self.decompress_variable_wide(accum, length),
+            (SequenceLength::Prefixed(length), _) => self.decompress_prefixed(accum, length),
+        };
+        match result {
+            Ok(mut values) => {
+                if let Some((fill, eq)) = self.strip() {
+                    while values.last().is_some_and(|value| eq(value, fill)) {
+                        values.pop();
+                    }
+                }
+                Ok(values)
+            },
+            Err(error) => {
+                *accum = snapshot;
+                Err(error)
+            },
+        }
+    }
+
+    /// Number of bits the sequence adds to the accumulator in the worst case
+    /// (every element having the largest value, and the most elements for a
+    /// variable length), or None if the spec of the elements is not bounded.
+    /// A variable length sequence includes the end of sequence marker, which
+    /// only takes space if there is already data in the accumulator
+    pub fn max_bits(&self) -> Option<f64> {
+        match (self.length, self.spec().single()) {
+            (SequenceLength::Fixed(length), Some(spec)) => {
+                Some(length as f64 * permutation_bits(spec.permutations() as f64))
+            },
+            (SequenceLength::Variable(length), Some(spec)) => {
+                Some((length + 1) as f64 * permutation_bits(spec.permutations() as f64 + 1.0))
+            },
+            (SequenceLength::Fixed(length), None) => Some(length as f64 * self.spec().max_bits()?),
+            (SequenceLength::Variable(length), None) => Some(length as f64 * (self.spec().max_bits()? + 1.0) + 1.0),
+            (SequenceLength::Prefixed(length), _) => {
+                let count_bits = permutation_bits(length as f64 + 1.0);
+                Some(Sequencer::new(self.spec(), SequenceLength::Fixed(length)).max_bits()? + count_bits)
+            },
+        }
+    }
+
+    /// Number of bytes of the accumulator (see `Accumulator::to_bytes`) in
+    /// the worst case, if the sequence is the only thing in it
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bits().map(max_bytes)
+    }
+
+    /// Decompress values from the accumulator one at a time, last to first
+    /// (the reverse of the order they were compressed in), without collecting
+    /// them. Errors give the index of the element counted from the start for
+    /// fixed length sequences and from the end for variable length sequences.
+    ///
+    /// The accumulator is not restored on error. If the iterator is dropped
+    /// before it ends, the accumulator still holds the elements that were not
+    /// decompressed (and the end marker of a variable length sequence), so it
+    /// is only useful to finish the sequence or discard the accumulator
+    pub fn decompress_iter<'s>(&'s self, accum: &'s mut Accumulator) -> DecompressRev<'s, 'a, T> {
+        DecompressRev { sequencer: self, accum, count: 0, length: None, stripping: self.strip().is_some(), done: false }
+    }
+
+    fn decompress_prefixed(&self, accum: &mut Accumulator, max_length: usize) -> Result<Vec<T>, Error> {
+        let count = accum.checked_div(prefix_permutations(max_length)?)? as usize;
+        match self.spec().single() {
+            Some(spec) => decompress_fixed(spec, accum, count),
+            None => self.decompress_fixed_wide(accum, count),
         }
     }
 
-    fn decompress_fixed(&self, accum: &mut Accumulator, length: usize) -> Vec<T> {
-        let permutations = self.spec.permutations();
+    fn decompress_fixed_wide(&self, accum: &mut Accumulator, length: usize) -> Result<Vec<T>, Error> {
         let mut decompressed: Vec<T> = Vec::with_capacity(length);
-        for _ in 0..length {
-            decompressed.push(self.spec.decode(accum.div(permutations)).unwrap());
+        for index in (0..length).rev() {
+            check_remaining(accum, index)?;
+            decompressed.push(self.spec().decode_from(accum).map_err(|error| element_error(index, error))?);
         }
         decompressed.reverse();
-        decompressed
+        Ok(decompressed)
     }
 
-    fn decompress_variable(&self, accum: &mut Accumulator, length: usize) -> Vec<T> {
-        let permutations = self.spec.permutations() + 1;
-        let mut decompressed: Vec<T> = Vec::with_capacity(length);
-        for _ in 0..length {
-            let coded_value = accum.div(permutations);
-            if coded_value == 0 {
+    fn decompress_variable_wide(&self, accum: &mut Accumulator, max_length: usize) -> Result<Vec<T>, Error> {
+        let mut decompressed: Vec<T> = Vec::new();
+        loop {
+            // The index is counted from the end, since the start of the 
+            // sequence was never found
+            let index = decompressed.len();
+            check_remaining(accum, index)?;
+            if accum.div(2) == 0 {
                 break;
             }
-            decompressed.push(self.spec.decode(coded_value-1).unwrap());
+            if index == max_length {
+                return Err(Error::UnterminatedSequence { max: max_length });
+            }
+            check_remaining(accum, index)?;
+            decompressed.push(self.spec().decode_from(accum).map_err(|error| element_error(index, error))?);
         }
         decompressed.reverse();
-        decompressed
+        Ok(decompressed)
     }
 }
 
-
-/// Trait used to define a piece of data that can be compressed to a small 
-/// binary representation
-pub trait DataSpec<T> {
-    fn permutations(&self) -> Digit;
-    fn encode(&self, input: &T) -> Result<Digit, &str>;
-    fn decode(&self, value: Digit) -> Result<T, &str>;
+/// Iterator over the values of a sequence from last to first, returned by
+/// `Sequencer::decompress_iter`
+pub struct DecompressRev<'s, 'a, T> {
+    sequencer: &'s Sequencer<'a, T>,
+    accum: &'s mut Accumulator,
+    count: usize,
+    /// Length of a prefixed sequence, once it has been decompressed
+    length: Option<usize>,
+    /// Trailing fill values are being skipped
+    stripping: bool,
+    done: bool,
 }
 
-/// Boolean type specification
-pub struct Bool {}
+impl<T> DecompressRev<'_, '_, T> {
 
-impl Bool {
+    fn next_fixed(&mut self, length: usize) -> Result<Option<T>, Error> {
+        if self.count == length {
+            return Ok(None);
+        }
+        let index = length - self.count - 1;
+        let value = match self.sequencer.spec().single() {
+            // A spec with a single permutation was never written
+            Some(spec) if spec.permutations() == 1 => decode_element(spec, index, 0)?,
+            Some(spec) => {
+                check_remaining(self.accum, index)?;
+                decode_element(spec, index, self.accum.checked_div(spec.permutations())?)?
+            },
+            None => {
+                check_remaining(self.accum, index)?;
+                self.sequencer.spec().decode_from(self.accum).map_err(|error| element_error(index, error))?
+            },
+        };
+        Ok(Some(value))
+    }
 
-    pub fn new() -> Self {
-        Self {}
+    fn next_prefixed(&mut self, max_length: usize) -> Result<Option<T>, Error> {
+        let length = match self.length {
+            Some(length) => length,
+            None => {
+                let length = self.accum.checked_div(prefix_permutations(max_length)?)? as usize;
+                *self.length.insert(length)
+            },
+        };
+        self.next_fixed(length)
+    }
+
+    fn next_variable(&mut self, max_length: usize) -> Result<Option<T>, Error> {
+        let index = self.count;
+        check_remaining(self.accum, index)?;
+        let value = match self.sequencer.spec().single() {
+            Some(spec) => match self.accum.checked_div(variable_permutations(spec)?)? {
+                0 => return Ok(None),
+                _ if index == max_length => return Err(Error::UnterminatedSequence { max: max_length }),
+                coded_value => decode_element(spec, index, coded_value - 1)?,
+            },
+            None => {
+                if self.accum.div(2) == 0 {
+                    return Ok(None);
+                }
+                if index == max_length {
+                    return Err(Error::UnterminatedSequence { max: max_length });
+                }
+                check_remaining(self.accum, index)?;
+                self.sequencer.spec().decode_from(self.accum).map_err(|error| element_error(index, error))?
+            },
+        };
+        Ok(Some(value))
     }
 }
 
-impl DataSpec<bool> for Bool {
+impl<T> Iterator for DecompressRev<'_, '_, T> {
+    type Item = Result<T, Error>;
 
-    fn permutations(&self) -> Digit {
-        2
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let result = match self.sequencer.length {
+                SequenceLength::Fixed(length) => self.next_fixed(length),
+                SequenceLength::Variable(max_length) => self.next_variable(max_length),
+                SequenceLength::Prefixed(max_length) => self.next_prefixed(max_length),
+            };
+            match result {
+                Ok(Some(value)) => {
+                    self.count += 1;
+                    // The last values come first, so the padding is skipped
+                    // until the first value that is not the fill
+                    if self.stripping {
+                        match self.sequencer.strip() {
+                            Some((fill, eq)) if eq(&value, fill) => continue,
+                            _ => self.stripping = false,
+                        }
+                    }
+                    return Some(Ok(value));
+                },
+                Ok(None) => self.done = true,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                },
+            }
+        }
+        None
     }
+}
+
+/// A Sequencer is the spec of a whole sequence, so sequences of sequences can
+/// be compressed by an outer Sequencer (e.g. lists of points for polygons).
+/// Each level has its own length and end of sequence marker
+impl<T> WideDataSpec<Vec<T>> for Sequencer<'_, T> {
 
-    fn encode(&self, input: &bool) -> Result<Digit, &str> {
-        Ok(input.clone() as Digit)
+    fn encode_into(&self, input: &Vec<T>, accum: &mut Accumulator) -> Result<(), Error> {
+        self.compress(input, accum)
     }
 
-    fn decode(&self, input: Digit) -> Result<bool, &str> {
-        match input {
-            0 => Ok(false),
-            1 => Ok(true),
-            _ => Err("Could not decode the given value as boolean")
-        }
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Vec<T>, Error> {
+        self.decompress(accum)
+    }
+
+    fn max_bits(&self) -> Option<f64> {
+        Sequencer::max_bits(self)
     }
 }
 
-/// Integer Range type specification
-pub struct IntRange {
-    min: SignedDigit,
-    max: SignedDigit,
+/// Map of keys to values, compressed as the sequence of its keys followed by
+/// the values in the same order
+///
+/// The map is a BTreeMap so the entries are always compressed in the same
+/// order. Errors for a key or value are a `Component` error (0 for keys and 1
+/// for values) holding the error of the element.
+pub struct MapSequencer<'a, K, V> {
+    key: Box<dyn WideDataSpec<K> + 'a>,
+    value: Box<dyn WideDataSpec<V> + 'a>,
+    length: SequenceLength,
 }
 
-impl IntRange {
-    pub fn new(min: SignedDigit, max: SignedDigit) -> Self {
-        if min < (SignedDigit::MIN + 1) {
-            panic!("IntRange min cannot be less than SignedDigit::MIN + 1");
+impl<'a, K: Ord + fmt::Debug, V> MapSequencer<'a, K, V> {
+
+    pub fn new(key: impl WideDataSpec<K> + 'a, value: impl WideDataSpec<V> + 'a, length: SequenceLength) -> Self {
+        Self { key: Box::new(key), value: Box::new(value), length }
+    }
+
+    /// Compress the entries of the map into the accumulator. If any entry can
+    /// not be compressed the accumulator is left unchanged
+    pub fn compress(&self, map: &BTreeMap<K, V>, accum: &mut Accumulator) -> Result<(), Error> {
+        let snapshot = accum.clone();
+        let result = self.compress_unchecked(map, accum);
+        if result.is_err() {
+            *accum = snapshot;
         }
-        // Check if input is valid
-        if min >= max {
-            panic!("IntRange min may not be greater than or equal to the max");
+        result
+    }
+
+    /// The values are compressed first with the number of keys, so only the
+    /// keys store the length of the map
+    fn compress_unchecked(&self, map: &BTreeMap<K, V>, accum: &mut Accumulator) -> Result<(), Error> {
+        let values = Sequencer::new(self.value.as_ref(), SequenceLength::Fixed(map.len()));
+        values.compress_iter(map.values(), accum).map_err(|error| component_error(1, error))?;
+        let keys = Sequencer::new(self.key.as_ref(), self.length);
+        keys.compress_iter(map.keys(), accum).map_err(|error| component_error(0, error))?;
+        Ok(())
+    }
+
+    /// Decompress a map from the accumulator. If any entry can not be
+    /// decompressed, or a key is decompressed twice, the accumulator is left
+    /// unchanged
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<BTreeMap<K, V>, Error> {
+        let snapshot = accum.clone();
+        let result = self.decompress_unchecked(accum);
+        if result.is_err() {
+            *accum = snapshot;
         }
-        Self {min, max}
+        result
     }
 
-    pub fn new_full() -> Self {
-        Self::new(SignedDigit::MIN+1, SignedDigit::MAX)
+    fn decompress_unchecked(&self, accum: &mut Accumulator) -> Result<BTreeMap<K, V>, Error> {
+        let keys = Sequencer::new(self.key.as_ref(), self.length).decompress(accum)
+            .map_err(|error| component_error(0, error))?;
+        let mut first_indices: BTreeMap<&K, usize> = BTreeMap::new();
+        for (index, key) in keys.iter().enumerate() {
+            if let Some(first) = first_indices.insert(key, index) {
+                let error = Error::DuplicateElement { value: format!("{:?}", key), index, first };
+                return Err(component_error(0, error));
+            }
+        }
+        let values = Sequencer::new(self.value.as_ref(), SequenceLength::Fixed(keys.len())).decompress(accum)
+            .map_err(|error| component_error(1, error))?;
+        Ok(keys.into_iter().zip(values).collect())
+    }
+
+    /// Number of bits the map adds to the accumulator in the worst case, or
+    /// None if the key or value spec is not bounded
+    pub fn max_bits(&self) -> Option<f64> {
+        let max_length = match self.length {
+            SequenceLength::Fixed(length) | SequenceLength::Variable(length) | SequenceLength::Prefixed(length) => length,
+        };
+        let values = Sequencer::new(self.value.as_ref(), SequenceLength::Fixed(max_length));
+        Some(Sequencer::new(self.key.as_ref(), self.length).max_bits()? + values.max_bits()?)
+    }
+
+    /// Number of bytes of the accumulator (see `Accumulator::to_bytes`) in
+    /// the worst case, if the map is the only thing in it
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bits().map(max_bytes)
     }
 }
 
-impl DataSpec<SignedDigit> for IntRange {
+impl<K: Ord + fmt::Debug, V> WideDataSpec<BTreeMap<K, V>> for MapSequencer<'_, K, V> {
 
-    fn permutations(&self) -> Digit {
-        (self.max - self.min + 1) as Digit
+    fn encode_into(&self, input: &BTreeMap<K, V>, accum: &mut Accumulator) -> Result<(), Error> {
+        self.compress(input, accum)
     }
 
-    fn encode(&self, input: &SignedDigit) -> Result<Digit, &str> {
-        if *input < self.min || *input > self.max {
-            return Err("Value to encode is outside allowed range");
-        }
-        Ok((*input - self.min) as Digit)
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<BTreeMap<K, V>, Error> {
+        self.decompress(accum)
     }
 
-    fn decode(&self, input: Digit) -> Result<SignedDigit, &str> {
-        if input >= self.permutations() {
-            return Err("Cannot decode data, input larger than possible permutations");
-        }
-        let result = (input as SignedDoubleDigit + self.min as SignedDoubleDigit) as SignedDigit;
-        Ok(result) 
+    fn max_bits(&self) -> Option<f64> {
+        MapSequencer::max_bits(self)
     }
 }
 
-/// Number range in fixed point format
-/// 
-/// Note: The compression used by this data type is not lossless, also the 
-///       provided minimum and maximum values are not guaranteed to be exact
-pub struct FixedPointRange {
-    min: SignedDigit,
-    max: SignedDigit,
-    decimals: u32,
+/// Sequence of integers compressed as the first value followed by the
+/// differences between consecutive values, for sorted or slowly changing
+/// values (e.g. timestamps or increasing IDs)
+///
+/// Every value must be in the value spec and every difference in the delta
+/// spec, a delta spec from zero only allows sequences that never decrease.
+/// Errors give the index of the value that could not be compressed or
+/// decompressed.
+pub struct DeltaSequencer<V, D> {
+    values: V,
+    deltas: D,
+    length: SequenceLength,
 }
 
-impl FixedPointRange {
+impl<V: DataSpec<SignedDigit>, D: DataSpec<SignedDigit>> DeltaSequencer<V, D> {
 
-    pub fn new(min: f64, max: f64, decimals: u32) -> Self {
-        // Calculate the absolute maximum values from the required decimals
-        let abs_max_fixed = SignedDigit::MAX >> decimals;
-        let abs_max_float = abs_max_fixed as f64;
-        if max > abs_max_float {
-            panic!("Max allowable value for {} binary decimals is {}", decimals, abs_max_float);
-        }
-        if min < -abs_max_float {
-            panic!("Min allowable value for {} binary decimals is {}", decimals, -abs_max_float);
+    pub fn new(values: V, deltas: D, length: SequenceLength) -> Self {
+        Self { values, deltas, length }
+    }
+
+    /// Sequencer of the length differences after the first value of a fixed
+    /// or prefixed length sequence
+    fn fixed_delta_sequencer(&self, length: usize) -> Sequencer<'_, SignedDigit> {
+        Sequencer::new(&self.deltas, SequenceLength::Fixed(length))
+    }
+
+    /// Sequencer of the differences after the first value of a variable
+    /// length sequence of up to max_length values
+    fn variable_delta_sequencer(&self, max_length: usize) -> Sequencer<'_, SignedDigit> {
+        Sequencer::new(&self.deltas, SequenceLength::Variable(max_length.saturating_sub(1)))
+    }
+
+    /// Compress values into the accumulator, leaving it unchanged on error
+    pub fn compress(&self, values: &[SignedDigit], accum: &mut Accumulator) -> Result<(), Error> {
+        let snapshot = accum.clone();
+        let result = self.compress_unchecked(values, accum);
+        if result.is_err() {
+            *accum = snapshot;
         }
-        let min = float2fixed(min, decimals);
-        let max = float2fixed(max, decimals);
-        Self { min, max, decimals }
+        result
     }
-}
 
-impl DataSpec<f64> for FixedPointRange {
+    fn compress_unchecked(&self, values: &[SignedDigit], accum: &mut Accumulator) -> Result<(), Error> {
+        let count = values.len();
+        match self.length {
+            SequenceLength::Fixed(length) if count != length => {
+                return Err(Error::LengthMismatch { expected: length, actual: count });
+            },
+            SequenceLength::Variable(max_length) | SequenceLength::Prefixed(max_length) if count > max_length => {
+                return Err(Error::SequenceTooLong { len: count, max: max_length });
+            },
+            _ => (),
+        }
+        let mut deltas = Vec::with_capacity(count.saturating_sub(1));
+        for (index, value) in values.iter().enumerate() {
+            encode_element(&self.values, index, value)?;
+            if index > 0 {
+                let previous = values[index - 1];
+                let delta = value.checked_sub(previous).ok_or_else(|| element_error(index, Error::ValueOutOfRange {
+                    value: (*value as SignedDoubleDigit - previous as SignedDoubleDigit).to_string(),
+                    min: SignedDigit::MIN.to_string(),
+                    max: SignedDigit::MAX.to_string(),
+                }))?;
+                encode_element(&self.deltas, index, &delta)?;
+                deltas.push(delta);
+            }
+        }
+        if let Some(first) = values.first() {
+            let sequencer = match self.length {
+                SequenceLength::Variable(max_length) => self.variable_delta_sequencer(max_length),
+                _ => self.fixed_delta_sequencer(deltas.len()),
+            };
+            sequencer.compress(&deltas, accum)?;
+            self.values.encode_into(first, accum)?;
+        }
+        match self.length {
+            SequenceLength::Fixed(_) => (),
+            SequenceLength::Variable(_) => {
+                accum.checked_mul(2)?;
+                accum.add(!values.is_empty() as Digit);
+            },
+            SequenceLength::Prefixed(max_length) => {
+                accum.checked_mul(prefix_permutations(max_length)?)?;
+                accum.add(count as Digit);
+            },
+        }
+        Ok(())
+    }
 
-    fn permutations(&self) -> Digit {
-        ((self.max - self.min) + 1) as Digit
+    /// Decompress values from the accumulator, leaving it unchanged on error
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<Vec<SignedDigit>, Error> {
+        let snapshot = accum.clone();
+        let result = self.decompress_unchecked(accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
     }
 
-    fn encode(&self, input: &f64) -> Result<Digit, &str> {
-        let num = float2fixed(*input, self.decimals);
-        if num < self.min {
-            Err("Number is to small to be encoded as a fixed point")
-        } else if num > self.max {
-            Err("Number is too big to be encoded as a fixed point")
-        } else {
-            let encoded_num = (num - self.min) as Digit;
-            Ok(encoded_num)
+    fn decompress_unchecked(&self, accum: &mut Accumulator) -> Result<Vec<SignedDigit>, Error> {
+        let length = match self.length {
+            SequenceLength::Fixed(0) => return Ok(Vec::new()),
+            SequenceLength::Fixed(length) => length,
+            SequenceLength::Variable(max_length) => match accum.div(2) {
+                0 => return Ok(Vec::new()),
+                _ if max_length == 0 => return Err(Error::UnterminatedSequence { max: max_length }),
+                _ => max_length,
+            },
+            SequenceLength::Prefixed(max_length) => match accum.checked_div(prefix_permutations(max_length)?)? {
+                0 => return Ok(Vec::new()),
+                count => count as usize,
+            },
+        };
+        // A variable length is only known once the deltas are decompressed
+        let mut values = match self.length {
+            SequenceLength::Variable(_) => Vec::new(),
+            _ => Vec::with_capacity(length),
+        };
+        let first = self.values.decode_from(accum).map_err(|error| element_error(0, error))?;
+        values.push(first);
+        let sequencer = match self.length {
+            SequenceLength::Variable(max_length) => self.variable_delta_sequencer(max_length),
+            _ => self.fixed_delta_sequencer(length - 1),
+        };
+        // Errors for deltas are counted from the first value
+        let deltas = sequencer.decompress(accum).map_err(|error| match error {
+            Error::Element { index, error } => Error::Element { index: index + 1, error },
+            Error::DecodeElement { index, value, error } => Error::DecodeElement { index: index + 1, value, error },
+            error => error,
+        })?;
+        for delta in deltas {
+            let index = values.len();
+            let previous = values[index - 1];
+            let value = previous.checked_add(delta).ok_or_else(|| element_error(index, Error::ValueOutOfRange {
+                value: (previous as SignedDoubleDigit + delta as SignedDoubleDigit).to_string(),
+                min: SignedDigit::MIN.to_string(),
+                max: SignedDigit::MAX.to_string(),
+            }))?;
+            // The sum of valid deltas can still leave the value spec
+            encode_element(&self.values, index, &value)?;
+            values.push(value);
         }
+        Ok(values)
     }
 
-    fn decode(&self, input: Digit) -> Result<f64, &str> {
-        if input >= self.permutations() {
-            return Err("Cannot decode data, input larger than possible permutations");
+    /// Number of bits the sequence adds to the accumulator in the worst case
+    pub fn max_bits(&self) -> Option<f64> {
+        let first = permutation_bits(self.values.permutations() as f64);
+        match self.length {
+            SequenceLength::Fixed(0) => Some(0.0),
+            SequenceLength::Fixed(length) => Some(first + self.fixed_delta_sequencer(length - 1).max_bits()?),
+            SequenceLength::Variable(max_length) => Some(first + self.variable_delta_sequencer(max_length).max_bits()? + 1.0),
+            SequenceLength::Prefixed(0) => Some(0.0),
+            SequenceLength::Prefixed(max_length) => {
+                let count_bits = permutation_bits(max_length as f64 + 1.0);
+                Some(first + self.fixed_delta_sequencer(max_length - 1).max_bits()? + count_bits)
+            },
         }
-        let num = (input as SignedDigit) + self.min;
-        Ok(fixed2float(num, self.decimals))
     }
-}
 
-/// CharSet type specification
-pub struct CharSet {
-    charset: Vec<char>,
-    lookup: HashMap<char, usize>,
+    /// Number of bytes of the accumulator (see `Accumulator::to_bytes`) in
+    /// the worst case, if the sequence is the only thing in it
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bits().map(max_bytes)
+    }
 }
 
-impl CharSet {
+impl<V: DataSpec<SignedDigit>, D: DataSpec<SignedDigit>> WideDataSpec<Vec<SignedDigit>> for DeltaSequencer<V, D> {
 
-    pub fn new(charset: &'static str) -> Self {
-        let charset: Vec<char> = charset.chars().collect();
-        let mut lookup: HashMap<char, usize> = HashMap::with_capacity(charset.len());
-        for ii in 0..charset.len() {
-            if lookup.contains_key(&charset[ii]) {
-                panic!("Attempted to add duplicate characters to CharSet data");
-            }
-            lookup.insert(charset[ii], ii);
-        }
-        Self { charset, lookup }
+    fn encode_into(&self, input: &Vec<SignedDigit>, accum: &mut Accumulator) -> Result<(), Error> {
+        self.compress(input, accum)
     }
 
-    pub fn lowercase_letter() -> Self {
-        Self::new("abcdefghijklmnopqrstuvwxyz")
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Vec<SignedDigit>, Error> {
+        self.decompress(accum)
     }
 
-    pub fn lowercase_ascii() -> Self {
-        Self::new("abcdefghijklmnopqrstuvwxyz .!?0123456789()&@#$%:;'\"")
+    fn max_bits(&self) -> Option<f64> {
+        DeltaSequencer::max_bits(self)
     }
+}
 
-    pub fn uppercase_letter() -> Self {
-        Self::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+/// Opaque bytes (e.g. part of a hash or signature), shifted into the
+/// accumulator a Digit at a time
+///
+/// Every byte value is valid data, so a variable length is stored once before
+/// the bytes (the same as `SequenceLength::Prefixed`) instead of marking the
+/// end of the bytes.
+pub struct BytesSpec {
+    length: SequenceLength,
+}
+
+impl BytesSpec {
+
+    pub fn new(length: SequenceLength) -> Self {
+        let length = match length {
+            SequenceLength::Variable(max_length) => SequenceLength::Prefixed(max_length),
+            length => length,
+        };
+        Self { length }
     }
 
-    pub fn uppercase_ascii() -> Self {
-        Self::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ .!?0123456789()&@#$%:;'\"")
+    fn sequencer(&self) -> Sequencer<'static, u8> {
+        Sequencer::new(&Byte {}, self.length)
+    }
+
+    /// Compress bytes into the accumulator, leaving it unchanged on error
+    pub fn compress(&self, bytes: &[u8], accum: &mut Accumulator) -> Result<(), Error> {
+        self.sequencer().compress(bytes, accum)
     }
 
+    /// Decompress bytes from the accumulator, leaving it unchanged on error
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<Vec<u8>, Error> {
+        self.sequencer().decompress(accum)
+    }
 }
 
-impl DataSpec<char> for CharSet {
+impl WideDataSpec<Vec<u8>> for BytesSpec {
 
-    fn permutations(&self) -> Digit {
-        self.charset.len() as Digit
+    fn encode_into(&self, input: &Vec<u8>, accum: &mut Accumulator) -> Result<(), Error> {
+        self.compress(input, accum)
     }
 
-    fn encode(&self, input: &char) -> Result<Digit, &str> {
-        let value = self.lookup.get(&input);
-        match value {
-            None => Err("Could not encode character not defined in the character set"),
-            Some(value) => Ok(value.clone() as Digit)
-        }
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Vec<u8>, Error> {
+        self.decompress(accum)
     }
 
-    fn decode(&self, input: Digit) -> Result<char, &str> {
-        let index = input as usize;
-        if index >= self.charset.len() {
-            Err("Could not decode value to a character")
-        } else {
-            let result = self.charset[index];
-            Ok(result)
-        }
+    fn max_bits(&self) -> Option<f64> {
+        self.sequencer().max_bits()
     }
 }
 
-/// Enumeration type specification
-pub struct Enum {
-    options: &'static [&'static str],
-    lookup: HashMap<String, usize>,
+/// Values of a grid in row major order (the value at row r and column c is
+/// at index `r * cols + c`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix<T> {
+    data: Vec<T>,
+    rows: usize,
+    cols: usize,
 }
 
-impl Enum {
+impl<T> Matrix<T> {
 
-    pub fn new(options: &'static [&'static str]) -> Self {
-        let mut lookup = HashMap::with_capacity(options.len());
-        for ii in 0..options.len() {
-            let new_option = String::from(options[ii]);
-            if lookup.contains_key(&new_option) {
-                panic!("Attempted to add duplicate strings to Enum data");
-            }
-            lookup.insert(new_option, ii);
+    pub fn new(data: Vec<T>, rows: usize, cols: usize) -> Self {
+        Self::try_new(data, rows, cols).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Matrix from values in row major order, or an error if
+    /// there is not a value for every row and column
+    pub fn try_new(data: Vec<T>, rows: usize, cols: usize) -> Result<Self, Error> {
+        let expected = rows.checked_mul(cols).ok_or(Error::TooManyPermutations)?;
+        if data.len() != expected {
+            return Err(Error::InvalidLength { expected, actual: data.len() });
         }
-        Self{ options, lookup }
+        Ok(Self { data, rows, cols })
     }
-}
 
-impl DataSpec<String> for Enum {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
 
-    fn permutations(&self) -> Digit {
-        self.options.len() as Digit
+    pub fn cols(&self) -> usize {
+        self.cols
     }
 
-    fn encode(&self, input: &String) -> Result<Digit, &str> {
-        let value = self.lookup.get(input);
-        match value {
-            None => Err("Given value not contained in this Enum type"),
-            Some(value) => Ok(value.clone() as Digit),
-        }
+    /// Values in row major order
+    pub fn data(&self) -> &[T] {
+        &self.data
     }
 
-    fn decode(&self, input: Digit) -> Result<String, &str> {
-        let index = input as usize;
-        if index >= self.options.len() {
-            Err("Could not decode value as an Enum")
-        } else {
-            let result = String::from(self.options[index]);
-            Ok(result)
+    /// Value at a row and column, if they are in the matrix
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        match row < self.rows && col < self.cols {
+            true => self.data.get(row * self.cols + col),
+            false => None,
         }
     }
-}
 
-// Utility functions
+    /// Values of a row, if it is in the matrix
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        match row < self.rows {
+            true => Some(&self.data[row * self.cols..(row + 1) * self.cols]),
+            false => None,
+        }
+    }
 
-/// Convert a floating point number to a fixed point number
-fn float2fixed(value: f64, decimals: u32) -> SignedDigit {
-    let abs_max_fixed = SignedDigit::MAX >> decimals;
-    let two: f64 = 2.0;
-    let mut value = (value * two.powi(decimals as i32)) as SignedDigit;
-    if value < -abs_max_fixed {
-        value = -abs_max_fixed;
-    } else if value > abs_max_fixed {
-        value = abs_max_fixed;
+    /// Values in row major order, the number of rows and the number of
+    /// columns
+    pub fn into_parts(self) -> (Vec<T>, usize, usize) {
+        (self.data, self.rows, self.cols)
     }
-    value
 }
 
-fn fixed2float(value: SignedDigit, decimals: u32) -> f64 {
-    let two: f64 = 2.0;
-    (value as f64) / two.powi(decimals as i32)
+/// Dimensions of a MatrixSequencer
+#[derive(Clone, Copy)]
+enum MatrixSize {
+    Fixed { rows: usize, cols: usize },
+    Variable { max_rows: usize, max_cols: usize },
 }
 
-#[cfg(test)]
-mod tests {
+/// Grid of values of a spec (e.g. an occupancy map or a tile layer),
+/// compressed with its dimensions so they do not need to be known to
+/// decompress it
+///
+/// Values are compressed in row major order. The dimensions of a variable
+/// size matrix are compressed after the values, so they are decompressed
+/// first.
+pub struct MatrixSequencer<'a, T> {
+    spec: Box<dyn WideDataSpec<T> + 'a>,
+    size: MatrixSize,
+}
 
-    //use std::vec::Vec;
+impl<'a, T> MatrixSequencer<'a, T> {
 
-    use crate::data::{
-        DataSpec, 
-        Bool, 
-        IntRange, 
-        FixedPointRange, 
-        CharSet, 
-        Enum,
-        SequenceLength,
-        Sequencer,
-    };
-    
-    use crate::accum::Accumulator;
+    /// Create a new MatrixSequencer with a fixed number of rows and columns
+    pub fn new(spec: impl WideDataSpec<T> + 'a, rows: usize, cols: usize) -> Self {
+        Self::try_new(spec, rows, cols).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-    #[test]
-    fn bool() {
-        let b = Bool::new();
-        assert_eq!(b.permutations(), 2);
-        assert_eq!(b.encode(&false).unwrap(), 0);
-        assert_eq!(b.encode(&true).unwrap(), 1);
-        assert_eq!(b.decode(0).unwrap(), false);
-        assert_eq!(b.decode(1).unwrap(), true);
-        assert!(b.decode(2).is_err());
+    /// Create a new MatrixSequencer with a fixed number of rows and columns,
+    /// or an error if there are too many values
+    pub fn try_new(spec: impl WideDataSpec<T> + 'a, rows: usize, cols: usize) -> Result<Self, Error> {
+        rows.checked_mul(cols).ok_or(Error::TooManyPermutations)?;
+        Ok(Self { spec: Box::new(spec), size: MatrixSize::Fixed { rows, cols } })
     }
 
-    #[test]
-    fn int_range() {
-        let r = IntRange::new(-10, 10);
-        assert_eq!(r.permutations(), 21);
-        // low values
-        assert_eq!(r.encode(&-10).unwrap(), 0);
-        assert_eq!(r.decode(0).unwrap(), -10);
+    /// Create a new MatrixSequencer with up to a number of rows and columns
+    pub fn new_variable(spec: impl WideDataSpec<T> + 'a, max_rows: usize, max_cols: usize) -> Self {
+        Self::try_new_variable(spec, max_rows, max_cols).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new MatrixSequencer with up to a number of rows and columns,
+    /// or an error if there are too many values or dimensions
+    pub fn try_new_variable(spec: impl WideDataSpec<T> + 'a, max_rows: usize, max_cols: usize) -> Result<Self, Error> {
+        max_rows.checked_mul(max_cols).ok_or(Error::TooManyPermutations)?;
+        prefix_permutations(max_rows)?;
+        prefix_permutations(max_cols)?;
+        Ok(Self { spec: Box::new(spec), size: MatrixSize::Variable { max_rows, max_cols } })
+    }
+
+    /// Compress values in row major order into the accumulator, leaving it
+    /// unchanged on error
+    pub fn compress(&self, data: &[T], rows: usize, cols: usize, accum: &mut Accumulator) -> Result<(), Error> {
+        let snapshot = accum.clone();
+        let result = self.compress_unchecked(data, rows, cols, accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    fn compress_unchecked(&self, data: &[T], rows: usize, cols: usize, accum: &mut Accumulator) -> Result<(), Error> {
+        match self.size {
+            MatrixSize::Fixed { rows: expected_rows, cols: expected_cols } => {
+                if (rows, cols) != (expected_rows, expected_cols) {
+                    return Err(Error::DimensionMismatch { rows, cols, expected_rows, expected_cols });
+                }
+            },
+            MatrixSize::Variable { max_rows, max_cols } => {
+                for (axis, value, max) in [("rows", rows, max_rows), ("cols", cols, max_cols)] {
+                    if value > max {
+                        return Err(Error::MatrixDimension { axis: String::from(axis), value, max });
+                    }
+                }
+            },
+        }
+        let expected = rows.checked_mul(cols).ok_or(Error::TooManyPermutations)?;
+        if data.len() != expected {
+            return Err(Error::LengthMismatch { expected, actual: data.len() });
+        }
+        Sequencer::new(self.spec.as_ref(), SequenceLength::Fixed(expected)).compress(data, accum)?;
+        if let MatrixSize::Variable { max_rows, max_cols } = self.size {
+            accum.checked_mul(prefix_permutations(max_rows)?)?;
+            accum.add(rows as Digit);
+            accum.checked_mul(prefix_permutations(max_cols)?)?;
+            accum.add(cols as Digit);
+        }
+        Ok(())
+    }
+
+    /// Decompress a matrix from the accumulator, leaving it unchanged on error
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<Matrix<T>, Error> {
+        let snapshot = accum.clone();
+        let result = self.decompress_unchecked(accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    fn decompress_unchecked(&self, accum: &mut Accumulator) -> Result<Matrix<T>, Error> {
+        let (rows, cols) = match self.size {
+            MatrixSize::Fixed { rows, cols } => (rows, cols),
+            MatrixSize::Variable { max_rows, max_cols } => {
+                let cols = accum.checked_div(prefix_permutations(max_cols)?)? as usize;
+                let rows = accum.checked_div(prefix_permutations(max_rows)?)? as usize;
+                (rows, cols)
+            },
+        };
+        let data = Sequencer::new(self.spec.as_ref(), SequenceLength::Fixed(rows * cols)).decompress(accum)?;
+        Ok(Matrix { data, rows, cols })
+    }
+
+    /// Number of bits the matrix adds to the accumulator in the worst case, or
+    /// None if the spec of the values is not bounded
+    pub fn max_bits(&self) -> Option<f64> {
+        match self.size {
+            MatrixSize::Fixed { rows, cols } => {
+                Sequencer::new(self.spec.as_ref(), SequenceLength::Fixed(rows * cols)).max_bits()
+            },
+            MatrixSize::Variable { max_rows, max_cols } => {
+                let values = Sequencer::new(self.spec.as_ref(), SequenceLength::Fixed(max_rows * max_cols)).max_bits()?;
+                Some(values + permutation_bits(max_rows as f64 + 1.0) + permutation_bits(max_cols as f64 + 1.0))
+            },
+        }
+    }
+
+    /// Number of bytes of the accumulator (see `Accumulator::to_bytes`) in
+    /// the worst case, if the matrix is the only thing in it
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bits().map(max_bytes)
+    }
+}
+
+impl<T> WideDataSpec<Matrix<T>> for MatrixSequencer<'_, T> {
+
+    fn encode_into(&self, input: &Matrix<T>, accum: &mut Accumulator) -> Result<(), Error> {
+        self.compress(&input.data, input.rows, input.cols, accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Matrix<T>, Error> {
+        self.decompress(accum)
+    }
+
+    fn max_bits(&self) -> Option<f64> {
+        MatrixSequencer::max_bits(self)
+    }
+}
+
+/// Single byte of a BytesSpec
+struct Byte {}
+
+impl DataSpec<u8> for Byte {
+
+    fn permutations(&self) -> Digit {
+        256
+    }
+
+    fn encode(&self, input: &u8) -> Result<Digit, Error> {
+        Ok(*input as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<u8, Error> {
+        u8::try_from(input).map_err(|_| Error::DecodeOutOfBounds { value: input, permutations: 256 })
+    }
+}
+
+/// Error for a single component of a tuple or map entry
+fn component_error(index: usize, error: Error) -> Error {
+    Error::Component { index, error: Box::new(error) }
+}
+
+fn compress_fixed<T>(spec: &dyn DataSpec<T>, values: &[T], accum: &mut Accumulator, length: usize) -> Result<(), Error> {
+    if values.len() != length {
+        return Err(Error::LengthMismatch { expected: length, actual: values.len() });
+    }
+    let permutations = spec.permutations();
+    // A spec with a single permutation adds no information, only check 
+    // that the values can be encoded
+    if permutations == 1 {
+        for (index, value) in values.iter().enumerate() {
+            encode_element(spec, index, value)?;
+        }
+        return Ok(());
+    }
+    if permutations.is_power_of_two() {
+        return compress_packed(spec, values, accum);
+    }
+    for (index, value) in values.iter().enumerate() {
+        accum.checked_mul(permutations)?;
+        accum.add(encode_element(spec, index, value)?);
+    }
+    Ok(())
+}
+
+fn compress_variable<T>(spec: &dyn DataSpec<T>, values: &[T], accum: &mut Accumulator, max_length: usize) -> Result<(), Error> {
+    let permutations = variable_permutations(spec)?;
+    let count = values.len();
+    if count > max_length {
+        return Err(Error::SequenceTooLong { len: count, max: max_length });
+    }
+    accum.checked_mul(permutations)?; // Zero to indicate end of sequence
+    for (index, value) in values.iter().enumerate() {
+        accum.checked_mul(permutations)?;
+        accum.add(encode_element(spec, index, value)? + 1);
+    }
+    Ok(())
+}
+
+fn decompress_fixed<T>(spec: &dyn DataSpec<T>, accum: &mut Accumulator, length: usize) -> Result<Vec<T>, Error> {
+    let permutations = spec.permutations();
+    let mut decompressed: Vec<T> = Vec::with_capacity(length);
+    // A spec with a single permutation was never written to the accumulator
+    if permutations == 1 {
+        for index in 0..length {
+            decompressed.push(decode_element(spec, index, 0)?);
+        }
+        return Ok(decompressed);
+    }
+    // Guarded accumulators are checked for the end of the data after every
+    // value
+    if permutations.is_power_of_two() && !accum.is_guarded() {
+        return decompress_packed(spec, accum, length);
+    }
+    let divisor = PreparedDivisor::try_new(permutations)?;
+    // Values come out last to first
+    for index in (0..length).rev() {
+        check_remaining(accum, index)?;
+        decompressed.push(decode_element(spec, index, accum.div_prepared(&divisor))?);
+    }
+    decompressed.reverse();
+    Ok(decompressed)
+}
+
+/// Compress values of a spec with a power of two permutations as many values
+/// to a Digit at a time, with one shift of the accumulator for each Digit
+/// instead of a multiplication for each value. The result is the same as
+/// multiplying for each value
+fn compress_packed<T>(spec: &dyn DataSpec<T>, values: &[T], accum: &mut Accumulator) -> Result<(), Error> {
+    let bits = spec.permutations().trailing_zeros() as usize;
+    let per_digit = Digit::BITS as usize / bits;
+    for (chunk_index, chunk) in values.chunks(per_digit).enumerate() {
+        let mut packed: Digit = 0;
+        for (offset, value) in chunk.iter().enumerate() {
+            packed = (packed << bits) | encode_element(spec, chunk_index * per_digit + offset, value)?;
+        }
+        accum.checked_shl(bits * chunk.len())?;
+        accum.add(packed);
+    }
+    Ok(())
+}
+
+/// Decompress values compressed by `compress_packed` (or one at a time)
+fn decompress_packed<T>(spec: &dyn DataSpec<T>, accum: &mut Accumulator, length: usize) -> Result<Vec<T>, Error> {
+    let bits = spec.permutations().trailing_zeros() as usize;
+    let mask = spec.permutations() - 1;
+    let per_digit = Digit::BITS as usize / bits;
+    let mut decompressed: Vec<T> = Vec::with_capacity(length);
+    // Values come out last to first, starting with the last (maybe partial)
+    // chunk
+    for start in (0..length).step_by(per_digit).rev() {
+        let count = per_digit.min(length - start);
+        let mut packed = accum.checked_shr(bits * count)?;
+        for index in (start..start + count).rev() {
+            decompressed.push(decode_element(spec, index, packed & mask)?);
+            packed >>= bits;
+        }
+    }
+    decompressed.reverse();
+    Ok(decompressed)
+}
+
+fn decompress_variable<T>(spec: &dyn DataSpec<T>, accum: &mut Accumulator, max_length: usize) -> Result<Vec<T>, Error> {
+    let permutations = variable_permutations(spec)?;
+    let divisor = PreparedDivisor::try_new(permutations)?;
+    // The number of values is only known once the end of sequence marker
+    // is found, so collect the coded values before decoding them in order
+    let mut coded_values: Vec<Digit> = Vec::new();
+    loop {
+        // The index is counted from the end, since the start of the 
+        // sequence was never found
+        check_remaining(accum, coded_values.len())?;
+        let coded_value = accum.div_prepared(&divisor);
+        if coded_value == 0 {
+            break;
+        }
+        if coded_values.len() == max_length {
+            return Err(Error::UnterminatedSequence { max: max_length });
+        }
+        coded_values.push(coded_value - 1);
+    }
+    coded_values
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, value)| decode_element(spec, index, *value))
+        .collect()
+}
+
+/// Permutations of each element in a variable length sequence, including
+/// the zero used to mark the end of the sequence
+fn variable_permutations<T>(spec: &dyn DataSpec<T>) -> Result<Digit, Error> {
+    spec.permutations().checked_add(1).ok_or(Error::TooManyPermutations)
+}
+
+/// Value of a Digit as a u32, if it fits (it always does for 32 bit Digits)
+#[cfg(not(feature = "digit32"))]
+pub(crate) fn digit_to_u32(value: Digit) -> Option<u32> {
+    u32::try_from(value).ok()
+}
+
+/// Value of a Digit as a u32, if it fits (it always does for 32 bit Digits)
+#[cfg(feature = "digit32")]
+pub(crate) fn digit_to_u32(value: Digit) -> Option<u32> {
+    Some(value)
+}
+
+/// Permutations of the number of values of a prefixed sequence
+fn prefix_permutations(max_length: usize) -> Result<Digit, Error> {
+    Digit::try_from(max_length).ok().and_then(|max| max.checked_add(1)).ok_or(Error::TooManyPermutations)
+}
+
+/// Encode a single element of a sequence, attaching its index to any error
+fn encode_element<T>(spec: &dyn DataSpec<T>, index: usize, value: &T) -> Result<Digit, Error> {
+    spec.encode(value).map_err(|error| element_error(index, error))
+}
+
+/// Decode a single element of a sequence, attaching its index and coded value
+/// to any error
+fn decode_element<T>(spec: &dyn DataSpec<T>, index: usize, value: Digit) -> Result<T, Error> {
+    spec.decode(value).map_err(|error| Error::DecodeElement { index, value, error: Box::new(error) })
+}
+
+fn element_error(index: usize, error: Error) -> Error {
+    Error::Element { index, error: Box::new(error) }
+}
+
+/// Check that there is data left to read from a guarded accumulator, an 
+/// unguarded accumulator can not tell if it has run out of data
+fn check_remaining(accum: &Accumulator, index: usize) -> Result<(), Error> {
+    if accum.is_guarded() && accum.is_exhausted() {
+        Err(Error::Exhausted { index })
+    } else {
+        Ok(())
+    }
+}
+
+/// Trait used to define a piece of data that can be compressed to a small 
+/// binary representation
+pub trait DataSpec<T> {
+    fn permutations(&self) -> Digit;
+    fn encode(&self, input: &T) -> Result<Digit, Error>;
+    fn decode(&self, value: Digit) -> Result<T, Error>;
+}
+
+/// Trait used to define a piece of data that is compressed directly into an
+/// accumulator, for data that does not fit in the permutations of a single 
+/// Digit
+/// 
+/// Every DataSpec is also a WideDataSpec
+pub trait WideDataSpec<T> {
+    fn encode_into(&self, input: &T, accum: &mut Accumulator) -> Result<(), Error>;
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<T, Error>;
+
+    /// The spec as a DataSpec, if it is one. Lets the Sequencer use faster 
+    /// methods for specs with a known number of permutations
+    fn single(&self) -> Option<&dyn DataSpec<T>> {
+        None
+    }
+
+    /// Number of bits the spec adds to the accumulator for the largest value,
+    /// or None if it is not bounded (or the spec does not know)
+    fn max_bits(&self) -> Option<f64> {
+        self.single().map(|spec| permutation_bits(spec.permutations() as f64))
+    }
+}
+
+/// Number of bits needed for a number of permutations
+fn permutation_bits(permutations: f64) -> f64 {
+    libm::log2(permutations)
+}
+
+impl<T, S: DataSpec<T>> WideDataSpec<T> for S {
+
+    fn encode_into(&self, input: &T, accum: &mut Accumulator) -> Result<(), Error> {
+        let value = self.encode(input)?;
+        accum.checked_mul(self.permutations())?;
+        accum.add(value);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<T, Error> {
+        let value = accum.checked_div(self.permutations())?;
+        self.decode(value)
+    }
+
+    fn single(&self) -> Option<&dyn DataSpec<T>> {
+        Some(self)
+    }
+}
+
+/// Boolean type specification
+pub struct Bool {}
+
+impl Bool {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for Bool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSpec<bool> for Bool {
+
+    fn permutations(&self) -> Digit {
+        2
+    }
+
+    fn encode(&self, input: &bool) -> Result<Digit, Error> {
+        Ok(*input as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<bool, Error> {
+        match input {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::DecodeOutOfBounds { value: input, permutations: 2 })
+        }
+    }
+}
+
+/// Integer Range type specification
+/// 
+/// A stepped range (`new_stepped`) only encodes values which are a multiple 
+/// of the step away from the minimum
+pub struct IntRange {
+    min: SignedDigit,
+    max: SignedDigit,
+    step: Digit,
+    round: bool,
+}
+
+impl IntRange {
+    pub fn new(min: SignedDigit, max: SignedDigit) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new IntRange, or an error if min and max are not valid
+    pub fn try_new(min: SignedDigit, max: SignedDigit) -> Result<Self, Error> {
+        Self::try_new_stepped(min, max, 1)
+    }
+
+    pub fn new_full() -> Self {
+        Self::new(SignedDigit::MIN+1, SignedDigit::MAX)
+    }
+
+    pub fn new_stepped(min: SignedDigit, max: SignedDigit, step: Digit) -> Self {
+        Self::try_new_stepped(min, max, step).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new IntRange that only encodes multiples of step from min, or
+    /// an error if min, max and step are not valid. If max is not on a step 
+    /// the range ends at the last step before it.
+    pub fn try_new_stepped(min: SignedDigit, max: SignedDigit, step: Digit) -> Result<Self, Error> {
+        if min < (SignedDigit::MIN + 1) {
+            return Err(Error::RangeExceedsLimit {
+                value: min.to_string(),
+                limit: (SignedDigit::MIN + 1).to_string(),
+            });
+        }
+        // Check if input is valid
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        if step == 0 {
+            return Err(Error::ZeroStep);
+        }
+        let steps = (max as SignedDoubleDigit - min as SignedDoubleDigit) / step as SignedDoubleDigit;
+        let max = (min as SignedDoubleDigit + steps * step as SignedDoubleDigit) as SignedDigit;
+        if Digit::try_from(steps + 1).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self {min, max, step, round: false})
+    }
+
+    /// Round values to the nearest step when encoding, instead of returning 
+    /// an error for values that are not on a step
+    pub fn with_rounding(mut self) -> Self {
+        self.round = true;
+        self
+    }
+}
+
+impl DataSpec<SignedDigit> for IntRange {
+
+    fn permutations(&self) -> Digit {
+        ((self.max as SignedDoubleDigit - self.min as SignedDoubleDigit) / self.step as SignedDoubleDigit + 1) as Digit
+    }
+
+    fn encode(&self, input: &SignedDigit) -> Result<Digit, Error> {
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        let offset = (*input as SignedDoubleDigit - self.min as SignedDoubleDigit) as Digit;
+        if self.round {
+            // Round half up, without going past the last step
+            let index = (offset / self.step) + Digit::from(offset % self.step >= self.step - self.step / 2);
+            return Ok(index.min(self.permutations() - 1));
+        }
+        if !offset.is_multiple_of(self.step) {
+            return Err(Error::ValueNotOnStep {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                step: self.step.to_string(),
+            });
+        }
+        Ok(offset / self.step)
+    }
+
+    fn decode(&self, input: Digit) -> Result<SignedDigit, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        let offset = input as SignedDoubleDigit * self.step as SignedDoubleDigit;
+        let result = (offset + self.min as SignedDoubleDigit) as SignedDigit;
+        Ok(result) 
+    }
+}
+
+/// Unsigned integer range type specification
+/// 
+/// The full range of a Digit has one more permutation than can be stored in a 
+/// Digit, so the largest range (`new_full`) leaves out `Digit::MAX`
+pub struct UIntRange {
+    min: Digit,
+    max: Digit,
+}
+
+impl UIntRange {
+    pub fn new(min: Digit, max: Digit) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new UIntRange, or an error if min and max are not valid
+    pub fn try_new(min: Digit, max: Digit) -> Result<Self, Error> {
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        if urange_permutations(min, max).is_none() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self {min, max})
+    }
+
+    pub fn new_full() -> Self {
+        Self::new(0, Digit::MAX - 1)
+    }
+}
+
+impl DataSpec<Digit> for UIntRange {
+
+    fn permutations(&self) -> Digit {
+        urange_permutations(self.min, self.max).unwrap()
+    }
+
+    fn encode(&self, input: &Digit) -> Result<Digit, Error> {
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        Ok(*input - self.min)
+    }
+
+    fn decode(&self, input: Digit) -> Result<Digit, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok(input + self.min)
+    }
+}
+
+/// Primitive integer types that can be used with IntRangeOf
+pub trait RangeInt: Copy + PartialOrd + fmt::Display {
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! impl_range_int {
+    ($($t:ty),*) => {
+        $(
+            impl RangeInt for $t {
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_range_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+/// Value that is always the same (e.g. a protocol version), which takes no
+/// space at all since it only has one permutation
+pub struct ConstSpec<T> {
+    value: T,
+}
+
+impl<T: Clone + PartialEq + fmt::Debug> ConstSpec<T> {
+
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+}
+
+impl<T: Clone + PartialEq + fmt::Debug> DataSpec<T> for ConstSpec<T> {
+
+    fn permutations(&self) -> Digit {
+        1
+    }
+
+    fn encode(&self, input: &T) -> Result<Digit, Error> {
+        if *input != self.value {
+            return Err(Error::ConstantMismatch { value: format!("{:?}", input), expected: format!("{:?}", self.value) });
+        }
+        Ok(0)
+    }
+
+    fn decode(&self, input: Digit) -> Result<T, Error> {
+        match input {
+            0 => Ok(self.value.clone()),
+            _ => Err(Error::DecodeOutOfBounds { value: input, permutations: 1 }),
+        }
+    }
+}
+
+/// Integer range type specification for any primitive integer type
+pub struct IntRangeOf<T: RangeInt> {
+    min: T,
+    max: T,
+}
+
+impl<T: RangeInt> IntRangeOf<T> {
+    pub fn new(min: T, max: T) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new IntRangeOf, or an error if min and max are not valid
+    pub fn try_new(min: T, max: T) -> Result<Self, Error> {
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        if Digit::try_from(max.to_i128() - min.to_i128() + 1).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self {min, max})
+    }
+}
+
+impl<T: RangeInt> DataSpec<T> for IntRangeOf<T> {
+
+    fn permutations(&self) -> Digit {
+        (self.max.to_i128() - self.min.to_i128() + 1) as Digit
+    }
+
+    fn encode(&self, input: &T) -> Result<Digit, Error> {
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        Ok((input.to_i128() - self.min.to_i128()) as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<T, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok(T::from_i128(input as i128 + self.min.to_i128()))
+    }
+}
+
+/// 128 bit integer range type specification, values are compressed as 
+/// multiple Digits
+pub struct Int128Range {
+    min: i128,
+    max: i128,
+}
+
+impl Int128Range {
+    pub fn new(min: i128, max: i128) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Int128Range, or an error if min and max are not valid
+    pub fn try_new(min: i128, max: i128) -> Result<Self, Error> {
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        Ok(Self {min, max})
+    }
+
+    pub fn new_full() -> Self {
+        Self::new(i128::MIN, i128::MAX)
+    }
+
+    fn span(&self) -> u128 {
+        (self.max as u128).wrapping_sub(self.min as u128)
+    }
+}
+
+impl WideDataSpec<i128> for Int128Range {
+
+    fn encode_into(&self, input: &i128, accum: &mut Accumulator) -> Result<(), Error> {
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        encode_wide_index((*input as u128).wrapping_sub(self.min as u128), self.span(), accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<i128, Error> {
+        let index = decode_wide_index(self.span(), accum)?;
+        Ok((self.min as u128).wrapping_add(index) as i128)
+    }
+
+    fn max_bits(&self) -> Option<f64> {
+        Some(wide_index_bits(self.span()))
+    }
+}
+
+/// 128 bit unsigned integer range type specification, values are compressed 
+/// as multiple Digits
+pub struct UInt128Range {
+    min: u128,
+    max: u128,
+}
+
+impl UInt128Range {
+    pub fn new(min: u128, max: u128) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new UInt128Range, or an error if min and max are not valid
+    pub fn try_new(min: u128, max: u128) -> Result<Self, Error> {
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        Ok(Self {min, max})
+    }
+
+    pub fn new_full() -> Self {
+        Self::new(u128::MIN, u128::MAX)
+    }
+}
+
+impl WideDataSpec<u128> for UInt128Range {
+
+    fn encode_into(&self, input: &u128, accum: &mut Accumulator) -> Result<(), Error> {
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        encode_wide_index(*input - self.min, self.max - self.min, accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<u128, Error> {
+        Ok(self.min + decode_wide_index(self.max - self.min, accum)?)
+    }
+
+    fn max_bits(&self) -> Option<f64> {
+        Some(wide_index_bits(self.max - self.min))
+    }
+}
+
+/// Rounding applied to values that fall between two representable numbers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round towards zero
+    #[default]
+    Truncate,
+    /// Round to the nearest value, halfway values round away from zero
+    Nearest,
+    /// Round towards negative infinity
+    Floor,
+    /// Round towards positive infinity
+    Ceil,
+}
+
+/// Number range in fixed point format
+/// 
+/// Note: The compression used by this data type is not lossless, also the 
+///       provided minimum and maximum values are not guaranteed to be exact
+///       (see `actual_min` and `actual_max`)
+pub struct FixedPointRange {
+    min: SignedDigit,
+    max: SignedDigit,
+    decimals: u32,
+    rounding: RoundingMode,
+}
+
+impl FixedPointRange {
+
+    pub fn new(min: f64, max: f64, decimals: u32) -> Self {
+        Self::try_new(min, max, decimals).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new FixedPointRange, or an error if the range can not be
+    /// represented with the required binary decimals
+    pub fn try_new(min: f64, max: f64, decimals: u32) -> Result<Self, Error> {
+        Self::try_new_with_rounding(min, max, decimals, RoundingMode::default())
+    }
+
+    pub fn new_with_rounding(min: f64, max: f64, decimals: u32, rounding: RoundingMode) -> Self {
+        Self::try_new_with_rounding(min, max, decimals, rounding).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new FixedPointRange which rounds values to encode with a 
+    /// rounding mode, the range itself is always truncated
+    pub fn try_new_with_rounding(min: f64, max: f64, decimals: u32, rounding: RoundingMode) -> Result<Self, Error> {
+        if decimals >= SignedDigit::BITS - 1 {
+            return Err(Error::InvalidDecimals(decimals));
+        }
+        if !min.is_finite() || !max.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        // Calculate the absolute maximum values from the required decimals
+        let abs_max_fixed = SignedDigit::MAX >> decimals;
+        let abs_max_float = abs_max_fixed as f64;
+        if max > abs_max_float {
+            return Err(Error::RangeExceedsLimit {
+                value: max.to_string(),
+                limit: abs_max_float.to_string(),
+            });
+        }
+        if min < -abs_max_float {
+            return Err(Error::RangeExceedsLimit {
+                value: min.to_string(),
+                limit: (-abs_max_float).to_string(),
+            });
+        }
+        let (requested_min, requested_max) = (min, max);
+        let min = float2fixed(min, decimals, RoundingMode::Truncate);
+        let max = float2fixed(max, decimals, RoundingMode::Truncate);
+        if min >= max {
+            return Err(Error::CollapsedRange {
+                min: requested_min.to_string(),
+                max: requested_max.to_string(),
+            });
+        }
+        if range_permutations(min, max).is_none() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { min, max, decimals, rounding })
+    }
+
+    /// Smallest value that can be encoded, the requested minimum truncated to
+    /// the binary decimals
+    pub fn actual_min(&self) -> f64 {
+        fixed2float(self.min, self.decimals)
+    }
+
+    /// Largest value that can be encoded, the requested maximum truncated to
+    /// the binary decimals
+    pub fn actual_max(&self) -> f64 {
+        fixed2float(self.max, self.decimals)
+    }
+
+    /// Number of binary decimals
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+
+    /// Distance between two representable values, the error of an encoded 
+    /// value is less than this (or half of it with `RoundingMode::Nearest`)
+    pub fn quantization_step(&self) -> f64 {
+        1.0 / pow2(self.decimals)
+    }
+}
+
+impl DataSpec<f64> for FixedPointRange {
+
+    fn permutations(&self) -> Digit {
+        range_permutations(self.min, self.max).unwrap()
+    }
+
+    fn encode(&self, input: &f64) -> Result<Digit, Error> {
+        // NaN and infinity would saturate to a valid looking number
+        if !input.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        let num = float2fixed(*input, self.decimals, self.rounding);
+        if num < self.min || num > self.max {
+            Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: fixed2float(self.min, self.decimals).to_string(),
+                max: fixed2float(self.max, self.decimals).to_string(),
+            })
+        } else {
+            let encoded_num = (num as SignedDoubleDigit - self.min as SignedDoubleDigit) as Digit;
+            Ok(encoded_num)
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<f64, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        let num = (input as SignedDoubleDigit + self.min as SignedDoubleDigit) as SignedDigit;
+        Ok(fixed2float(num, self.decimals))
+    }
+}
+
+/// CharSet type specification
+///
+/// A case insensitive CharSet encodes both cases of a letter the same, and
+/// decodes to the case of the letter in the set.
+pub struct CharSet {
+    charset: Vec<char>,
+    lookup: BTreeMap<char, usize>,
+    fold: bool,
+}
+
+impl CharSet {
+
+    pub fn new(charset: &str) -> Self {
+        Self::try_new(charset).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new CharSet, or an error if a character is repeated
+    pub fn try_new(charset: &str) -> Result<Self, Error> {
+        Self::try_new_folded(charset, false)
+    }
+
+    pub fn case_insensitive(charset: &str) -> Self {
+        Self::try_case_insensitive(charset).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new case insensitive CharSet, or an error if a character is
+    /// repeated or the set has both cases of a letter
+    pub fn try_case_insensitive(charset: &str) -> Result<Self, Error> {
+        Self::try_new_folded(charset, true)
+    }
+
+    fn try_new_folded(charset: &str, fold: bool) -> Result<Self, Error> {
+        let charset: Vec<char> = charset.chars().collect();
+        if Digit::try_from(charset.len()).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        let mut lookup: BTreeMap<char, usize> = BTreeMap::new();
+        for (ii, c) in charset.iter().enumerate() {
+            let key = if fold { fold_case(*c) } else { *c };
+            if let Some(first) = lookup.insert(key, ii) {
+                if charset[first] != *c {
+                    return Err(Error::AmbiguousCase { value: *c, index: ii, first });
+                }
+                return Err(Error::DuplicateChar { value: *c, index: ii, first });
+            }
+        }
+        Ok(Self { charset, lookup, fold })
+    }
+
+    pub fn from_ranges(ranges: &[(char, char)], extra: &str) -> Self {
+        Self::try_from_ranges(ranges, extra).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new CharSet from inclusive ranges of characters followed by
+    /// the characters of `extra`, or an error if a range is reversed or a
+    /// character is repeated (e.g. by overlapping ranges)
+    pub fn try_from_ranges(ranges: &[(char, char)], extra: &str) -> Result<Self, Error> {
+        let mut charset = String::new();
+        for (first, last) in ranges {
+            if first > last {
+                return Err(Error::InvalidRange { min: first.to_string(), max: last.to_string() });
+            }
+            charset.extend(*first..=*last);
+        }
+        charset.push_str(extra);
+        Self::try_new(&charset)
+    }
+
+    pub fn lowercase_letter() -> Self {
+        Self::new("abcdefghijklmnopqrstuvwxyz")
+    }
+
+    pub fn lowercase_ascii() -> Self {
+        Self::new("abcdefghijklmnopqrstuvwxyz .!?0123456789()&@#$%:;'\"")
+    }
+
+    pub fn uppercase_letter() -> Self {
+        Self::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+    }
+
+    pub fn uppercase_ascii() -> Self {
+        Self::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ .!?0123456789()&@#$%:;'\"")
+    }
+
+    /// All 95 printable ASCII characters, from space to `~` in code point order
+    pub fn ascii_printable() -> Self {
+        Self::from_ranges(&[(' ', '~')], "")
+    }
+
+    /// Decimal digits `0` to `9`
+    pub fn digits() -> Self {
+        Self::new("0123456789")
+    }
+
+    /// Hexadecimal digits `0` to `9` then `a` to `f`
+    pub fn hex_lower() -> Self {
+        Self::new("0123456789abcdef")
+    }
+
+    /// Hexadecimal digits `0` to `9` then `A` to `F`
+    pub fn hex_upper() -> Self {
+        Self::new("0123456789ABCDEF")
+    }
+
+    /// URL safe base64 alphabet in the order of RFC 4648 (`A` to `Z`, `a` to
+    /// `z`, `0` to `9`, `-` and `_`), so indices are the base64 digit values
+    pub fn base64url() -> Self {
+        Self::from_ranges(&[('A', 'Z'), ('a', 'z'), ('0', '9')], "-_")
+    }
+
+    /// Letters of both cases and digits in code point order (`0` to `9`, `A`
+    /// to `Z` then `a` to `z`)
+    pub fn alphanumeric() -> Self {
+        Self::from_ranges(&[('0', '9'), ('A', 'Z'), ('a', 'z')], "")
+    }
+
+}
+
+impl DataSpec<char> for CharSet {
+
+    fn permutations(&self) -> Digit {
+        self.charset.len() as Digit
+    }
+
+    fn encode(&self, input: &char) -> Result<Digit, Error> {
+        let value = if self.fold {
+            self.lookup.get(&fold_case(*input))
+        } else {
+            self.lookup.get(input)
+        };
+        match value {
+            None => Err(Error::CharNotInSet(*input)),
+            Some(value) => Ok(*value as Digit)
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<char, Error> {
+        let index = input as usize;
+        if index >= self.charset.len() {
+            Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() })
+        } else {
+            let result = self.charset[index];
+            Ok(result)
+        }
+    }
+}
+
+/// Builder for a CharSet assembled from several parts
+///
+/// Characters are indexed in the order they are added, so a CharSet built
+/// from the same parts in the same order always encodes the same way. Repeated
+/// characters are an error when the CharSet is built, unless they are dropped
+/// with `dedup_silently`, which keeps the first occurrence.
+#[derive(Default)]
+pub struct CharSetBuilder {
+    chars: Vec<char>,
+    dedup: bool,
+    error: Option<Error>,
+}
+
+impl CharSetBuilder {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the characters of a string
+    pub fn add_str(mut self, chars: &str) -> Self {
+        self.chars.extend(chars.chars());
+        self
+    }
+
+    /// Add an inclusive range of characters, a reversed range is an error
+    /// when the CharSet is built
+    pub fn add_range(mut self, first: char, last: char) -> Self {
+        if first > last && self.error.is_none() {
+            self.error = Some(Error::InvalidRange { min: first.to_string(), max: last.to_string() });
+        }
+        self.chars.extend(first..=last);
+        self
+    }
+
+    /// Add the characters of another CharSet in its order
+    pub fn add_charset(mut self, charset: &CharSet) -> Self {
+        self.chars.extend(charset.charset.iter());
+        self
+    }
+
+    /// Drop repeated characters instead of returning an error
+    pub fn dedup_silently(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Create the CharSet, or an error if a range was reversed or a character
+    /// was repeated
+    pub fn build(self) -> Result<CharSet, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let mut chars = self.chars;
+        if self.dedup {
+            let mut seen = BTreeSet::new();
+            chars.retain(|c| seen.insert(*c));
+        }
+        CharSet::try_new(&chars.into_iter().collect::<String>())
+    }
+}
+
+/// Enumeration type specification
+///
+/// Aliases encode the same as their option, and decode as the option.
+pub struct Enum {
+    options: EnumOf<String>,
+    aliases: BTreeMap<String, Digit>,
+}
+
+impl Enum {
+
+    pub fn new(options: &[&str]) -> Self {
+        Self::try_new(options).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Enum, or an error if an option is repeated
+    pub fn try_new(options: &[&str]) -> Result<Self, Error> {
+        Self::try_from_vec(options.iter().map(|option| String::from(*option)).collect())
+    }
+
+    pub fn from_vec(options: Vec<String>) -> Self {
+        Self::try_from_vec(options).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Enum from options built at runtime, or an error if an
+    /// option is repeated
+    pub fn try_from_vec(options: Vec<String>) -> Result<Self, Error> {
+        Ok(Self { options: EnumOf::try_new_named(options, String::clone)?, aliases: BTreeMap::new() })
+    }
+
+    pub fn with_aliases(options: &[&str], aliases: &[(&str, &str)]) -> Self {
+        Self::try_with_aliases(options, aliases).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Enum where each alias is another name for one of the
+    /// options, or an error if an option is repeated, an alias is for an
+    /// unknown option or an alias is the same as an option or another alias
+    pub fn try_with_aliases(options: &[&str], aliases: &[(&str, &str)]) -> Result<Self, Error> {
+        let mut spec = Self::try_new(options)?;
+        for (alias, option) in aliases {
+            let index = spec.options.encode(&String::from(*option))?;
+            let is_option = spec.options.encode(&String::from(*alias)).is_ok();
+            if is_option || spec.aliases.insert(String::from(*alias), index).is_some() {
+                return Err(Error::DuplicateAlias(String::from(*alias)));
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl DataSpec<String> for Enum {
+
+    fn permutations(&self) -> Digit {
+        self.options.permutations()
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        match self.aliases.get(input) {
+            Some(index) => Ok(*index),
+            None => self.options.encode(input),
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        self.options.decode(input)
+    }
+}
+
+/// Enumeration that can have options added later without changing the
+/// encoding of its existing options
+///
+/// The permutations are always the reserved capacity, so values compressed
+/// before options were added still decompress the same. New options can only
+/// be appended, which gives them the next free index. Decoding an index of an
+/// option that is not defined (e.g. by an older version) is an error.
+pub struct VersionedEnum {
+    options: Vec<String>,
+    lookup: BTreeMap<String, usize>,
+    capacity: Digit,
+}
+
+impl VersionedEnum {
+
+    pub fn new(options: &[&str], capacity: Digit) -> Self {
+        Self::try_new(options, capacity).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new VersionedEnum with room for a number of options, or an
+    /// error if an option is repeated or there is no room for every option
+    pub fn try_new(options: &[&str], capacity: Digit) -> Result<Self, Error> {
+        if capacity == 0 || !Digit::try_from(options.len()).is_ok_and(|len| len <= capacity) {
+            return Err(Error::InsufficientCapacity { options: options.len(), capacity });
+        }
+        let mut spec = Self { options: Vec::new(), lookup: BTreeMap::new(), capacity };
+        for option in options {
+            spec.append_option(option)?;
+        }
+        Ok(spec)
+    }
+
+    /// Add an option with the next free index, or return an error if it is
+    /// already an option or there is no room left
+    pub fn append_option(&mut self, option: &str) -> Result<(), Error> {
+        let index = self.options.len();
+        if index as Digit >= self.capacity {
+            return Err(Error::CapacityExceeded { capacity: self.capacity });
+        }
+        if let Some(first) = self.lookup.get(option) {
+            return Err(Error::DuplicateOption { value: String::from(option), index, first: *first });
+        }
+        self.lookup.insert(String::from(option), index);
+        self.options.push(String::from(option));
+        Ok(())
+    }
+}
+
+impl DataSpec<String> for VersionedEnum {
+
+    fn permutations(&self) -> Digit {
+        self.capacity
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        match self.lookup.get(input) {
+            None => Err(Error::UnknownEnumVariant(input.clone())),
+            Some(value) => Ok(*value as Digit),
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        if input >= self.capacity {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.capacity });
+        }
+        self.options.get(input as usize).cloned().ok_or(Error::UnknownVariantIndex(input))
+    }
+}
+
+/// Enumeration where each option costs about the bits of its share of the
+/// total weight, so common options cost less than rare ones
+///
+/// Must be compressed with a WideDataSpec interface (e.g. a Sequencer), the
+/// same as a WeightedCharSet. Options are compressed the same way, dividing
+/// by the weight of the option before multiplying by the total, as only
+/// multiplying by the total would cost every option `log2(total)` bits.
+pub struct WeightedEnum {
+    options: Vec<String>,
+    weights: Vec<Digit>,
+    starts: Vec<Digit>,
+    lookup: BTreeMap<String, usize>,
+    total: Digit,
+}
+
+impl WeightedEnum {
+
+    pub fn new(pairs: &[(&str, u32)]) -> Self {
+        Self::try_new(pairs).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new WeightedEnum, or an error if an option is repeated, has
+    /// a weight of zero or the total weight does not fit in a Digit
+    pub fn try_new(pairs: &[(&str, u32)]) -> Result<Self, Error> {
+        let mut lookup = BTreeMap::new();
+        let mut starts = Vec::with_capacity(pairs.len());
+        let mut total: Digit = 0;
+        for (ii, (option, weight)) in pairs.iter().enumerate() {
+            if *weight == 0 {
+                return Err(Error::ZeroOptionWeight(String::from(*option)));
+            }
+            if let Some(first) = lookup.insert(String::from(*option), ii) {
+                return Err(Error::DuplicateOption { value: String::from(*option), index: ii, first });
+            }
+            starts.push(total);
+            total = total.checked_add(*weight as Digit).ok_or(Error::TooManyPermutations)?;
+        }
+        Ok(Self {
+            options: pairs.iter().map(|(option, _)| String::from(*option)).collect(),
+            weights: pairs.iter().map(|(_, weight)| *weight as Digit).collect(),
+            starts,
+            lookup,
+            total,
+        })
+    }
+}
+
+impl WideDataSpec<String> for WeightedEnum {
+
+    fn encode_into(&self, input: &String, accum: &mut Accumulator) -> Result<(), Error> {
+        let index = *self.lookup.get(input).ok_or_else(|| Error::UnknownEnumVariant(input.clone()))?;
+        let remainder = accum.checked_div(self.weights[index])?;
+        accum.checked_mul(self.total)?;
+        accum.add(self.starts[index] + remainder);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        if self.options.is_empty() {
+            return Err(Error::DecodeOutOfBounds { value: 0, permutations: 0 });
+        }
+        let slot = accum.checked_div(self.total)?;
+        let index = self.starts.partition_point(|start| *start <= slot) - 1;
+        accum.checked_mul(self.weights[index])?;
+        accum.add(slot - self.starts[index]);
+        Ok(self.options[index].clone())
+    }
+
+    /// Bits of the rarest option, `log2(total / min_weight)`
+    fn max_bits(&self) -> Option<f64> {
+        let min_weight = self.weights.iter().min()?;
+        Some(permutation_bits(self.total as f64 / *min_weight as f64))
+    }
+}
+
+/// Enumeration of values of any ordered type (e.g. a list of resolutions or
+/// addresses), options are indexed in the order they are given
+///
+/// Errors give options in their `Debug` form.
+pub struct EnumOf<T> {
+    options: Vec<T>,
+    lookup: BTreeMap<T, usize>,
+    name: fn(&T) -> String,
+}
+
+impl<T: Clone + Ord + fmt::Debug> EnumOf<T> {
+
+    pub fn new(options: Vec<T>) -> Self {
+        Self::try_new(options).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new EnumOf, or an error if an option is repeated
+    pub fn try_new(options: Vec<T>) -> Result<Self, Error> {
+        Self::try_new_named(options, |option| format!("{:?}", option))
+    }
+}
+
+impl<T: Clone + Ord> EnumOf<T> {
+
+    fn try_new_named(options: Vec<T>, name: fn(&T) -> String) -> Result<Self, Error> {
+        if Digit::try_from(options.len()).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        let mut lookup = BTreeMap::new();
+        for (ii, option) in options.iter().enumerate() {
+            if let Some(first) = lookup.insert(option.clone(), ii) {
+                return Err(Error::DuplicateOption { value: name(option), index: ii, first });
+            }
+        }
+        Ok(Self { options, lookup, name })
+    }
+}
+
+impl<T: Clone + Ord> DataSpec<T> for EnumOf<T> {
+
+    fn permutations(&self) -> Digit {
+        self.options.len() as Digit
+    }
+
+    fn encode(&self, input: &T) -> Result<Digit, Error> {
+        let value = self.lookup.get(input);
+        match value {
+            None => Err(Error::UnknownEnumVariant((self.name)(input))),
+            Some(value) => Ok(*value as Digit),
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<T, Error> {
+        let index = input as usize;
+        if index >= self.options.len() {
+            Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() })
+        } else {
+            let result = self.options[index].clone();
+            Ok(result)
+        }
+    }
+}
+
+/// Set of named flags (e.g. the capabilities of a device), each flag is one
+/// bit of the index in the order the names are given
+///
+/// There can be at most `Digit::BITS - 1` flags.
+pub struct BitFlagsSpec {
+    names: Vec<String>,
+    lookup: BTreeMap<String, u32>,
+}
+
+impl BitFlagsSpec {
+
+    pub fn new(names: &[&str]) -> Self {
+        Self::try_new(names).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new BitFlagsSpec, or an error if a name is repeated or there
+    /// are too many flags for the permutations to fit in a Digit
+    pub fn try_new(names: &[&str]) -> Result<Self, Error> {
+        if names.len() >= Digit::BITS as usize {
+            return Err(Error::TooManyPermutations);
+        }
+        let mut lookup = BTreeMap::new();
+        for (ii, name) in names.iter().enumerate() {
+            if let Some(first) = lookup.insert(String::from(*name), ii as u32) {
+                return Err(Error::DuplicateOption { value: String::from(*name), index: ii, first: first as usize });
+            }
+        }
+        Ok(Self { names: names.iter().map(|name| String::from(*name)).collect(), lookup })
+    }
+}
+
+impl DataSpec<BTreeSet<String>> for BitFlagsSpec {
+
+    fn permutations(&self) -> Digit {
+        1 << self.names.len()
+    }
+
+    fn encode(&self, input: &BTreeSet<String>) -> Result<Digit, Error> {
+        let mut value: Digit = 0;
+        for name in input {
+            match self.lookup.get(name) {
+                None => return Err(Error::UnknownEnumVariant(name.clone())),
+                Some(bit) => value |= 1 << bit,
+            }
+        }
+        Ok(value)
+    }
+
+    fn decode(&self, input: Digit) -> Result<BTreeSet<String>, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok(self.names.iter().enumerate().filter(|(bit, _)| input & (1 << bit) != 0).map(|(_, name)| name.clone()).collect())
+    }
+}
+
+// Utility functions
+
+/// Simple case folding of a character, characters that lowercase to more than
+/// one character are left as they are
+pub(crate) fn fold_case(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c,
+    }
+}
+
+/// Count the integers in the range [min, max], giving None if the count does
+/// not fit in a Digit
+pub(crate) fn range_permutations(min: SignedDigit, max: SignedDigit) -> Option<Digit> {
+    let count = max as SignedDoubleDigit - min as SignedDoubleDigit + 1;
+    Digit::try_from(count).ok()
+}
+
+/// Number of values from min to max (inclusive) for unsigned ranges, if it 
+/// fits in a Digit
+fn urange_permutations(min: Digit, max: Digit) -> Option<Digit> {
+    let count = max as DoubleDigit - min as DoubleDigit + 1;
+    Digit::try_from(count).ok()
+}
+
+/// Number of bytes needed for a number of bits
+pub(crate) fn max_bytes(bits: f64) -> usize {
+    libm::ceil(bits / 8.0) as usize
+}
+
+/// Number of bits used by an index from 0 to span (inclusive)
+fn wide_index_bits(span: u128) -> f64 {
+    let (count, top_permutations) = wide_digits(span);
+    let top_bits = match top_permutations {
+        Some(permutations) => permutation_bits(permutations as f64),
+        None => Digit::BITS as f64,
+    };
+    top_bits + (Digit::BITS * (count - 1)) as f64
+}
+
+/// Split the Digits needed for indexes from 0 to span (inclusive). Returns 
+/// the number of Digits, and the permutations of the most significant Digit 
+/// (None if it uses every bit of the Digit). The other Digits use every bit.
+fn wide_digits(span: u128) -> (u32, Option<Digit>) {
+    let bits = u128::BITS - span.leading_zeros();
+    let count = bits.div_ceil(Digit::BITS).max(1);
+    let top_span = span >> (Digit::BITS * (count - 1));
+    match Digit::try_from(top_span + 1) {
+        Ok(permutations) => (count, Some(permutations)),
+        Err(_) => (count, None),
+    }
+}
+
+/// Compress an index from 0 to span (inclusive) into the accumulator, most 
+/// significant Digit first
+pub(crate) fn encode_wide_index(index: u128, span: u128, accum: &mut Accumulator) -> Result<(), Error> {
+    let (count, top_permutations) = wide_digits(span);
+    for ii in (0..count).rev() {
+        let digit = (index >> (Digit::BITS * ii)) as Digit;
+        match (ii == count - 1, top_permutations) {
+            (true, Some(permutations)) => accum.checked_mul(permutations)?,
+            _ => accum.checked_shl(Digit::BITS as usize)?,
+        }
+        accum.add(digit);
+    }
+    Ok(())
+}
+
+/// Decompress an index from 0 to span (inclusive) from the accumulator
+pub(crate) fn decode_wide_index(span: u128, accum: &mut Accumulator) -> Result<u128, Error> {
+    let (count, top_permutations) = wide_digits(span);
+    let mut index: u128 = 0;
+    for ii in 0..count {
+        let digit = match (ii == count - 1, top_permutations) {
+            (true, Some(permutations)) => accum.checked_div(permutations)?,
+            _ => accum.checked_shr(Digit::BITS as usize)?,
+        };
+        index |= (digit as u128) << (Digit::BITS * ii);
+    }
+    if index > span {
+        return Err(Error::DecodeOutOfRange { value: index.to_string(), max: span.to_string() });
+    }
+    Ok(index)
+}
+
+/// Convert a floating point number to a fixed point number
+fn float2fixed(value: f64, decimals: u32, rounding: RoundingMode) -> SignedDigit {
+    let abs_max_fixed = SignedDigit::MAX >> decimals;
+    let value = value * pow2(decimals);
+    let value = match rounding {
+        RoundingMode::Truncate => value,
+        RoundingMode::Nearest => libm::round(value),
+        RoundingMode::Floor => libm::floor(value),
+        RoundingMode::Ceil => libm::ceil(value),
+    };
+    let mut value = value as SignedDigit;
+    if value < -abs_max_fixed {
+        value = -abs_max_fixed;
+    } else if value > abs_max_fixed {
+        value = abs_max_fixed;
+    }
+    value
+}
+
+fn fixed2float(value: SignedDigit, decimals: u32) -> f64 {
+    (value as f64) / pow2(decimals)
+}
+
+/// Calculate 2^exponent exactly (without needing the std float functions)
+fn pow2(exponent: u32) -> f64 {
+    (1u128 << exponent) as f64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::boxed::Box;
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::net::Ipv4Addr;
+
+    use crate::data::{
+        DataSpec, 
+        WideDataSpec,
+        Bool, 
+        ConstSpec,
+        IntRange, 
+        UIntRange,
+        IntRangeOf,
+        Int128Range,
+        UInt128Range,
+        FixedPointRange, 
+        RoundingMode,
+        CharSet, 
+        CharSetBuilder,
+        Enum,
+        EnumOf,
+        VersionedEnum,
+        WeightedEnum,
+        BitFlagsSpec,
+        SequenceLength,
+        Sequencer,
+        MapSequencer,
+        DeltaSequencer,
+        BytesSpec,
+        Matrix,
+        MatrixSequencer,
+    };
+    
+    use crate::accum::{Accumulator, Digit, SignedDigit};
+    use crate::error::Error;
+
+    #[test]
+    fn bool() {
+        let b = Bool::new();
+        assert_eq!(b.permutations(), 2);
+        assert_eq!(b.encode(&false).unwrap(), 0);
+        assert_eq!(b.encode(&true).unwrap(), 1);
+        assert!(!b.decode(0).unwrap());
+        assert!(b.decode(1).unwrap());
+        assert!(b.decode(2).is_err());
+    }
+
+    #[test]
+    fn const_spec() {
+        let version = ConstSpec::new(3u8);
+        assert_eq!(version.permutations(), 1);
+        assert_eq!(version.encode(&3), Ok(0));
+        assert_eq!(version.decode(0), Ok(3));
+        assert_eq!(version.decode(1), Err(Error::DecodeOutOfBounds { value: 1, permutations: 1 }));
+        assert_eq!(
+            version.encode(&4),
+            Err(Error::ConstantMismatch { value: String::from("4"), expected: String::from("3") }),
+        );
+        let magic = ConstSpec::new(String::from("ADMS"));
+        assert_eq!(
+            magic.encode(&String::from("ADMZ")),
+            Err(Error::ConstantMismatch { value: String::from("\"ADMZ\""), expected: String::from("\"ADMS\"") }),
+        );
+        // Compressing a constant leaves the accumulator unchanged
+        let mut a = Accumulator::new();
+        a.add(12345);
+        let bytes = a.to_bytes();
+        magic.encode_into(&String::from("ADMS"), &mut a).unwrap();
+        assert_eq!(a.to_bytes(), bytes);
+        assert_eq!(magic.decode_from(&mut a), Ok(String::from("ADMS")));
+        assert_eq!(a.to_bytes(), bytes);
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&version, SequenceLength::Fixed(4));
+        sequencer.compress(&[3, 3, 3, 3], &mut a).unwrap();
+        assert!(a.is_empty());
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), vec![3, 3, 3, 3]);
+        assert!(sequencer.compress(&[3, 3, 4, 3], &mut a).is_err());
+    }
+
+    #[test]
+    fn int_range() {
+        let r = IntRange::new(-10, 10);
+        assert_eq!(r.permutations(), 21);
+        // low values
+        assert_eq!(r.encode(&-10).unwrap(), 0);
+        assert_eq!(r.decode(0).unwrap(), -10);
         // mid values
         assert_eq!(r.encode(&0).unwrap(), 10);
         assert_eq!(r.decode(10).unwrap(), 0);
@@ -396,6 +2602,267 @@ mod tests {
         assert_eq!(r.decode(20).unwrap(), 10);
     }
 
+    #[test]
+    fn int_range_full() {
+        let r = IntRange::new_full();
+        assert_eq!(r.permutations(), Digit::MAX);
+        assert_eq!(r.encode(&(SignedDigit::MIN + 1)).unwrap(), 0);
+        assert_eq!(r.encode(&0).unwrap(), Digit::MAX / 2);
+        assert_eq!(r.encode(&SignedDigit::MAX).unwrap(), Digit::MAX - 1);
+        assert_eq!(r.decode(Digit::MAX - 1).unwrap(), SignedDigit::MAX);
+        assert_eq!(r.decode(0).unwrap(), SignedDigit::MIN + 1);
+        assert!(r.decode(Digit::MAX).is_err());
+        // Nearly the full range
+        let r = IntRange::new(SignedDigit::MIN + 2, SignedDigit::MAX - 1);
+        assert_eq!(r.permutations(), Digit::MAX - 2);
+        assert_eq!(r.encode(&(SignedDigit::MAX - 1)).unwrap(), Digit::MAX - 3);
+        assert!(r.encode(&SignedDigit::MAX).is_err());
+    }
+
+    #[test]
+    fn int_range_stepped() {
+        let r = IntRange::new_stepped(0, 1000, 25);
+        assert_eq!(r.permutations(), 41);
+        assert_eq!(r.encode(&0).unwrap(), 0);
+        assert_eq!(r.encode(&75).unwrap(), 3);
+        assert_eq!(r.encode(&1000).unwrap(), 40);
+        assert_eq!(r.decode(3).unwrap(), 75);
+        assert_eq!(r.decode(40).unwrap(), 1000);
+        assert_eq!(r.encode(&80), Err(Error::ValueNotOnStep {
+            value: String::from("80"),
+            min: String::from("0"),
+            step: String::from("25"),
+        }));
+        assert!(r.decode(41).is_err());
+        // Steps are counted from the minimum, max is clamped to the last step
+        let r = IntRange::new_stepped(-180, 179, 5);
+        assert_eq!(r.permutations(), 72);
+        assert_eq!(r.encode(&-180).unwrap(), 0);
+        assert_eq!(r.encode(&175).unwrap(), 71);
+        assert_eq!(r.decode(71).unwrap(), 175);
+        assert!(matches!(r.encode(&179), Err(Error::ValueOutOfRange { .. })));
+        let r = IntRange::new_stepped(-3, 100, 10);
+        assert_eq!(r.encode(&7).unwrap(), 1);
+        assert_eq!(r.decode(10).unwrap(), 97);
+        // Step larger than the range only leaves the minimum
+        let r = IntRange::new_stepped(10, 20, 50);
+        assert_eq!(r.permutations(), 1);
+        assert_eq!(r.encode(&10).unwrap(), 0);
+        assert!(r.encode(&20).is_err());
+        // Rounding to the nearest step
+        let r = IntRange::new_stepped(0, 1000, 25).with_rounding();
+        assert_eq!(r.encode(&80).unwrap(), 3);
+        assert_eq!(r.encode(&87).unwrap(), 3);
+        assert_eq!(r.encode(&88).unwrap(), 4);
+        assert_eq!(r.encode(&1000).unwrap(), 40);
+        assert!(r.encode(&1001).is_err());
+        let r = IntRange::new_stepped(0, 10, 4).with_rounding();
+        assert_eq!(r.encode(&8).unwrap(), 2);
+        assert_eq!(r.encode(&2).unwrap(), 1);
+        // Invalid steps
+        assert_eq!(IntRange::try_new_stepped(0, 10, 0).err(), Some(Error::ZeroStep));
+        assert!(IntRange::try_new_stepped(SignedDigit::MIN + 1, SignedDigit::MAX, 1).is_ok());
+        assert_eq!(IntRange::new_stepped(SignedDigit::MIN + 1, SignedDigit::MAX, 2).permutations(), Digit::MAX / 2 + 1);
+    }
+
+    #[test]
+    fn uint_range() {
+        let r = UIntRange::new(10, 30);
+        assert_eq!(r.permutations(), 21);
+        assert_eq!(r.encode(&10).unwrap(), 0);
+        assert_eq!(r.encode(&30).unwrap(), 20);
+        assert_eq!(r.decode(0).unwrap(), 10);
+        assert_eq!(r.decode(20).unwrap(), 30);
+        assert_eq!(r.encode(&9), Err(Error::ValueOutOfRange {
+            value: String::from("9"),
+            min: String::from("10"),
+            max: String::from("30"),
+        }));
+        assert!(r.encode(&31).is_err());
+        assert_eq!(r.decode(21), Err(Error::DecodeOutOfBounds { value: 21, permutations: 21 }));
+        assert!(matches!(UIntRange::try_new(5, 5), Err(Error::InvalidRange { .. })));
+        assert!(matches!(UIntRange::try_new(6, 5), Err(Error::InvalidRange { .. })));
+    }
+
+    #[test]
+    fn uint_range_full() {
+        // Every Digit value is one permutation too many
+        assert_eq!(UIntRange::try_new(0, Digit::MAX).err(), Some(Error::TooManyPermutations));
+        let r = UIntRange::new_full();
+        assert_eq!(r.permutations(), Digit::MAX);
+        assert_eq!(r.encode(&(Digit::MAX - 1)).unwrap(), Digit::MAX - 1);
+        assert!(r.encode(&Digit::MAX).is_err());
+        // Top of the range without zero
+        let r = UIntRange::new(1, Digit::MAX);
+        assert_eq!(r.permutations(), Digit::MAX);
+        assert_eq!(r.encode(&Digit::MAX).unwrap(), Digit::MAX - 1);
+        assert_eq!(r.decode(Digit::MAX - 1).unwrap(), Digit::MAX);
+        assert!(r.encode(&0).is_err());
+    }
+
+    #[test]
+    fn int_range_of() {
+        let r = IntRangeOf::new(u8::MIN, u8::MAX);
+        assert_eq!(r.permutations(), 256);
+        assert_eq!(r.encode(&0).unwrap(), 0);
+        assert_eq!(r.encode(&255).unwrap(), 255);
+        assert_eq!(r.decode(255).unwrap(), 255u8);
+        assert!(r.decode(256).is_err());
+        let r = IntRangeOf::new(i16::MIN, i16::MAX);
+        assert_eq!(r.permutations(), 65536);
+        assert_eq!(r.encode(&i16::MIN).unwrap(), 0);
+        assert_eq!(r.encode(&0).unwrap(), 32768);
+        assert_eq!(r.decode(65535).unwrap(), i16::MAX);
+        let r = IntRangeOf::new(-40i16, 125i16);
+        assert_eq!(r.encode(&-41), Err(Error::ValueOutOfRange {
+            value: String::from("-41"),
+            min: String::from("-40"),
+            max: String::from("125"),
+        }));
+        assert!(r.encode(&126).is_err());
+        let r = IntRangeOf::new(u32::MAX - 10, u32::MAX);
+        assert_eq!(r.permutations(), 11);
+        assert_eq!(r.encode(&u32::MAX).unwrap(), 10);
+        assert_eq!(r.decode(0).unwrap(), u32::MAX - 10);
+        assert!(r.encode(&0).is_err());
+        assert!(matches!(IntRangeOf::try_new(3u8, 3u8), Err(Error::InvalidRange { .. })));
+        assert_eq!(IntRangeOf::try_new(u64::MIN, u64::MAX).err(), Some(Error::TooManyPermutations));
+        assert_eq!(IntRangeOf::try_new(i64::MIN, i64::MAX).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    #[cfg(not(feature = "digit32"))]
+    fn int_range_of_wide() {
+        let r = IntRangeOf::new(u32::MIN, u32::MAX);
+        assert_eq!(r.permutations(), 1 << 32);
+        assert_eq!(r.decode((1 << 32) - 1).unwrap(), u32::MAX);
+        let r = IntRangeOf::new(i64::MIN + 1, i64::MAX);
+        assert_eq!(r.permutations(), Digit::MAX);
+        assert_eq!(r.encode(&i64::MAX).unwrap(), Digit::MAX - 1);
+        assert_eq!(r.decode(0).unwrap(), i64::MIN + 1);
+    }
+
+    #[test]
+    fn int128_range() {
+        let r = Int128Range::new(-10, 10);
+        let mut a = Accumulator::new();
+        r.encode_into(&-10, &mut a).unwrap();
+        r.encode_into(&10, &mut a).unwrap();
+        // Small ranges use the exact number of permutations
+        assert_eq!(a.div(21), 20);
+        assert_eq!(a.div(21), 0);
+        assert!(a.is_empty());
+        assert_eq!(r.encode_into(&11, &mut a), Err(Error::ValueOutOfRange {
+            value: String::from("11"),
+            min: String::from("-10"),
+            max: String::from("10"),
+        }));
+        assert!(matches!(Int128Range::try_new(1, 1), Err(Error::InvalidRange { .. })));
+        assert!(matches!(UInt128Range::try_new(2, 1), Err(Error::InvalidRange { .. })));
+        // Values above a Digit
+        let big = u64::MAX as i128 * 1000;
+        let r = Int128Range::new(-big, big);
+        for value in [-big, -big + 1, -1, 0, 1, u64::MAX as i128 + 1, big] {
+            r.encode_into(&value, &mut a).unwrap();
+            assert_eq!(r.decode_from(&mut a).unwrap(), value);
+            assert!(a.is_empty());
+        }
+        // Extremes of the full ranges
+        let r = Int128Range::new_full();
+        for value in [i128::MIN, i128::MIN + 1, 0, i128::MAX - 1, i128::MAX] {
+            r.encode_into(&value, &mut a).unwrap();
+            assert_eq!(r.decode_from(&mut a).unwrap(), value);
+            assert!(a.is_empty());
+        }
+        let r = UInt128Range::new_full();
+        for value in [0, 1, u64::MAX as u128, u64::MAX as u128 + 1, u128::MAX] {
+            r.encode_into(&value, &mut a).unwrap();
+            assert_eq!(r.decode_from(&mut a).unwrap(), value);
+            assert!(a.is_empty());
+        }
+        // Constrained range at the top of the u128 values
+        let r = UInt128Range::new(u128::MAX - (1 << 100), u128::MAX);
+        for value in [u128::MAX - (1 << 100), u128::MAX - 1, u128::MAX] {
+            r.encode_into(&value, &mut a).unwrap();
+            assert_eq!(r.decode_from(&mut a).unwrap(), value);
+            assert!(a.is_empty());
+        }
+        assert!(r.encode_into(&(u128::MAX - (1 << 100) - 1), &mut a).is_err());
+        // Data past the top of the range can not be decoded
+        r.encode_into(&u128::MAX, &mut a).unwrap();
+        a.add(1);
+        assert!(matches!(r.decode_from(&mut a), Err(Error::DecodeOutOfRange { .. })));
+    }
+
+    #[test]
+    fn fixed_point_range_full() {
+        let abs_max = SignedDigit::MAX as f64;
+        let r = FixedPointRange::new(-abs_max, abs_max, 0);
+        assert_eq!(r.permutations(), Digit::MAX);
+        assert_eq!(r.encode(&-abs_max).unwrap(), 0);
+        assert_eq!(r.encode(&abs_max).unwrap(), Digit::MAX - 1);
+        assert_eq!(r.decode(Digit::MAX - 1).unwrap(), abs_max);
+        // Nearly the full range
+        let r = FixedPointRange::new(-abs_max, 0.0, 0);
+        assert_eq!(r.permutations(), Digit::MAX / 2 + 1);
+        assert_eq!(r.encode(&0.0).unwrap(), Digit::MAX / 2);
+    }
+
+    #[test]
+    fn seq_variable_full_permutations() {
+        let spec = IntRange::new_full();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(3));
+        assert_eq!(sequencer.compress(&[0, 1], &mut Accumulator::new()), Err(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn int_range_try_new() {
+        let r = IntRange::try_new(-10, 10).unwrap();
+        assert_eq!(r.permutations(), 21);
+        assert_eq!(r.encode(&-10).unwrap(), 0);
+        assert_eq!(r.decode(20).unwrap(), 10);
+        assert_eq!(IntRange::try_new(SignedDigit::MIN, 0).err(), Some(Error::RangeExceedsLimit {
+            value: SignedDigit::MIN.to_string(),
+            limit: (SignedDigit::MIN + 1).to_string(),
+        }));
+        assert_eq!(IntRange::try_new(5, 5).err(), Some(Error::InvalidRange {
+            min: String::from("5"),
+            max: String::from("5"),
+        }));
+        assert!(matches!(IntRange::try_new(6, 5), Err(Error::InvalidRange { .. })));
+    }
+
+    #[test]
+    #[should_panic(expected = "Range min may not be greater than or equal to the max")]
+    fn int_range_new_panics() {
+        IntRange::new(10, -10);
+    }
+
+    #[test]
+    fn fixed_point_range_try_new() {
+        let r = FixedPointRange::try_new(-255.99, 255.99, 2).unwrap();
+        assert_eq!(r.permutations(), 2047);
+        assert_eq!(r.encode(&0.25).unwrap(), 1024);
+        let limit = (SignedDigit::MAX >> 2) as f64;
+        assert_eq!(FixedPointRange::try_new(-1.0, limit * 2.0, 2).err(), Some(Error::RangeExceedsLimit {
+            value: (limit * 2.0).to_string(),
+            limit: limit.to_string(),
+        }));
+        assert_eq!(FixedPointRange::try_new(-limit * 2.0, 1.0, 2).err(), Some(Error::RangeExceedsLimit {
+            value: (-limit * 2.0).to_string(),
+            limit: (-limit).to_string(),
+        }));
+        assert!(matches!(FixedPointRange::try_new(1.0, 1.0, 2), Err(Error::InvalidRange { .. })));
+        assert!(matches!(FixedPointRange::try_new(1.0, -1.0, 2), Err(Error::InvalidRange { .. })));
+        let max_decimals = SignedDigit::BITS - 1;
+        assert!(FixedPointRange::try_new(-0.5, 0.5, max_decimals - 1).is_ok());
+        assert_eq!(FixedPointRange::try_new(-0.5, 0.5, max_decimals).err(), Some(Error::InvalidDecimals(max_decimals)));
+        assert_eq!(FixedPointRange::try_new(-0.5, 0.5, 200).err(), Some(Error::InvalidDecimals(200)));
+        assert_eq!(FixedPointRange::try_new(f64::NAN, 0.5, 2).err(), Some(Error::NonFiniteValue));
+        assert_eq!(FixedPointRange::try_new(0.0, f64::INFINITY, 2).err(), Some(Error::NonFiniteValue));
+        assert_eq!(FixedPointRange::try_new(f64::NEG_INFINITY, 0.0, 2).err(), Some(Error::NonFiniteValue));
+    }
+
     #[test]
     fn fixed_point_range() {
         let r = FixedPointRange::new(-255.99, 255.99, 2);
@@ -413,6 +2880,90 @@ mod tests {
         assert_eq!(r.decode(2046).unwrap(), 255.75);
     }
 
+    #[test]
+    fn fixed_point_range_actual_bounds() {
+        for (min, max, decimals, actual_min, actual_max) in [
+            (-255.99, 255.99, 0, -255.0, 255.0),
+            (-255.99, 255.99, 2, -255.75, 255.75),
+            (-255.99, 255.99, 4, -255.9375, 255.9375),
+            (0.3, 0.7, 3, 0.25, 0.625),
+            (-1.0, 1.0, 8, -1.0, 1.0),
+        ] {
+            let r = FixedPointRange::try_new(min, max, decimals).unwrap();
+            assert_eq!(r.decimals(), decimals);
+            assert_eq!(r.actual_min(), actual_min);
+            assert_eq!(r.actual_max(), actual_max);
+            assert_eq!(r.decode(0), Ok(actual_min));
+            assert_eq!(r.decode(r.permutations() - 1), Ok(actual_max));
+        }
+        assert_eq!(FixedPointRange::try_new(0.1, 0.2, 2).err(), Some(Error::CollapsedRange {
+            min: String::from("0.1"),
+            max: String::from("0.2"),
+        }));
+        assert!(matches!(FixedPointRange::try_new(-0.9, 0.9, 0), Err(Error::CollapsedRange { .. })));
+        assert!(FixedPointRange::try_new(0.1, 0.3, 2).is_ok());
+    }
+
+    #[test]
+    fn fixed_point_range_non_finite() {
+        let r = FixedPointRange::new(-255.99, 255.99, 2);
+        assert_eq!(r.encode(&f64::NAN), Err(Error::NonFiniteValue));
+        assert_eq!(r.encode(&f64::INFINITY), Err(Error::NonFiniteValue));
+        assert_eq!(r.encode(&f64::NEG_INFINITY), Err(Error::NonFiniteValue));
+        // Subnormal numbers are finite and round to zero
+        let subnormal = f64::MIN_POSITIVE / 2.0;
+        assert!(subnormal.is_subnormal());
+        assert_eq!(r.encode(&subnormal).unwrap(), 1023);
+        assert_eq!(r.encode(&-subnormal).unwrap(), 1023);
+        // Out of range finite values are still range errors
+        assert!(matches!(r.encode(&f64::MAX), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(r.encode(&f64::MIN), Err(Error::ValueOutOfRange { .. })));
+        let sequencer = Sequencer::new(&r, SequenceLength::Fixed(2));
+        assert_eq!(
+            sequencer.compress(&[0.0, f64::NAN], &mut Accumulator::new()),
+            Err(Error::Element { index: 1, error: Box::new(Error::NonFiniteValue) })
+        );
+    }
+
+    #[test]
+    fn fixed_point_range_rounding() {
+        let spec = |rounding| FixedPointRange::new_with_rounding(-255.99, 255.99, 2, rounding);
+        let round = |rounding, value: f64| {
+            let r = spec(rounding);
+            r.encode(&value).and_then(|encoded| r.decode(encoded))
+        };
+        assert_eq!(spec(RoundingMode::Truncate).quantization_step(), 0.25);
+        // Default is to truncate
+        let r = FixedPointRange::new(-255.99, 255.99, 2);
+        assert_eq!(r.decode(r.encode(&-255.76).unwrap()), round(RoundingMode::Truncate, -255.76));
+        // Half step boundaries
+        for (value, truncate, nearest, floor, ceil) in [
+            (0.125, 0.0, 0.25, 0.0, 0.25),
+            (-0.125, 0.0, -0.25, -0.25, 0.0),
+            (0.1, 0.0, 0.0, 0.0, 0.25),
+            (-0.1, 0.0, 0.0, -0.25, 0.0),
+            (0.2, 0.0, 0.25, 0.0, 0.25),
+            (-0.2, 0.0, -0.25, -0.25, 0.0),
+            (0.25, 0.25, 0.25, 0.25, 0.25),
+            (-255.76, -255.75, -255.75, -256.0, -255.75),
+            (255.76, 255.75, 255.75, 255.75, 256.0),
+        ] {
+            assert_eq!(round(RoundingMode::Truncate, value), Ok(truncate), "truncate {}", value);
+            assert_eq!(round(RoundingMode::Nearest, value), Ok(nearest), "nearest {}", value);
+            if floor < -255.75 {
+                assert!(matches!(round(RoundingMode::Floor, value), Err(Error::ValueOutOfRange { .. })));
+            } else {
+                assert_eq!(round(RoundingMode::Floor, value), Ok(floor), "floor {}", value);
+            }
+            if ceil > 255.75 {
+                assert!(matches!(round(RoundingMode::Ceil, value), Err(Error::ValueOutOfRange { .. })));
+            } else {
+                assert_eq!(round(RoundingMode::Ceil, value), Ok(ceil), "ceil {}", value);
+            }
+        }
+        assert_eq!(FixedPointRange::new(0.0, 1.0, 10).quantization_step(), 1.0 / 1024.0);
+    }
+
     #[test]
     fn charset() {
         let cs = CharSet::new("abcあいうえお123$正體字");
@@ -445,6 +2996,389 @@ mod tests {
         assert!(e.decode(3).is_err());
     }
 
+    #[test]
+    fn charset_try_new() {
+        let runtime = String::from("xyz");
+        let cs = CharSet::try_new(&runtime).unwrap();
+        assert_eq!(cs.permutations(), 3);
+        for (ii, c) in runtime.chars().enumerate() {
+            assert_eq!(cs.encode(&c).unwrap(), ii as Digit);
+            assert_eq!(cs.decode(ii as Digit).unwrap(), c);
+        }
+        assert_eq!(CharSet::try_new("aabc").err(), Some(Error::DuplicateChar { value: 'a', index: 1, first: 0 }));
+        assert_eq!(CharSet::try_new("abあcあd").err(), Some(Error::DuplicateChar { value: 'あ', index: 4, first: 2 }));
+        assert_eq!(CharSet::try_new("abcda").err(), Some(Error::DuplicateChar { value: 'a', index: 4, first: 0 }));
+    }
+
+    #[test]
+    fn charset_from_ranges() {
+        let cs = CharSet::from_ranges(&[('a', 'z'), ('0', '9')], "_-");
+        let literal = CharSet::new("abcdefghijklmnopqrstuvwxyz0123456789_-");
+        assert_eq!(cs.permutations(), 38);
+        for ii in 0..literal.permutations() {
+            let c = literal.decode(ii).unwrap();
+            assert_eq!(cs.encode(&c), Ok(ii));
+            assert_eq!(cs.decode(ii), Ok(c));
+        }
+        assert_eq!(CharSet::from_ranges(&[('x', 'x')], "").permutations(), 1);
+        // Hiragana block plus fullwidth digits
+        let cs = CharSet::from_ranges(&[('\u{3040}', '\u{309F}'), ('０', '９')], "〇");
+        assert_eq!(cs.permutations(), 0x60 + 10 + 1);
+        assert_eq!(cs.encode(&'\u{3040}'), Ok(0));
+        assert_eq!(cs.encode(&'あ'), Ok(2));
+        assert_eq!(cs.encode(&'\u{309F}'), Ok(0x5F));
+        assert_eq!(cs.decode(0x60), Ok('０'));
+        assert_eq!(cs.decode(0x69), Ok('９'));
+        assert_eq!(cs.decode(0x6A), Ok('〇'));
+        // Ranges across the surrogate gap only contain valid characters
+        assert_eq!(CharSet::from_ranges(&[('\u{D7FF}', '\u{E000}')], "").permutations(), 2);
+        assert_eq!(
+            CharSet::try_from_ranges(&[('z', 'a')], "").err(),
+            Some(Error::InvalidRange { min: String::from("z"), max: String::from("a") })
+        );
+        assert_eq!(
+            CharSet::try_from_ranges(&[('a', 'm'), ('k', 'z')], "").err(),
+            Some(Error::DuplicateChar { value: 'k', index: 13, first: 10 })
+        );
+        assert_eq!(
+            CharSet::try_from_ranges(&[('0', '9')], "+5").err(),
+            Some(Error::DuplicateChar { value: '5', index: 11, first: 5 })
+        );
+    }
+
+    #[test]
+    fn charset_case_insensitive() {
+        let cs = CharSet::case_insensitive("ABCXYZ0123-ÄΣ");
+        assert_eq!(cs.permutations(), 13);
+        assert_eq!(cs.encode(&'a'), Ok(0));
+        assert_eq!(cs.encode(&'A'), Ok(0));
+        assert_eq!(cs.encode(&'z'), cs.encode(&'Z'));
+        assert_eq!(cs.encode(&'ä'), Ok(11));
+        assert_eq!(cs.encode(&'σ'), Ok(12));
+        assert_eq!(cs.encode(&'1'), Ok(7));
+        assert_eq!(cs.decode(0), Ok('A'));
+        assert_eq!(cs.decode(11), Ok('Ä'));
+        assert_eq!(cs.encode(&'d'), Err(Error::CharNotInSet('d')));
+        let text: Vec<char> = "abc-0xZy".chars().collect();
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&cs, SequenceLength::Fixed(8));
+        sequencer.compress(&text, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), "ABC-0XZY".chars().collect::<Vec<char>>());
+        // Both cases of a letter are ambiguous, plain repeats are still duplicates
+        assert_eq!(CharSet::try_case_insensitive("ABCa").err(), Some(Error::AmbiguousCase { value: 'a', index: 3, first: 0 }));
+        assert_eq!(CharSet::try_case_insensitive("ABCB").err(), Some(Error::DuplicateChar { value: 'B', index: 3, first: 1 }));
+        assert!(CharSet::try_new("ABCa").is_ok());
+        assert_eq!(CharSet::new("ABC").encode(&'a'), Err(Error::CharNotInSet('a')));
+    }
+
+    #[test]
+    fn charset_builtin() {
+        // Pinned indices, changing any of these breaks existing payloads
+        type Pinned = (CharSet, Digit, &'static [(char, Digit)]);
+        let pinned: &[Pinned] = &[
+            (CharSet::lowercase_letter(), 26, &[('a', 0), ('z', 25)]),
+            (CharSet::uppercase_letter(), 26, &[('A', 0), ('Z', 25)]),
+            (CharSet::lowercase_ascii(), 51, &[('a', 0), (' ', 26), ('?', 29), ('0', 30), ('(', 40), ('"', 50)]),
+            (CharSet::uppercase_ascii(), 51, &[('A', 0), (' ', 26), ('?', 29), ('0', 30), ('(', 40), ('"', 50)]),
+            (CharSet::ascii_printable(), 95, &[(' ', 0), ('0', 16), ('A', 33), ('a', 65), ('~', 94)]),
+            (CharSet::digits(), 10, &[('0', 0), ('5', 5), ('9', 9)]),
+            (CharSet::hex_lower(), 16, &[('0', 0), ('9', 9), ('a', 10), ('f', 15)]),
+            (CharSet::hex_upper(), 16, &[('0', 0), ('9', 9), ('A', 10), ('F', 15)]),
+            (CharSet::base64url(), 64, &[('A', 0), ('Z', 25), ('a', 26), ('z', 51), ('0', 52), ('9', 61), ('-', 62), ('_', 63)]),
+            (CharSet::alphanumeric(), 62, &[('0', 0), ('9', 9), ('A', 10), ('Z', 35), ('a', 36), ('z', 61)]),
+        ];
+        for (cs, permutations, chars) in pinned {
+            assert_eq!(cs.permutations(), *permutations);
+            for (c, index) in chars.iter() {
+                assert_eq!(cs.encode(c), Ok(*index));
+                assert_eq!(cs.decode(*index), Ok(*c));
+            }
+        }
+        assert!(CharSet::hex_lower().encode(&'A').is_err());
+        assert!(CharSet::hex_upper().encode(&'a').is_err());
+        assert!(CharSet::base64url().encode(&'+').is_err());
+        assert!(CharSet::ascii_printable().encode(&'\n').is_err());
+    }
+
+    #[test]
+    fn charset_builder() {
+        let built = CharSetBuilder::new()
+            .add_range('a', 'f')
+            .add_charset(&CharSet::new("0123"))
+            .add_str(" .")
+            .build()
+            .unwrap();
+        let ranges = CharSet::from_ranges(&[('a', 'f'), ('0', '3')], " .");
+        assert_eq!(built.permutations(), 12);
+        let mut a = Accumulator::new();
+        let mut b = Accumulator::new();
+        let text: Vec<char> = "face 0.1 bad 32".chars().collect();
+        let sequencer = Sequencer::new(&built, SequenceLength::Variable(20));
+        sequencer.compress(&text, &mut a).unwrap();
+        Sequencer::new(&ranges, SequenceLength::Variable(20)).compress(&text, &mut b).unwrap();
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), text);
+        // Duplicates are an error unless dropped, keeping the first
+        let parts = || CharSetBuilder::new().add_str("abc").add_range('b', 'e').add_charset(&CharSet::new("a!"));
+        assert_eq!(parts().build().err(), Some(Error::DuplicateChar { value: 'b', index: 3, first: 1 }));
+        let deduped = parts().dedup_silently(true).build().unwrap();
+        assert_eq!(deduped.permutations(), 6);
+        for (ii, c) in "abcde!".chars().enumerate() {
+            assert_eq!(deduped.encode(&c), Ok(ii as Digit));
+        }
+        assert_eq!(
+            CharSetBuilder::new().add_range('9', '0').dedup_silently(true).build().err(),
+            Some(Error::InvalidRange { min: String::from("9"), max: String::from("0") })
+        );
+        assert_eq!(CharSetBuilder::new().build().unwrap().permutations(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to add duplicate characters to CharSet data")]
+    fn charset_new_panics() {
+        CharSet::new("abca");
+    }
+
+    #[test]
+    fn tenum_try_new() {
+        let runtime: Vec<String> = ["Red", "Green", "Blue"].iter().map(|s| s.to_string()).collect();
+        let options: Vec<&str> = runtime.iter().map(|s| s.as_str()).collect();
+        let e = Enum::try_new(&options).unwrap();
+        assert_eq!(e.permutations(), 3);
+        for (ii, option) in runtime.iter().enumerate() {
+            assert_eq!(e.encode(option).unwrap(), ii as Digit);
+            assert_eq!(&e.decode(ii as Digit).unwrap(), option);
+        }
+        let duplicate = |value: &str, index, first| Some(Error::DuplicateOption {
+            value: String::from(value), index, first,
+        });
+        assert_eq!(Enum::try_new(&["Red", "Red", "Blue"]).err(), duplicate("Red", 1, 0));
+        assert_eq!(Enum::try_new(&["Red", "Green", "Green", "Blue"]).err(), duplicate("Green", 2, 1));
+        assert_eq!(Enum::try_new(&["Red", "Green", "Blue", "Red"]).err(), duplicate("Red", 3, 0));
+    }
+
+    #[test]
+    fn tenum_from_vec() {
+        let runtime: Vec<String> = "Banana,Orange,Apple".split(',').map(String::from).collect();
+        let owned = Enum::from_vec(runtime.clone());
+        let literal = Enum::new(&["Banana", "Orange", "Apple"]);
+        assert_eq!(owned.permutations(), literal.permutations());
+        for (ii, option) in runtime.iter().enumerate() {
+            assert_eq!(owned.encode(option), literal.encode(option));
+            assert_eq!(owned.encode(option), Ok(ii as Digit));
+            assert_eq!(owned.decode(ii as Digit), literal.decode(ii as Digit));
+        }
+        assert_eq!(owned.encode(&String::from("Mango")), literal.encode(&String::from("Mango")));
+        let runtime: Vec<String> = "Banana,Orange,Banana".split(',').map(String::from).collect();
+        assert_eq!(
+            Enum::try_from_vec(runtime).err(),
+            Some(Error::DuplicateOption { value: String::from("Banana"), index: 2, first: 0 })
+        );
+        assert_eq!(Enum::from_vec(Vec::new()).permutations(), 0);
+    }
+
+    #[test]
+    fn tenum_with_aliases() {
+        let e = Enum::with_aliases(
+            &["New York", "California", "Texas"],
+            &[("NY", "New York"), ("new york", "New York"), ("CA", "California"), ("Calif.", "California")],
+        );
+        assert_eq!(e.permutations(), 3);
+        for alias in ["New York", "NY", "new york"] {
+            assert_eq!(e.encode(&String::from(alias)), Ok(0));
+        }
+        assert_eq!(e.encode(&String::from("CA")), e.encode(&String::from("California")));
+        assert_eq!(e.encode(&String::from("Calif.")), Ok(1));
+        assert_eq!(e.decode(e.encode(&String::from("NY")).unwrap()), Ok(String::from("New York")));
+        assert_eq!(e.encode(&String::from("TX")), Err(Error::UnknownEnumVariant(String::from("TX"))));
+        assert_eq!(
+            Enum::try_with_aliases(&["New York", "NY"], &[("NY", "New York")]).err(),
+            Some(Error::DuplicateAlias(String::from("NY")))
+        );
+        assert_eq!(
+            Enum::try_with_aliases(&["New York", "Nevada"], &[("NY", "New York"), ("NY", "Nevada")]).err(),
+            Some(Error::DuplicateAlias(String::from("NY")))
+        );
+        assert_eq!(
+            Enum::try_with_aliases(&["New York"], &[("TX", "Texas")]).err(),
+            Some(Error::UnknownEnumVariant(String::from("Texas")))
+        );
+        assert!(matches!(Enum::try_with_aliases(&["A", "A"], &[]), Err(Error::DuplicateOption { .. })));
+    }
+
+    #[test]
+    fn versioned_enum() {
+        let v1 = VersionedEnum::new(&["ok", "warning", "error"], 16);
+        let mut v2 = VersionedEnum::new(&["ok", "warning", "error"], 16);
+        v2.append_option("offline").unwrap();
+        v2.append_option("degraded").unwrap();
+        assert_eq!(v1.permutations(), 16);
+        assert_eq!(v2.permutations(), 16);
+        // Old payloads decode with the new version
+        let statuses: Vec<String> = ["error", "ok", "warning"].iter().map(|s| String::from(*s)).collect();
+        let mut a = Accumulator::new();
+        Sequencer::new(&v1, SequenceLength::Variable(4)).compress(&statuses, &mut a).unwrap();
+        assert_eq!(Sequencer::new(&v2, SequenceLength::Variable(4)).decompress(&mut a).unwrap(), statuses);
+        // New payloads decode with the old version unless they use new options
+        for status in ["ok", "warning", "error"] {
+            let status = String::from(status);
+            assert_eq!(v1.decode(v2.encode(&status).unwrap()), Ok(status));
+        }
+        assert_eq!(v2.encode(&String::from("degraded")), Ok(4));
+        assert_eq!(v1.decode(4), Err(Error::UnknownVariantIndex(4)));
+        assert_eq!(v2.decode(5), Err(Error::UnknownVariantIndex(5)));
+        assert!(matches!(v2.decode(16), Err(Error::DecodeOutOfBounds { .. })));
+        assert_eq!(v1.encode(&String::from("degraded")), Err(Error::UnknownEnumVariant(String::from("degraded"))));
+        // No room left
+        let mut full = VersionedEnum::new(&["a", "b"], 3);
+        full.append_option("c").unwrap();
+        assert_eq!(full.append_option("d"), Err(Error::CapacityExceeded { capacity: 3 }));
+        assert_eq!(full.permutations(), 3);
+        // The capacity must have room for every option
+        assert_eq!(
+            VersionedEnum::try_new(&["a", "b", "c"], 2).err(),
+            Some(Error::InsufficientCapacity { options: 3, capacity: 2 }),
+        );
+        assert_eq!(
+            VersionedEnum::try_new(&[], 0).err(),
+            Some(Error::InsufficientCapacity { options: 0, capacity: 0 }),
+        );
+        assert_eq!(VersionedEnum::new(&["a", "b", "c"], 3).permutations(), 3);
+        assert_eq!(full.append_option("a"), Err(Error::CapacityExceeded { capacity: 3 }));
+        let mut e = VersionedEnum::new(&["a", "b"], 4);
+        assert_eq!(e.append_option("a"), Err(Error::DuplicateOption { value: String::from("a"), index: 2, first: 0 }));
+        assert_eq!(e.decode(2), Err(Error::UnknownVariantIndex(2)));
+    }
+
+    #[test]
+    fn weighted_enum() {
+        let spec = WeightedEnum::new(&[("ok", 90), ("warning", 7), ("error", 2), ("catastrophic_failure", 1)]);
+        let mut a = Accumulator::new();
+        for option in ["ok", "warning", "error", "catastrophic_failure"] {
+            spec.encode_into(&String::from(option), &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(String::from(option)));
+            assert!(a.is_empty());
+        }
+        assert_eq!(spec.encode_into(&String::from("fine"), &mut a), Err(Error::UnknownEnumVariant(String::from("fine"))));
+        assert!(a.is_empty());
+        assert_eq!(WeightedEnum::try_new(&[("a", 1), ("b", 0)]).err(), Some(Error::ZeroOptionWeight(String::from("b"))));
+        assert_eq!(
+            WeightedEnum::try_new(&[("a", 1), ("b", 2), ("a", 3)]).err(),
+            Some(Error::DuplicateOption { value: String::from("a"), index: 2, first: 0 }),
+        );
+        assert!(WeightedEnum::new(&[]).decode_from(&mut a).is_err());
+    }
+
+    #[test]
+    fn seq_weighted_enum() {
+        let weights = &[("ok", 90), ("warning", 7), ("error", 2), ("catastrophic_failure", 1)];
+        let weighted = WeightedEnum::new(weights);
+        let uniform = Enum::new(&["ok", "warning", "error", "catastrophic_failure"]);
+        let mut values: Vec<String> = (0..96).map(|ii| String::from(if ii % 12 == 5 { "warning" } else { "ok" })).collect();
+        values.extend(["error", "ok", "catastrophic_failure", "error"].iter().map(|v| String::from(*v)));
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&weighted, SequenceLength::Fixed(values.len()));
+        sequencer.compress(&values, &mut a).unwrap();
+        let weighted_bytes = a.to_bytes().len();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+        let mut b = Accumulator::new();
+        Sequencer::new(&uniform, SequenceLength::Fixed(values.len())).compress(&values, &mut b).unwrap();
+        // 8 bytes instead of 24
+        assert!(weighted_bytes * 2 < b.to_bytes().len());
+        // The rarest option is the worst case
+        assert_eq!(weighted.max_bits(), Some(libm::log2(100.0)));
+        assert_eq!(WeightedEnum::new(&[]).max_bits(), None);
+        let rarest = vec![String::from("catastrophic_failure"); 16];
+        let sequencer = Sequencer::new(&weighted, SequenceLength::Fixed(rarest.len()));
+        sequencer.compress(&rarest, &mut a).unwrap();
+        assert!(a.to_bytes().len() <= sequencer.max_bytes().unwrap());
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), rarest);
+    }
+
+    #[test]
+    fn bit_flags() {
+        let spec = BitFlagsSpec::new(&["wifi", "gps", "bt", "nfc"]);
+        assert_eq!(spec.permutations(), 16);
+        let flags = |names: &[&str]| names.iter().map(|name| String::from(*name)).collect::<BTreeSet<String>>();
+        assert_eq!(spec.encode(&flags(&[])), Ok(0));
+        assert_eq!(spec.encode(&flags(&["wifi"])), Ok(1));
+        assert_eq!(spec.encode(&flags(&["nfc", "gps"])), Ok(10));
+        assert_eq!(spec.encode(&flags(&["wifi", "gps", "bt", "nfc"])), Ok(15));
+        for value in 0..16 {
+            assert_eq!(spec.encode(&spec.decode(value).unwrap()), Ok(value));
+        }
+        assert_eq!(spec.decode(10), Ok(flags(&["gps", "nfc"])));
+        assert!(spec.decode(6).unwrap().contains("bt"));
+        assert_eq!(spec.decode(16), Err(Error::DecodeOutOfBounds { value: 16, permutations: 16 }));
+        assert_eq!(spec.encode(&flags(&["gps", "lte"])), Err(Error::UnknownEnumVariant(String::from("lte"))));
+        assert_eq!(
+            BitFlagsSpec::try_new(&["a", "b", "a"]).err(),
+            Some(Error::DuplicateOption { value: String::from("a"), index: 2, first: 0 }),
+        );
+        let names: Vec<String> = (0..Digit::BITS).map(|ii| ii.to_string()).collect();
+        let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        let full = BitFlagsSpec::new(&names[1..]);
+        assert_eq!(full.permutations(), 1 << (Digit::BITS - 1));
+        assert_eq!(full.encode(&full.decode(full.permutations() - 1).unwrap()), Ok(full.permutations() - 1));
+        assert_eq!(BitFlagsSpec::try_new(&names).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn enum_of() {
+        let resolutions = EnumOf::new(vec![(640u16, 480u16), (1280, 720), (1920, 1080), (3840, 2160)]);
+        assert_eq!(resolutions.permutations(), 4);
+        assert_eq!(resolutions.encode(&(640, 480)), Ok(0));
+        assert_eq!(resolutions.encode(&(3840, 2160)), Ok(3));
+        assert_eq!(resolutions.decode(2), Ok((1920, 1080)));
+        assert_eq!(resolutions.encode(&(800, 600)), Err(Error::UnknownEnumVariant(String::from("(800, 600)"))));
+        assert!(resolutions.decode(4).is_err());
+        let gateways = EnumOf::new(vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(192, 168, 1, 1)]);
+        assert_eq!(gateways.encode(&Ipv4Addr::new(192, 168, 1, 1)), Ok(1));
+        assert_eq!(gateways.decode(0), Ok(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(
+            EnumOf::try_new(vec![1u8, 2, 3, 2]).err(),
+            Some(Error::DuplicateOption { value: String::from("2"), index: 3, first: 1 })
+        );
+        let mut a = Accumulator::new();
+        let values = [(1280, 720), (640, 480), (1280, 720), (3840, 2160)];
+        let sequencer = Sequencer::new(&resolutions, SequenceLength::Variable(8));
+        sequencer.compress(&values, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to add duplicate strings to Enum data")]
+    fn tenum_new_panics() {
+        Enum::new(&["Banana", "Banana"]);
+    }
+
+    #[test]
+    fn errors() {
+        let b = Bool::new();
+        assert_eq!(b.decode(2), Err(Error::DecodeOutOfBounds { value: 2, permutations: 2 }));
+        let r = IntRange::new(-10, 10);
+        assert_eq!(r.encode(&11), Err(Error::ValueOutOfRange {
+            value: String::from("11"),
+            min: String::from("-10"),
+            max: String::from("10"),
+        }));
+        assert_eq!(r.decode(21), Err(Error::DecodeOutOfBounds { value: 21, permutations: 21 }));
+        let r = FixedPointRange::new(-1.0, 1.0, 1);
+        assert_eq!(r.encode(&-1.5), Err(Error::ValueOutOfRange {
+            value: String::from("-1.5"),
+            min: String::from("-1"),
+            max: String::from("1"),
+        }));
+        let cs = CharSet::lowercase_letter();
+        assert_eq!(cs.encode(&'A'), Err(Error::CharNotInSet('A')));
+        assert_eq!(cs.decode(26), Err(Error::DecodeOutOfBounds { value: 26, permutations: 26 }));
+        let e = Enum::new(&["Banana", "Orange"]);
+        assert_eq!(e.encode(&String::from("Mango")), Err(Error::UnknownEnumVariant(String::from("Mango"))));
+        assert_eq!(e.decode(2), Err(Error::DecodeOutOfBounds { value: 2, permutations: 2 }));
+    }
+
     #[test]
     fn seq_bool() {
         // Fixed length sequence
@@ -453,14 +3387,14 @@ mod tests {
         let seq = &[false, true, true, false, true];
         // Sequence as fixed
         let sequencer0 = Sequencer::new(&spec, SequenceLength::Fixed(5));
-        sequencer0.compress(seq, &mut a);
+        sequencer0.compress(seq, &mut a).unwrap();
         // Sequence as variable length
         let sequencer1 = Sequencer::new(&spec, SequenceLength::Variable(20));
-        sequencer1.compress(seq, &mut a);
+        sequencer1.compress(seq, &mut a).unwrap();
         // Decompress and compare
-        let deseq = sequencer1.decompress(&mut a);
+        let deseq = sequencer1.decompress(&mut a).unwrap();
         assert_eq!(deseq.as_slice(), seq);
-        let deseq = sequencer0.decompress(&mut a);
+        let deseq = sequencer0.decompress(&mut a).unwrap();
         assert_eq!(deseq.as_slice(), seq);
     }
 
@@ -474,18 +3408,18 @@ mod tests {
             &spec0,
             SequenceLength::Variable(50),
         );
-        sequencer0.compress(seq, &mut a);
+        sequencer0.compress(seq, &mut a).unwrap();
         // Sequence as fixed
         let spec1 = IntRange::new(-2, 20);
         let sequencer1 = Sequencer::new(
             &spec1,
             SequenceLength::Fixed(4),
         );
-        sequencer1.compress(seq, &mut a);
+        sequencer1.compress(seq, &mut a).unwrap();
         // Decompress
-        let deseq = sequencer1.decompress(&mut a);
+        let deseq = sequencer1.decompress(&mut a).unwrap();
         assert_eq!(deseq.as_slice(), seq);
-        let deseq = sequencer0.decompress(&mut a);
+        let deseq = sequencer0.decompress(&mut a).unwrap();
         assert_eq!(deseq.as_slice(), seq);
     }
 
@@ -499,18 +3433,1018 @@ mod tests {
             &spec,
             SequenceLength::Fixed(6),
         );
-        sequencer0.compress(seq0, &mut a);
+        sequencer0.compress(seq0, &mut a).unwrap();
         // Sequence 1
         let seq1: &[f64] = &[0.0, 0.125, -255.875, 255.875];
         let sequencer1 = Sequencer::new(
             &spec,
             SequenceLength::Variable(100),
         );
-        sequencer1.compress(seq1, &mut a);
+        sequencer1.compress(seq1, &mut a).unwrap();
         // Decompress
-        let deseq = sequencer1.decompress(&mut a);
+        let deseq = sequencer1.decompress(&mut a).unwrap();
         assert_eq!(deseq.as_slice(), seq1);
-        let deseq = sequencer0.decompress(&mut a);
+        let deseq = sequencer0.decompress(&mut a).unwrap();
         assert_eq!(deseq.as_slice(), seq0);
     }
+
+    #[test]
+    fn seq_constant() {
+        let mut a = Accumulator::new();
+        let bool_spec = Bool::new();
+        let const_spec = Enum::new(&["Constant"]);
+        let int_spec = IntRange::new(-5, 5);
+        assert_eq!(const_spec.permutations(), 1);
+        let bools = &[true, false, true];
+        let consts = &[String::from("Constant"), String::from("Constant")];
+        let ints = &[-5, 0, 5, 2];
+        let sequencer0 = Sequencer::new(&bool_spec, SequenceLength::Fixed(3));
+        let sequencer1 = Sequencer::new(&const_spec, SequenceLength::Fixed(2));
+        let sequencer2 = Sequencer::new(&const_spec, SequenceLength::Variable(5));
+        let sequencer3 = Sequencer::new(&int_spec, SequenceLength::Fixed(4));
+        sequencer0.compress(bools, &mut a).unwrap();
+        // Fixed sequences of a constant do not change the accumulator
+        let before = a.to_hex_str();
+        sequencer1.compress(consts, &mut a).unwrap();
+        assert_eq!(a.to_hex_str(), before);
+        sequencer2.compress(consts, &mut a).unwrap();
+        sequencer3.compress(ints, &mut a).unwrap();
+        // Decompress in reverse order
+        assert_eq!(sequencer3.decompress(&mut a).unwrap().as_slice(), ints);
+        assert_eq!(sequencer2.decompress(&mut a).unwrap().as_slice(), consts);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap().as_slice(), consts);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap().as_slice(), bools);
+        assert_eq!(a.len(), 0);
+    }
+
+    #[test]
+    fn seq_compress_errors() {
+        let spec = IntRange::new(-5, 5);
+        let out_of_range = |index, value: &str| Err(Error::Element {
+            index,
+            error: Box::new(Error::ValueOutOfRange {
+                value: String::from(value),
+                min: String::from("-5"),
+                max: String::from("5"),
+            }),
+        });
+        let mut a = Accumulator::new();
+        Sequencer::new(&Bool::new(), SequenceLength::Fixed(3)).compress(&[true, false, true], &mut a).unwrap();
+        let before = a.to_hex_str();
+        // Errors in fixed length sequences
+        let fixed = Sequencer::new(&spec, SequenceLength::Fixed(4));
+        assert_eq!(fixed.compress(&[6, 0, 1, 2], &mut a), out_of_range(0, "6"));
+        assert_eq!(fixed.compress(&[0, 1, -6, 2], &mut a), out_of_range(2, "-6"));
+        assert_eq!(fixed.compress(&[0, 1, 2, 10], &mut a), out_of_range(3, "10"));
+        assert_eq!(a.to_hex_str(), before);
+        // Errors in variable length sequences
+        let variable = Sequencer::new(&spec, SequenceLength::Variable(4));
+        assert_eq!(variable.compress(&[0, 7], &mut a), out_of_range(1, "7"));
+        assert_eq!(variable.compress(&[0, 1, 2, 3, 4], &mut a), Err(Error::SequenceTooLong { len: 5, max: 4 }));
+        assert_eq!(a.to_hex_str(), before);
+        // Errors for a single permutation spec
+        let const_spec = Enum::new(&["Constant"]);
+        let consts = Sequencer::new(&const_spec, SequenceLength::Fixed(2));
+        assert_eq!(
+            consts.compress(&[String::from("Constant"), String::from("Other")], &mut a),
+            Err(Error::Element { index: 1, error: Box::new(Error::UnknownEnumVariant(String::from("Other"))) })
+        );
+        assert_eq!(a.to_hex_str(), before);
+        // The accumulator is still usable after an error
+        fixed.compress(&[0, 1, 2, 3], &mut a).unwrap();
+        assert_eq!(fixed.decompress(&mut a).unwrap(), [0, 1, 2, 3]);
+    }
+
+    /// Spec which only decodes even values, to simulate corrupted data
+    struct Even {}
+
+    impl DataSpec<Digit> for Even {
+        fn permutations(&self) -> Digit {
+            8
+        }
+
+        fn encode(&self, input: &Digit) -> Result<Digit, Error> {
+            Ok(*input)
+        }
+
+        fn decode(&self, value: Digit) -> Result<Digit, Error> {
+            if value.is_multiple_of(2) {
+                Ok(value)
+            } else {
+                Err(Error::DecodeOutOfBounds { value, permutations: 8 })
+            }
+        }
+    }
+
+    #[test]
+    fn seq_decompress_errors() {
+        let spec = Even {};
+        let odd = |index, value| Err(Error::DecodeElement {
+            index,
+            value,
+            error: Box::new(Error::DecodeOutOfBounds { value, permutations: 8 }),
+        });
+        // Corrupted fixed length sequence
+        let fixed = Sequencer::new(&spec, SequenceLength::Fixed(4));
+        let mut a = Accumulator::new();
+        fixed.compress(&[0, 2, 4, 6], &mut a).unwrap();
+        a.add(8 * 8); // Element 1 becomes 3
+        let before = a.to_hex_str();
+        assert_eq!(fixed.decompress(&mut a), odd(1, 3));
+        assert_eq!(a.to_hex_str(), before);
+        // Corrupted variable length sequence
+        let variable = Sequencer::new(&spec, SequenceLength::Variable(4));
+        let mut a = Accumulator::new();
+        variable.compress(&[0, 2, 4], &mut a).unwrap();
+        a.add(9); // Element 1 becomes 3
+        let before = a.to_hex_str();
+        assert_eq!(variable.decompress(&mut a), odd(1, 3));
+        assert_eq!(a.to_hex_str(), before);
+        // Variable length sequence without an end marker
+        let mut a = Accumulator::new();
+        for _ in 0..5 {
+            a.mul(9);
+            a.add(1);
+        }
+        let before = a.to_hex_str();
+        assert_eq!(variable.decompress(&mut a), Err(Error::UnterminatedSequence { max: 4 }));
+        assert_eq!(a.to_hex_str(), before);
+    }
+
+    #[test]
+    fn seq_variable_max_length() {
+        let mut a = Accumulator::new();
+        let spec = IntRange::new(-5, 5);
+        let sequencer0 = Sequencer::new(&spec, SequenceLength::Fixed(2));
+        let sequencer1 = Sequencer::new(&spec, SequenceLength::Variable(3));
+        sequencer0.compress(&[-5, 5], &mut a).unwrap();
+        sequencer1.compress(&[1, 2, 3], &mut a).unwrap();
+        sequencer1.compress(&[], &mut a).unwrap();
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), []);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), [1, 2, 3]);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), [-5, 5]);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn seq_fixed_length_mismatch() {
+        let mut a = Accumulator::new();
+        let spec = IntRange::new(-5, 5);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(5));
+        assert_eq!(sequencer.compress(&[1, 2, 3], &mut a), Err(Error::LengthMismatch { expected: 5, actual: 3 }));
+        assert_eq!(sequencer.compress(&[1, 2, 3, 4, 5, 0, 0], &mut a), Err(Error::LengthMismatch { expected: 5, actual: 7 }));
+        assert!(a.is_empty());
+        sequencer.compress(&[1, 2, 3, 4, 5], &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), [1, 2, 3, 4, 5]);
+        // Single permutation specs are checked too
+        let const_spec = Enum::new(&["Constant"]);
+        let sequencer = Sequencer::new(&const_spec, SequenceLength::Fixed(2));
+        let consts = [String::from("Constant")];
+        assert_eq!(sequencer.compress(&consts, &mut a), Err(Error::LengthMismatch { expected: 2, actual: 1 }));
+    }
+
+    #[test]
+    fn seq_exhausted() {
+        let spec = IntRange::new(0, 9);
+        let fixed = Sequencer::new(&spec, SequenceLength::Fixed(3));
+        let variable = Sequencer::new(&spec, SequenceLength::Variable(3));
+        // Happy path, including zeros at the start of the data
+        let mut a = Accumulator::new_guarded();
+        fixed.compress(&[0, 0, 1], &mut a).unwrap();
+        variable.compress(&[0, 5], &mut a).unwrap();
+        let mut b = Accumulator::from_bytes_guarded(&a.to_bytes());
+        assert_eq!(variable.decompress(&mut b).unwrap(), [0, 5]);
+        assert_eq!(b.assert_empty(), Err(Error::TrailingData { len: 1 }));
+        assert_eq!(fixed.decompress(&mut b).unwrap(), [0, 0, 1]);
+        assert!(b.is_exhausted());
+        assert_eq!(b.assert_empty(), Ok(()));
+        // Truncated payload, reading more fields than were compressed
+        let mut a = Accumulator::new_guarded();
+        fixed.compress(&[0, 0, 1], &mut a).unwrap();
+        assert_eq!(fixed.decompress(&mut a).unwrap(), [0, 0, 1]);
+        assert_eq!(fixed.decompress(&mut a), Err(Error::Exhausted { index: 2 }));
+        let mut a = Accumulator::new_guarded();
+        assert_eq!(variable.decompress(&mut a), Err(Error::Exhausted { index: 0 }));
+        // Extra trailing data
+        let mut a = Accumulator::new_guarded();
+        fixed.compress(&[3, 4, 5], &mut a).unwrap();
+        fixed.compress(&[6, 7, 8], &mut a).unwrap();
+        assert_eq!(fixed.decompress(&mut a).unwrap(), [6, 7, 8]);
+        assert!(!a.is_exhausted());
+        assert!(a.assert_empty().is_err());
+        // Unguarded accumulators can not detect running out of data
+        let mut a = Accumulator::new();
+        fixed.compress(&[0, 0, 1], &mut a).unwrap();
+        assert_eq!(fixed.decompress(&mut a).unwrap(), [0, 0, 1]);
+        assert_eq!(fixed.decompress(&mut a).unwrap(), [0, 0, 0]);
+        assert_eq!(a.assert_empty(), Ok(()));
+    }
+
+    /// Spec without any permutations
+    struct Empty {}
+
+    impl DataSpec<Digit> for Empty {
+        fn permutations(&self) -> Digit {
+            0
+        }
+
+        fn encode(&self, _input: &Digit) -> Result<Digit, Error> {
+            Ok(0)
+        }
+
+        fn decode(&self, _value: Digit) -> Result<Digit, Error> {
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn seq_zero_permutations() {
+        let spec = Empty {};
+        let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(2));
+        let mut a = Accumulator::new();
+        a.add(5);
+        assert_eq!(sequencer.compress(&[0, 0], &mut a), Err(Error::MultiplyByZero));
+        assert_eq!(sequencer.decompress(&mut a), Err(Error::DivideByZero));
+        assert_eq!(a.div(10), 5);
+    }
+
+    #[test]
+    fn seq_uint_range() {
+        let mut a = Accumulator::new();
+        let low = &[0, 1, 2, 0, 5];
+        let high = &[Digit::MAX, Digit::MAX - 1, Digit::MAX - 1000, 1];
+        let spec0 = UIntRange::new(0, 5);
+        let spec1 = UIntRange::new(1, Digit::MAX);
+        let sequencer0 = Sequencer::new(&spec0, SequenceLength::Fixed(5));
+        let sequencer1 = Sequencer::new(&spec1, SequenceLength::Variable(10));
+        let sequencer2 = Sequencer::new(&spec1, SequenceLength::Fixed(4));
+        sequencer0.compress(low, &mut a).unwrap();
+        sequencer2.compress(high, &mut a).unwrap();
+        sequencer0.compress(low, &mut a).unwrap();
+        let full = UIntRange::new_full();
+        let sequencer3 = Sequencer::new(&full, SequenceLength::Fixed(3));
+        sequencer3.compress(&[0, Digit::MAX - 1, 12345], &mut a).unwrap();
+        assert!(sequencer1.compress(high, &mut a).is_err()); // Too many permutations for variable
+        assert_eq!(sequencer3.decompress(&mut a).unwrap(), [0, Digit::MAX - 1, 12345]);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), low);
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), high);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), low);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn seq_int_range_of() {
+        let mut a = Accumulator::new();
+        let ports: &[u8] = &[0, 22, 80, 255];
+        let temperatures: &[i16] = &[-40, -1, 0, 21, 125];
+        let counts: &[u32] = &[u32::MAX, 1, 2, u32::MAX - 1];
+        let port_spec = IntRangeOf::new(u8::MIN, u8::MAX);
+        let temperature_spec = IntRangeOf::new(-40, 125);
+        let count_spec = IntRangeOf::new(1, u32::MAX);
+        let sequencer0 = Sequencer::new(&port_spec, SequenceLength::Fixed(4));
+        let sequencer1 = Sequencer::new(&temperature_spec, SequenceLength::Variable(10));
+        let sequencer2 = Sequencer::new(&count_spec, SequenceLength::Fixed(4));
+        sequencer0.compress(ports, &mut a).unwrap();
+        sequencer1.compress(temperatures, &mut a).unwrap();
+        sequencer2.compress(counts, &mut a).unwrap();
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), counts);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), temperatures);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), ports);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn seq_int128_range() {
+        let mut a = Accumulator::new();
+        let ints = &[-5, 0, 5];
+        let wide: &[i128] = &[i128::MIN, -(u64::MAX as i128) - 1, 0, u64::MAX as i128 + 1, i128::MAX];
+        let uwide: &[u128] = &[u128::MAX, 1 << 64, 1 << 100];
+        let int_spec = IntRange::new(-5, 5);
+        let wide_spec = Int128Range::new_full();
+        let uwide_spec = UInt128Range::new(1 << 64, u128::MAX);
+        let sequencer0 = Sequencer::new(&int_spec, SequenceLength::Fixed(3));
+        let sequencer1 = Sequencer::new(&wide_spec, SequenceLength::Fixed(5));
+        let sequencer2 = Sequencer::new(&uwide_spec, SequenceLength::Variable(5));
+        sequencer0.compress(ints, &mut a).unwrap();
+        sequencer1.compress(wide, &mut a).unwrap();
+        sequencer2.compress(uwide, &mut a).unwrap();
+        sequencer2.compress(&[], &mut a).unwrap();
+        sequencer0.compress(ints, &mut a).unwrap();
+        assert_eq!(sequencer1.compress(&[0, 1], &mut a), Err(Error::LengthMismatch { expected: 5, actual: 2 }));
+        assert_eq!(sequencer2.compress(&[0, 1 << 64], &mut a), Err(Error::Element {
+            index: 0,
+            error: Box::new(Error::ValueOutOfRange {
+                value: String::from("0"),
+                min: (1u128 << 64).to_string(),
+                max: u128::MAX.to_string(),
+            }),
+        }));
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), ints);
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), []);
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), uwide);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), wide);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), ints);
+        assert!(a.is_empty());
+        // Variable length without an end marker
+        let mut a = Accumulator::new();
+        for _ in 0..6 {
+            uwide_spec.encode_into(&u128::MAX, &mut a).unwrap();
+            a.mul(2);
+            a.add(1);
+        }
+        assert_eq!(sequencer2.decompress(&mut a), Err(Error::UnterminatedSequence { max: 5 }));
+        // Running out of data
+        let mut a = Accumulator::new_guarded();
+        sequencer1.compress(wide, &mut a).unwrap();
+        sequencer1.decompress(&mut a).unwrap();
+        assert_eq!(sequencer2.decompress(&mut a), Err(Error::Exhausted { index: 0 }));
+    }
+
+    #[test]
+    fn seq_int_range_stepped() {
+        let mut a = Accumulator::new();
+        let prices = &[0, 25, 975, 1000, 500];
+        let angles = &[-180, 0, 5, 175];
+        let price_spec = IntRange::new_stepped(0, 1000, 25);
+        let angle_spec = IntRange::new_stepped(-180, 179, 5);
+        let sequencer0 = Sequencer::new(&price_spec, SequenceLength::Fixed(5));
+        let sequencer1 = Sequencer::new(&angle_spec, SequenceLength::Variable(8));
+        sequencer0.compress(prices, &mut a).unwrap();
+        sequencer1.compress(angles, &mut a).unwrap();
+        assert!(sequencer1.compress(&[1], &mut a).is_err());
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), angles);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), prices);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn seq_nested() {
+        let spec = IntRange::new(-1000, 1000);
+        let inner = Sequencer::new(&spec, SequenceLength::Variable(5));
+        let variable = Sequencer::new(&inner, SequenceLength::Variable(4));
+        let fixed = Sequencer::new(&inner, SequenceLength::Fixed(3));
+        let days: Vec<Vec<SignedDigit>> = vec![vec![1, -2, 3], vec![], vec![1000, -1000, 0, 0, 7], vec![5]];
+        let empty: Vec<Vec<SignedDigit>> = vec![];
+        let even: Vec<Vec<SignedDigit>> = vec![vec![], vec![], vec![]];
+        let mut a = Accumulator::new();
+        variable.compress(&days, &mut a).unwrap();
+        variable.compress(&empty, &mut a).unwrap();
+        fixed.compress(&days[..3], &mut a).unwrap();
+        fixed.compress(&even, &mut a).unwrap();
+        assert_eq!(fixed.decompress(&mut a).unwrap(), even);
+        assert_eq!(fixed.decompress(&mut a).unwrap(), days[..3]);
+        assert_eq!(variable.decompress(&mut a).unwrap(), empty);
+        assert_eq!(variable.decompress(&mut a).unwrap(), days);
+        assert!(a.is_empty());
+        // Errors give the index in each level
+        let bad = vec![vec![1], vec![2, 3, 1001]];
+        assert_eq!(variable.compress(&bad, &mut a), Err(Error::Element {
+            index: 1,
+            error: Box::new(Error::Element {
+                index: 2,
+                error: Box::new(Error::ValueOutOfRange {
+                    value: String::from("1001"),
+                    min: String::from("-1000"),
+                    max: String::from("1000"),
+                }),
+            }),
+        }));
+        let long = vec![vec![0; 6]];
+        assert!(matches!(variable.compress(&long, &mut a), Err(Error::Element { index: 0, .. })));
+        assert!(variable.compress(&vec![vec![]; 5], &mut a).is_err());
+        assert!(a.is_empty());
+        // Three levels
+        let outer = Sequencer::new(&variable, SequenceLength::Variable(2));
+        let nested = vec![days.clone(), empty.clone(), vec![vec![-7]]];
+        assert!(outer.compress(&nested, &mut a).is_err());
+        outer.compress(&nested[..2], &mut a).unwrap();
+        assert_eq!(outer.decompress(&mut a).unwrap(), nested[..2]);
+        assert!(a.is_empty());
+    }
+
+    /// Sequencer that owns its spec, built by a function
+    fn digit_string(length: usize) -> Sequencer<'static, char> {
+        Sequencer::owned(CharSet::digits(), SequenceLength::Variable(length))
+    }
+
+    /// Sequencers of boxed specs only known at runtime
+    fn bits(wide: bool) -> Sequencer<'static, Digit> {
+        let spec: Box<dyn WideDataSpec<Digit>> = match wide {
+            true => Box::new(UIntRange::new(0, 1023)),
+            false => Box::new(UIntRange::new(0, 15)),
+        };
+        Sequencer::boxed(spec, SequenceLength::Fixed(2))
+    }
+
+    #[test]
+    fn seq_owned() {
+        struct Registry {
+            pins: Sequencer<'static, char>,
+            samples: Vec<Sequencer<'static, Digit>>,
+        }
+        let registry = Registry { pins: digit_string(8), samples: vec![bits(false), bits(true)] };
+        let pin: Vec<char> = "4096".chars().collect();
+        let mut a = Accumulator::new();
+        registry.pins.compress(&pin, &mut a).unwrap();
+        registry.samples[0].compress(&[15, 0], &mut a).unwrap();
+        registry.samples[1].compress(&[1023, 512], &mut a).unwrap();
+        assert!(registry.samples[0].compress(&[16, 0], &mut a).is_err());
+        assert_eq!(registry.samples[1].decompress(&mut a).unwrap(), [1023, 512]);
+        assert_eq!(registry.samples[0].decompress(&mut a).unwrap(), [15, 0]);
+        assert_eq!(registry.pins.decompress(&mut a).unwrap(), pin);
+        assert!(a.is_empty());
+        // Owned and borrowed Sequencers of the same spec compress the same
+        let spec = CharSet::digits();
+        let borrowed = Sequencer::borrowed(&spec, SequenceLength::Variable(8));
+        borrowed.compress(&pin, &mut a).unwrap();
+        let bytes = a.to_bytes();
+        assert_eq!(registry.pins.decompress(&mut a).unwrap(), pin);
+        registry.pins.compress(&pin, &mut a).unwrap();
+        assert_eq!(a.to_bytes(), bytes);
+        // Owned nested Sequencers
+        let nested = Sequencer::owned(digit_string(4), SequenceLength::Fixed(2));
+        let codes = vec![vec!['1', '2'], vec![]];
+        let mut a = Accumulator::new();
+        nested.compress(&codes, &mut a).unwrap();
+        assert_eq!(nested.decompress(&mut a).unwrap(), codes);
+    }
+
+    #[test]
+    fn seq_compress_iter() {
+        let spec = UIntRange::new(0, 100);
+        let fixed = Sequencer::new(&spec, SequenceLength::Fixed(5));
+        let variable = Sequencer::new(&spec, SequenceLength::Variable(8));
+        let mut a = Accumulator::new();
+        let mut b = Accumulator::new();
+        // Compresses the same as a slice
+        assert_eq!(fixed.compress_iter((0..50).filter(|ii| ii % 10 == 3).map(|ii| ii * 2), &mut a), Ok(5));
+        fixed.compress(&[6, 26, 46, 66, 86], &mut b).unwrap();
+        assert_eq!(variable.compress_iter((1..4).map(|ii| ii * ii), &mut a), Ok(3));
+        variable.compress(&[1, 4, 9], &mut b).unwrap();
+        assert_eq!(variable.compress_iter(core::iter::empty::<Digit>(), &mut a), Ok(0));
+        variable.compress(&[], &mut b).unwrap();
+        let values: Vec<Digit> = vec![100, 0, 50];
+        assert_eq!(variable.compress_iter(values.iter(), &mut a), Ok(3));
+        variable.compress(&values, &mut b).unwrap();
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(variable.decompress(&mut a).unwrap(), values);
+        assert_eq!(variable.decompress(&mut a).unwrap(), []);
+        assert_eq!(variable.decompress(&mut a).unwrap(), [1, 4, 9]);
+        assert_eq!(fixed.decompress(&mut a).unwrap(), [6, 26, 46, 66, 86]);
+        assert!(a.is_empty());
+        // Too many or too few values, only one more value than the length is
+        // taken from the iterator
+        let mut taken = 0;
+        let counted = (0..100).inspect(|_| taken += 1);
+        assert_eq!(fixed.compress_iter(counted, &mut a), Err(Error::LengthMismatch { expected: 5, actual: 6 }));
+        assert_eq!(taken, 6);
+        assert_eq!(fixed.compress_iter(0..4, &mut a), Err(Error::LengthMismatch { expected: 5, actual: 4 }));
+        assert_eq!(variable.compress_iter(0..100, &mut a), Err(Error::SequenceTooLong { len: 9, max: 8 }));
+        assert!(matches!(variable.compress_iter([1, 200, 3], &mut a), Err(Error::Element { index: 1, .. })));
+        assert!(a.is_empty());
+        // Wide specs
+        let mut b = Accumulator::new();
+        let wide = UInt128Range::new(0, u128::MAX);
+        let wide_variable = Sequencer::new(&wide, SequenceLength::Variable(4));
+        let wide_fixed = Sequencer::new(&wide, SequenceLength::Fixed(2));
+        assert_eq!(wide_variable.compress_iter((0..3).map(|ii| u128::MAX >> ii), &mut a), Ok(3));
+        wide_variable.compress(&[u128::MAX, u128::MAX >> 1, u128::MAX >> 2], &mut b).unwrap();
+        assert_eq!(wide_fixed.compress_iter([1u128 << 100, 7].iter(), &mut a), Ok(2));
+        wide_fixed.compress(&[1 << 100, 7], &mut b).unwrap();
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(wide_fixed.decompress(&mut a).unwrap(), [1 << 100, 7]);
+        assert_eq!(wide_variable.decompress(&mut a).unwrap(), [u128::MAX, u128::MAX >> 1, u128::MAX >> 2]);
+        assert_eq!(wide_fixed.compress_iter(0..3u128, &mut a), Err(Error::LengthMismatch { expected: 2, actual: 3 }));
+        assert_eq!(wide_variable.compress_iter(0..5u128, &mut a), Err(Error::SequenceTooLong { len: 5, max: 4 }));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn seq_decompress_iter() {
+        let spec = IntRange::new(-100, 100);
+        let wide = UInt128Range::new(0, u128::MAX);
+        let values: Vec<SignedDigit> = vec![-100, 7, 0, 100, 42];
+        let wide_values: Vec<u128> = vec![u128::MAX, 0, 1 << 90];
+        let sequencers = [
+            Sequencer::new(&spec, SequenceLength::Fixed(5)),
+            Sequencer::new(&spec, SequenceLength::Variable(8)),
+        ];
+        let wide_sequencers = [
+            Sequencer::new(&wide, SequenceLength::Fixed(3)),
+            Sequencer::new(&wide, SequenceLength::Variable(3)),
+        ];
+        for sequencer in &sequencers {
+            let mut a = Accumulator::new();
+            sequencer.compress(&values, &mut a).unwrap();
+            let mut b = a.clone();
+            let mut decompressed: Vec<SignedDigit> = sequencer.decompress_iter(&mut a).map(Result::unwrap).collect();
+            assert!(a.is_empty());
+            decompressed.reverse();
+            assert_eq!(decompressed, sequencer.decompress(&mut b).unwrap());
+        }
+        for sequencer in &wide_sequencers {
+            let mut a = Accumulator::new();
+            sequencer.compress(&wide_values, &mut a).unwrap();
+            let mut decompressed: Vec<u128> = sequencer.decompress_iter(&mut a).map(Result::unwrap).collect();
+            assert!(a.is_empty());
+            decompressed.reverse();
+            assert_eq!(decompressed, wide_values);
+        }
+        // Stopping early leaves the rest of the sequence in the accumulator
+        let mut a = Accumulator::new();
+        sequencers[0].compress(&values, &mut a).unwrap();
+        let last: Vec<SignedDigit> = sequencers[0].decompress_iter(&mut a).take(2).map(Result::unwrap).collect();
+        assert_eq!(last, [42, 100]);
+        let rest = Sequencer::new(&spec, SequenceLength::Fixed(3));
+        assert_eq!(rest.decompress(&mut a).unwrap(), [-100, 7, 0]);
+        let mut a = Accumulator::new();
+        sequencers[1].compress(&[], &mut a).unwrap();
+        assert_eq!(sequencers[1].decompress_iter(&mut a).count(), 0);
+        assert!(a.is_empty());
+        // Errors end the iterator
+        let short = Sequencer::new(&spec, SequenceLength::Variable(3));
+        let mut a = Accumulator::new();
+        sequencers[1].compress(&values, &mut a).unwrap();
+        let mut iter = short.decompress_iter(&mut a);
+        assert_eq!(iter.next(), Some(Ok(42)));
+        assert_eq!(iter.next(), Some(Ok(100)));
+        assert_eq!(iter.next(), Some(Ok(0)));
+        assert_eq!(iter.next(), Some(Err(Error::UnterminatedSequence { max: 3 })));
+        assert_eq!(iter.next(), None);
+        // Constant specs read nothing, even from an exhausted accumulator
+        let constant = Sequencer::owned(ConstSpec::new(7), SequenceLength::Fixed(3));
+        let mut a = Accumulator::new_guarded();
+        constant.compress(&[7, 7, 7], &mut a).unwrap();
+        let mut b = a.clone();
+        let decompressed: Vec<i32> = constant.decompress_iter(&mut a).map(Result::unwrap).collect();
+        assert_eq!(decompressed, constant.decompress(&mut b).unwrap());
+        assert_eq!(decompressed, [7, 7, 7]);
+        let coded = Sequencer::new(&Bool {}, SequenceLength::Fixed(2));
+        let mut a = Accumulator::new();
+        a.add(3);
+        let mut iter = coded.decompress_iter(&mut a);
+        assert_eq!(iter.next(), Some(Ok(true)));
+        assert_eq!(iter.next(), Some(Ok(true)));
+        assert_eq!(iter.next(), None);
+    }
+
+    /// Check that the estimate is an upper bound of the bytes, and at most one
+    /// Digit more
+    fn assert_tight(estimate: usize, bytes: usize) {
+        assert!(bytes <= estimate, "{} bytes over the estimate of {}", bytes, estimate);
+        assert!(estimate - bytes <= (Digit::BITS / 8) as usize, "{} bytes for an estimate of {}", bytes, estimate);
+    }
+
+    #[test]
+    fn seq_max_bits() {
+        let spec = IntRange::new(-100, 100);
+        let worst: Vec<SignedDigit> = vec![100; 8];
+        let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(8));
+        assert_eq!(sequencer.max_bytes(), Some(8));
+        let mut a = Accumulator::new();
+        sequencer.compress(&worst, &mut a).unwrap();
+        assert_tight(sequencer.max_bytes().unwrap(), a.to_bytes().len());
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(8));
+        let mut a = Accumulator::new();
+        sequencer.compress(&worst, &mut a).unwrap();
+        assert_tight(sequencer.max_bytes().unwrap(), a.to_bytes().len());
+        // The end of sequence marker takes space once there is data below it
+        let mut a = Accumulator::new();
+        a.add(1);
+        sequencer.compress(&worst, &mut a).unwrap();
+        assert_tight(sequencer.max_bytes().unwrap(), a.to_bytes().len());
+        let bools = vec![true; 100];
+        let sequencer = Sequencer::new(&Bool {}, SequenceLength::Variable(100));
+        let mut a = Accumulator::new();
+        sequencer.compress(&bools, &mut a).unwrap();
+        assert_tight(sequencer.max_bytes().unwrap(), a.to_bytes().len());
+        let sequencer = Sequencer::new(&Bool {}, SequenceLength::Fixed(100));
+        assert_eq!(sequencer.max_bits(), Some(100.0));
+        let mut a = Accumulator::new();
+        sequencer.compress(&bools, &mut a).unwrap();
+        assert_eq!(a.to_bytes().len(), sequencer.max_bytes().unwrap());
+        let sequencer = Sequencer::owned(ConstSpec::new(7), SequenceLength::Fixed(100));
+        assert_eq!(sequencer.max_bytes(), Some(0));
+        // Wide specs and sequences of sequences
+        let wide = UInt128Range::new_full();
+        let worst = vec![u128::MAX; 3];
+        for length in [SequenceLength::Fixed(3), SequenceLength::Variable(3)] {
+            let sequencer = Sequencer::new(&wide, length);
+            let mut a = Accumulator::new();
+            sequencer.compress(&worst, &mut a).unwrap();
+            assert_tight(sequencer.max_bytes().unwrap(), a.to_bytes().len());
+        }
+        let narrow = Int128Range::new(0, (1 << 70) - 1);
+        assert_eq!(narrow.max_bits(), Some(70.0));
+        let inner = Sequencer::new(&spec, SequenceLength::Variable(4));
+        let outer = Sequencer::new(&inner, SequenceLength::Variable(3));
+        let worst = vec![vec![100; 4]; 3];
+        let mut a = Accumulator::new();
+        outer.compress(&worst, &mut a).unwrap();
+        assert_tight(outer.max_bytes().unwrap(), a.to_bytes().len());
+        // Specs without an upper bound
+        let unbounded = crate::data::VarUInt::new(16);
+        assert_eq!(Sequencer::new(&unbounded, SequenceLength::Fixed(2)).max_bits(), None);
+    }
+
+    #[test]
+    fn map_sequencer() {
+        let flags = MapSequencer::new(
+            Enum::new(&["dark_mode", "beta", "telemetry", "sync"]),
+            Bool::new(),
+            SequenceLength::Variable(4),
+        );
+        let mut a = Accumulator::new();
+        let empty: BTreeMap<String, bool> = BTreeMap::new();
+        flags.compress(&empty, &mut a).unwrap();
+        assert_eq!(flags.decompress(&mut a).unwrap(), empty);
+        assert!(a.is_empty());
+        let full: BTreeMap<String, bool> = [("dark_mode", true), ("beta", false), ("telemetry", false), ("sync", true)]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        flags.compress(&full, &mut a).unwrap();
+        let bytes = a.to_bytes();
+        assert!(bytes.len() <= flags.max_bytes().unwrap());
+        assert_eq!(flags.decompress(&mut a).unwrap(), full);
+        assert!(a.is_empty());
+        // Wide keys with sequences of values
+        let channels = MapSequencer::new(
+            UInt128Range::new_full(),
+            Sequencer::owned(IntRange::new(-10, 10), SequenceLength::Variable(3)),
+            SequenceLength::Fixed(2),
+        );
+        let settings: BTreeMap<u128, Vec<SignedDigit>> = [(u128::MAX, vec![-10, 0]), (7, vec![])].into_iter().collect();
+        channels.compress(&settings, &mut a).unwrap();
+        flags.compress(&full, &mut a).unwrap();
+        assert_eq!(flags.decompress(&mut a).unwrap(), full);
+        assert_eq!(channels.decompress(&mut a).unwrap(), settings);
+        assert!(a.is_empty());
+        // Errors give the component and the index of the entry
+        let too_many: BTreeMap<u128, Vec<SignedDigit>> = (0..3).map(|key| (key, vec![])).collect();
+        assert_eq!(channels.compress(&too_many, &mut a), Err(Error::Component {
+            index: 0,
+            error: Box::new(Error::LengthMismatch { expected: 2, actual: 3 }),
+        }));
+        let bad_value: BTreeMap<u128, Vec<SignedDigit>> = [(0, vec![]), (1, vec![11])].into_iter().collect();
+        assert_eq!(channels.compress(&bad_value, &mut a), Err(Error::Component {
+            index: 1,
+            error: Box::new(Error::Element {
+                index: 1,
+                error: Box::new(Error::Element {
+                    index: 0,
+                    error: Box::new(Error::ValueOutOfRange {
+                        value: String::from("11"),
+                        min: String::from("-10"),
+                        max: String::from("10"),
+                    }),
+                }),
+            }),
+        }));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn map_sequencer_duplicate_key() {
+        let keys = Enum::new(&["a", "b"]);
+        let map = MapSequencer::new(Enum::new(&["a", "b"]), IntRange::new(0, 9), SequenceLength::Variable(3));
+        // A corrupted map with the same key twice
+        let mut a = Accumulator::new();
+        Sequencer::new(&IntRange::new(0, 9), SequenceLength::Fixed(3)).compress(&[1, 2, 3], &mut a).unwrap();
+        let corrupted = vec![String::from("a"), String::from("b"), String::from("a")];
+        Sequencer::new(&keys, SequenceLength::Variable(3)).compress(&corrupted, &mut a).unwrap();
+        let bytes = a.to_bytes();
+        assert_eq!(map.decompress(&mut a), Err(Error::Component {
+            index: 0,
+            error: Box::new(Error::DuplicateElement { value: String::from("\"a\""), index: 2, first: 0 }),
+        }));
+        assert_eq!(a.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn delta_sequencer() {
+        let ids = IntRange::new(0, 1_000_000_000);
+        // Increasing IDs with small gaps
+        let values: Vec<SignedDigit> = (0..50).scan(123_456_789, |id, ii| {
+            *id += ii * 37 % 100;
+            Some(*id)
+        }).collect();
+        let sequencer = DeltaSequencer::new(IntRange::new(0, 1_000_000_000), IntRange::new(0, 255), SequenceLength::Variable(64));
+        let plain = Sequencer::new(&ids, SequenceLength::Variable(64));
+        let mut a = Accumulator::new();
+        sequencer.compress(&values, &mut a).unwrap();
+        let mut b = Accumulator::new();
+        plain.compress(&values, &mut b).unwrap();
+        // 53 bytes instead of 187
+        assert_eq!(a.to_bytes().len(), 53);
+        assert_eq!(b.to_bytes().len(), 187);
+        assert!(a.to_bytes().len() <= sequencer.max_bytes().unwrap());
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+        // Zero and maximum deltas, and empty sequences
+        let edges = vec![1_000_000_000 - 510, 1_000_000_000 - 510, 1_000_000_000 - 255, 1_000_000_000];
+        for values in [edges.clone(), vec![7], vec![]] {
+            sequencer.compress(&values, &mut a).unwrap();
+            assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+            assert!(a.is_empty());
+        }
+        let fixed = DeltaSequencer::new(IntRange::new(0, 1_000_000_000), IntRange::new(0, 255), SequenceLength::Fixed(4));
+        fixed.compress(&edges, &mut a).unwrap();
+        assert_eq!(fixed.decompress(&mut a).unwrap(), edges);
+        assert!(a.is_empty());
+        assert_eq!(fixed.compress(&edges[..3], &mut a), Err(Error::LengthMismatch { expected: 4, actual: 3 }));
+        let prefixed = DeltaSequencer::new(IntRange::new(0, 1_000_000_000), IntRange::new(0, 255), SequenceLength::Prefixed(4));
+        for values in [edges.clone(), vec![]] {
+            prefixed.compress(&values, &mut a).unwrap();
+            assert!(a.to_bytes().len() <= prefixed.max_bytes().unwrap());
+            assert_eq!(prefixed.decompress(&mut a).unwrap(), values);
+            assert!(a.is_empty());
+        }
+        // Deltas that decrease or are too large
+        let descending = vec![10, 20, 15];
+        assert_eq!(sequencer.compress(&descending, &mut a), Err(Error::Element {
+            index: 2,
+            error: Box::new(Error::ValueOutOfRange { value: String::from("-5"), min: String::from("0"), max: String::from("255") }),
+        }));
+        let signed = DeltaSequencer::new(IntRange::new(-100, 100), IntRange::new(-10, 10), SequenceLength::Variable(8));
+        signed.compress(&descending, &mut a).unwrap();
+        assert_eq!(signed.decompress(&mut a).unwrap(), descending);
+        assert_eq!(signed.compress(&[0, 11], &mut a), Err(Error::Element {
+            index: 1,
+            error: Box::new(Error::ValueOutOfRange { value: String::from("11"), min: String::from("-10"), max: String::from("10") }),
+        }));
+        assert!(a.is_empty());
+        // The max length only limits the number of values
+        let unbounded = DeltaSequencer::new(IntRange::new(0, 1000), IntRange::new(0, 10), SequenceLength::Variable(usize::MAX));
+        unbounded.compress(&[5, 7, 7, 17], &mut a).unwrap();
+        assert_eq!(unbounded.decompress(&mut a).unwrap(), [5, 7, 7, 17]);
+        assert!(a.is_empty());
+        // Valid deltas that decompress to a value outside of the value spec
+        let small = DeltaSequencer::new(IntRange::new(0, 1000), IntRange::new(0, 100), SequenceLength::Fixed(2));
+        a.add(100);
+        a.mul(1001);
+        a.add(990);
+        let bytes = a.to_bytes();
+        assert_eq!(small.decompress(&mut a), Err(Error::Element {
+            index: 1,
+            error: Box::new(Error::ValueOutOfRange { value: String::from("1090"), min: String::from("0"), max: String::from("1000") }),
+        }));
+        assert_eq!(a.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn seq_prefixed() {
+        let bools: Vec<bool> = (0..100).map(|ii| ii % 3 == 0).collect();
+        let prefixed = Sequencer::new(&Bool {}, SequenceLength::Prefixed(100));
+        let variable = Sequencer::new(&Bool {}, SequenceLength::Variable(100));
+        let mut a = Accumulator::new();
+        prefixed.compress(&bools, &mut a).unwrap();
+        let mut b = Accumulator::new();
+        variable.compress(&bools, &mut b).unwrap();
+        // 14 bytes instead of 20
+        assert_eq!(a.to_bytes().len(), 14);
+        assert_eq!(b.to_bytes().len(), 20);
+        assert!(a.to_bytes().len() <= prefixed.max_bytes().unwrap());
+        let mut c = Accumulator::new();
+        assert_eq!(prefixed.compress_iter(&bools, &mut c), Ok(100));
+        assert_eq!(c.to_bytes(), a.to_bytes());
+        let mut decompressed: Vec<bool> = prefixed.decompress_iter(&mut c).map(Result::unwrap).collect();
+        decompressed.reverse();
+        assert_eq!(decompressed, bools);
+        assert!(c.is_empty());
+        assert_eq!(prefixed.decompress(&mut a).unwrap(), bools);
+        assert!(a.is_empty());
+        // Empty, max length and too long
+        prefixed.compress(&[], &mut a).unwrap();
+        assert!(a.is_empty());
+        assert_eq!(prefixed.decompress(&mut a).unwrap(), Vec::<bool>::new());
+        let short = Sequencer::new(&Bool {}, SequenceLength::Prefixed(99));
+        assert_eq!(short.compress(&bools, &mut a), Err(Error::SequenceTooLong { len: 100, max: 99 }));
+        assert_eq!(short.compress_iter(&bools, &mut a), Err(Error::SequenceTooLong { len: 100, max: 99 }));
+        assert!(a.is_empty());
+        // Specs that use every permutation of a Digit, and wide specs
+        let full = UIntRange::new_full();
+        let digits: Vec<Digit> = vec![Digit::MAX - 1, 0, 7];
+        let prefixed = Sequencer::new(&full, SequenceLength::Prefixed(3));
+        assert_eq!(Sequencer::new(&full, SequenceLength::Variable(3)).compress(&digits, &mut a), Err(Error::TooManyPermutations));
+        prefixed.compress(&digits, &mut a).unwrap();
+        assert_eq!(prefixed.decompress(&mut a).unwrap(), digits);
+        assert!(a.is_empty());
+        let wide = UInt128Range::new_full();
+        let values: Vec<u128> = vec![u128::MAX, 1 << 100];
+        let prefixed = Sequencer::new(&wide, SequenceLength::Prefixed(4));
+        prefixed.compress(&values, &mut a).unwrap();
+        assert!(a.to_bytes().len() <= prefixed.max_bytes().unwrap());
+        assert_eq!(prefixed.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn seq_padding() {
+        let charset = CharSet::lowercase_ascii();
+        let padded = Sequencer::new(&charset, SequenceLength::Fixed(10)).with_padding(' ');
+        let stripped = Sequencer::new(&charset, SequenceLength::Fixed(10)).with_padding(' ').with_stripping();
+        let plain = Sequencer::new(&charset, SequenceLength::Fixed(10));
+        let chars = |text: &str| text.chars().collect::<Vec<char>>();
+        // Exact length is not padded
+        let mut a = Accumulator::new();
+        let mut b = Accumulator::new();
+        padded.compress(&chars("abcdefghij"), &mut a).unwrap();
+        plain.compress(&chars("abcdefghij"), &mut b).unwrap();
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(stripped.decompress(&mut a).unwrap(), chars("abcdefghij"));
+        // Short sequences are the same as padding them by hand
+        let mut b = Accumulator::new();
+        padded.compress(&chars("adamas"), &mut a).unwrap();
+        plain.compress(&chars("adamas    "), &mut b).unwrap();
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(padded.compress_iter(chars("adamas"), &mut a), Ok(6));
+        assert_eq!(stripped.decompress(&mut a).unwrap(), chars("adamas"));
+        assert_eq!(padded.decompress(&mut a).unwrap(), chars("adamas    "));
+        assert!(a.is_empty());
+        // Only trailing fill values are stripped
+        stripped.compress(&chars(" a b"), &mut a).unwrap();
+        let mut c = a.clone();
+        assert_eq!(stripped.decompress(&mut a).unwrap(), chars(" a b"));
+        let reversed: Vec<char> = stripped.decompress_iter(&mut c).map(Result::unwrap).collect();
+        assert_eq!(reversed, chars("b a "));
+        assert!(c.is_empty());
+        stripped.compress(&[], &mut a).unwrap();
+        assert_eq!(stripped.decompress(&mut a).unwrap(), Vec::<char>::new());
+        assert!(a.is_empty());
+        // Long sequences and fill values that are not in the spec
+        assert_eq!(padded.compress(&chars("abcdefghijk"), &mut a), Err(Error::LengthMismatch { expected: 10, actual: 11 }));
+        let letters = CharSet::lowercase_letter();
+        let bad_fill = Sequencer::new(&letters, SequenceLength::Fixed(4)).with_padding(' ');
+        assert_eq!(bad_fill.compress(&chars("ab"), &mut a), Err(Error::Element {
+            index: 2,
+            error: Box::new(Error::CharNotInSet(' ')),
+        }));
+        assert!(a.is_empty());
+        // Wide specs, and lengths that are not fixed ignore the padding
+        let wide = UInt128Range::new_full();
+        let padded = Sequencer::new(&wide, SequenceLength::Fixed(3)).with_padding(0).with_stripping();
+        padded.compress(&[u128::MAX], &mut a).unwrap();
+        assert_eq!(padded.decompress(&mut a).unwrap(), [u128::MAX]);
+        let variable = Sequencer::new(&charset, SequenceLength::Variable(10)).with_padding(' ').with_stripping();
+        variable.compress(&chars("abc "), &mut a).unwrap();
+        assert_eq!(variable.decompress(&mut a).unwrap(), chars("abc "));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn seq_packed() {
+        // Sequences of specs with a power of two permutations are shifted in
+        // many values at a time, which must match multiplying for each value
+        let bools: Vec<bool> = (0..1000).map(|ii| (ii * ii + ii / 7) % 3 == 0).collect();
+        for length in [0, 1, 63, 64, 65, 1000] {
+            let sequencer = Sequencer::new(&Bool {}, SequenceLength::Fixed(length));
+            let mut a = Accumulator::new();
+            a.add(12345);
+            let mut b = a.clone();
+            sequencer.compress(&bools[..length], &mut a).unwrap();
+            for value in &bools[..length] {
+                b.mul(2);
+                b.add(*value as Digit);
+            }
+            assert_eq!(a.to_bytes(), b.to_bytes());
+            let mut c = Accumulator::new();
+            c.add(12345);
+            sequencer.compress_iter(&bools[..length], &mut c).unwrap();
+            assert_eq!(c.to_bytes(), a.to_bytes());
+            assert_eq!(sequencer.decompress(&mut a).unwrap(), &bools[..length]);
+            assert_eq!(a.div(12346), 12345);
+            assert!(a.is_empty());
+        }
+        let spec = UIntRange::new(0, 31);
+        let values: Vec<Digit> = (0..100).map(|ii| ii * 7 % 32).collect();
+        let prefixed = Sequencer::new(&spec, SequenceLength::Prefixed(100));
+        let mut a = Accumulator::new();
+        prefixed.compress(&values, &mut a).unwrap();
+        let mut b = Accumulator::new();
+        for value in &values {
+            b.mul(32);
+            b.add(*value);
+        }
+        b.mul(101);
+        b.add(100);
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(prefixed.decompress(&mut a).unwrap(), values);
+        // Guarded accumulators still find the end of the data
+        let fixed = Sequencer::new(&spec, SequenceLength::Fixed(100));
+        let mut a = Accumulator::new_guarded();
+        Sequencer::new(&spec, SequenceLength::Fixed(10)).compress(&values[..10], &mut a).unwrap();
+        assert_eq!(fixed.decompress(&mut a), Err(Error::Exhausted { index: 89 }));
+        // Errors give the index of the element
+        let mut a = Accumulator::new();
+        let mut bad = values.clone();
+        bad[70] = 32;
+        assert!(matches!(fixed.compress(&bad, &mut a), Err(Error::Element { index: 70, .. })));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn bytes_spec() {
+        let fixed = BytesSpec::new(SequenceLength::Fixed(12));
+        let variable = BytesSpec::new(SequenceLength::Variable(32));
+        let mut blob = vec![0x00; 4];
+        blob.extend([0xFF; 4]);
+        blob.extend([0x12, 0x00, 0x00, 0x34]);
+        // The first byte is the most significant
+        let mut a = Accumulator::new();
+        fixed.compress(&blob, &mut a).unwrap();
+        let mut reversed = blob.clone();
+        reversed.reverse();
+        while reversed.last() == Some(&0) {
+            reversed.pop();
+        }
+        assert_eq!(a.to_bytes(), reversed);
+        assert_eq!(fixed.decompress(&mut a).unwrap(), blob);
+        assert!(a.is_empty());
+        assert_eq!(fixed.compress(&blob[..11], &mut a), Err(Error::LengthMismatch { expected: 12, actual: 11 }));
+        // Blobs between other values
+        let before = IntRange::new(-50, 50);
+        let after = IntRange::new(0, 9);
+        for bytes in [vec![], vec![0x00], vec![0xFF; 32], blob.clone()] {
+            let mut a = Accumulator::new();
+            before.encode_into(&-50, &mut a).unwrap();
+            variable.compress(&bytes, &mut a).unwrap();
+            after.encode_into(&9, &mut a).unwrap();
+            assert_eq!(after.decode_from(&mut a).unwrap(), 9);
+            assert_eq!(variable.decompress(&mut a).unwrap(), bytes);
+            assert_eq!(before.decode_from(&mut a).unwrap(), -50);
+            assert!(a.is_empty());
+        }
+        assert_eq!(variable.max_bits(), Some(256.0 + libm::log2(33.0)));
+        assert_eq!(variable.compress(&[0; 33], &mut a), Err(Error::SequenceTooLong { len: 33, max: 32 }));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn matrix_sequencer() {
+        let spec = UIntRange::new(0, 9);
+        // 2 rows of 3 columns
+        let data: Vec<Digit> = vec![1, 2, 3, 4, 5, 6];
+        let fixed = MatrixSequencer::new(UIntRange::new(0, 9), 2, 3);
+        let mut a = Accumulator::new();
+        fixed.compress(&data, 2, 3, &mut a).unwrap();
+        // Row major order is the same as the values as a flat sequence
+        let mut b = Accumulator::new();
+        Sequencer::new(&spec, SequenceLength::Fixed(6)).compress(&data, &mut b).unwrap();
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        let matrix = fixed.decompress(&mut a).unwrap();
+        assert!(a.is_empty());
+        assert_eq!((matrix.rows(), matrix.cols()), (2, 3));
+        assert_eq!(matrix.get(0, 2), Some(&3));
+        assert_eq!(matrix.get(1, 0), Some(&4));
+        assert_eq!(matrix.get(2, 0), None);
+        assert_eq!(matrix.get(0, 3), None);
+        assert_eq!(matrix.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(matrix.row(2), None);
+        assert_eq!(matrix.clone().into_parts(), (data.clone(), 2, 3));
+        // Dimension errors
+        assert_eq!(fixed.compress(&data, 3, 2, &mut a), Err(Error::DimensionMismatch {
+            rows: 3,
+            cols: 2,
+            expected_rows: 2,
+            expected_cols: 3,
+        }));
+        assert_eq!(fixed.compress(&data[..5], 2, 3, &mut a), Err(Error::LengthMismatch { expected: 6, actual: 5 }));
+        let variable = MatrixSequencer::new_variable(UIntRange::new(0, 9), 4, 4);
+        assert_eq!(variable.compress(&[0; 5], 5, 1, &mut a), Err(Error::MatrixDimension {
+            axis: String::from("rows"),
+            value: 5,
+            max: 4,
+        }));
+        assert_eq!(variable.compress(&[0; 5], 1, 5, &mut a), Err(Error::MatrixDimension {
+            axis: String::from("cols"),
+            value: 5,
+            max: 4,
+        }));
+        assert_eq!(variable.compress(&data, 1, 4, &mut a), Err(Error::LengthMismatch { expected: 4, actual: 6 }));
+        assert!(a.is_empty());
+        // Variable dimensions keep the shape, including degenerate shapes
+        for (rows, cols) in [(2, 3), (3, 2), (1, 4), (4, 1), (0, 3), (0, 0)] {
+            let values = &data[..rows * cols];
+            variable.compress(values, rows, cols, &mut a).unwrap();
+            assert!(a.to_bytes().len() <= variable.max_bytes().unwrap());
+            assert_eq!(variable.decompress(&mut a).unwrap(), Matrix::new(values.to_vec(), rows, cols));
+            assert!(a.is_empty());
+        }
+        let row = MatrixSequencer::new(UIntRange::new(0, 9), 1, 6);
+        let column = MatrixSequencer::new(UIntRange::new(0, 9), 6, 1);
+        row.compress(&data, 1, 6, &mut a).unwrap();
+        column.compress(&data, 6, 1, &mut a).unwrap();
+        let column_matrix = column.decompress(&mut a).unwrap();
+        assert_eq!(column_matrix.get(5, 0), Some(&6));
+        assert_eq!(row.decompress(&mut a).unwrap().get(0, 5), Some(&6));
+        assert!(a.is_empty());
+        // Matrices as values of other specs
+        let grids = Sequencer::new(&variable, SequenceLength::Variable(2));
+        let values = vec![Matrix::new(vec![1, 2], 1, 2), Matrix::new(vec![3, 4], 2, 1)];
+        grids.compress(&values, &mut a).unwrap();
+        assert_eq!(grids.decompress(&mut a).unwrap(), values);
+        assert_eq!(Matrix::try_new(vec![1, 2, 3], 2, 2), Err(Error::InvalidLength { expected: 4, actual: 3 }));
+        assert!(MatrixSequencer::try_new(UIntRange::new(0, 9), usize::MAX, 2).is_err());
+    }
 }
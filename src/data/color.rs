@@ -0,0 +1,295 @@
+//! Color data definitions
+
+use core::result::Result;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::accum::Digit;
+use crate::data::DataSpec;
+use crate::error::Error;
+
+/// Color as 8 bit (r, g, b) or (r, g, b, a) channels, compressed with fewer
+/// bits for each channel (e.g. 5, 6 and 5 bits for RGB565)
+///
+/// Channels are rounded to the nearest value of their bit depth, and decode
+/// back to 8 bits by repeating their bits (e.g. 5 bits of 0b10110 decode to
+/// 0b10110101), so 0 and 255 are always exact. The channels are compressed
+/// as a single mixed radix index.
+///
+/// Specs without an alpha channel only compress opaque colors (alpha 255),
+/// and the (r, g, b) form of a spec with an alpha channel is opaque.
+///
+/// Note: The compression used by this data type is not lossless
+pub struct ColorSpec {
+    /// Bits of the red, green, blue and alpha channels, alpha is 0 when there
+    /// is no alpha channel
+    depths: [u8; 4],
+}
+
+impl ColorSpec {
+
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self::try_rgb(red, green, blue).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new ColorSpec without an alpha channel, or an error if a bit
+    /// depth is not from 1 to 8
+    pub fn try_rgb(red: u8, green: u8, blue: u8) -> Result<Self, Error> {
+        Self::try_new([red, green, blue, 0])
+    }
+
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self::try_rgba(red, green, blue, alpha).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new ColorSpec with an alpha channel, or an error if a bit
+    /// depth is not from 1 to 8
+    pub fn try_rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Result<Self, Error> {
+        if alpha == 0 {
+            return Err(depth_out_of_range(alpha));
+        }
+        Self::try_new([red, green, blue, alpha])
+    }
+
+    fn try_new(depths: [u8; 4]) -> Result<Self, Error> {
+        // An alpha depth of 0 is a color without an alpha channel
+        for (index, depth) in depths.into_iter().enumerate() {
+            if depth > 8 || (depth == 0 && index < 3) {
+                return Err(depth_out_of_range(depth));
+            }
+        }
+        let bits: u32 = depths.iter().map(|depth| *depth as u32).sum();
+        if bits >= Digit::BITS {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { depths })
+    }
+
+    /// 16 bit color with 5 bits of red, 6 of green and 5 of blue
+    pub fn rgb565() -> Self {
+        Self::rgb(5, 6, 5)
+    }
+
+    /// 24 bit color with 8 bits for each channel
+    pub fn rgb888() -> Self {
+        Self::rgb(8, 8, 8)
+    }
+
+    /// 16 bit color with 4 bits for each channel, including alpha
+    pub fn rgba4444() -> Self {
+        Self::rgba(4, 4, 4, 4)
+    }
+
+    /// Compress a color in the `#RRGGBB` (or `#RRGGBBAA`) form
+    pub fn encode_hex(&self, input: &str) -> Result<Digit, Error> {
+        let invalid = || Error::InvalidHexColor(input.to_string());
+        let digits = input.strip_prefix('#').ok_or_else(invalid)?;
+        if !(digits.len() == 6 || digits.len() == 8) || !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let mut channels = [255; 4];
+        for (ii, channel) in channels.iter_mut().take(digits.len() / 2).enumerate() {
+            *channel = u8::from_str_radix(&digits[2 * ii..2 * ii + 2], 16).map_err(|_| invalid())?;
+        }
+        self.encode(&(channels[0], channels[1], channels[2], channels[3]))
+    }
+
+    /// Decompress a color to the `#rrggbb` form, or `#rrggbbaa` if the spec
+    /// has an alpha channel
+    pub fn decode_hex(&self, input: Digit) -> Result<String, Error> {
+        let (r, g, b, a) = self.decode(input)?;
+        if self.depths[3] == 0 {
+            Ok(format!("#{:02x}{:02x}{:02x}", r, g, b))
+        } else {
+            Ok(format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a))
+        }
+    }
+}
+
+impl DataSpec<(u8, u8, u8, u8)> for ColorSpec {
+
+    fn permutations(&self) -> Digit {
+        1 << self.depths.iter().map(|depth| *depth as u32).sum::<u32>()
+    }
+
+    fn encode(&self, input: &(u8, u8, u8, u8)) -> Result<Digit, Error> {
+        let (r, g, b, a) = *input;
+        if self.depths[3] == 0 && a != 255 {
+            return Err(Error::TranslucentColor { alpha: a });
+        }
+        let mut index: Digit = 0;
+        for (value, depth) in [r, g, b, a].into_iter().zip(self.depths) {
+            index = index << depth | quantize(value, depth) as Digit;
+        }
+        Ok(index)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(u8, u8, u8, u8), Error> {
+        if input >= DataSpec::<(u8, u8, u8, u8)>::permutations(self) {
+            return Err(Error::DecodeOutOfBounds {
+                value: input,
+                permutations: DataSpec::<(u8, u8, u8, u8)>::permutations(self),
+            });
+        }
+        let mut channels = [255; 4];
+        let mut input = input;
+        for ii in (0..4).rev() {
+            let depth = self.depths[ii];
+            if depth > 0 {
+                channels[ii] = expand((input & ((1 << depth) - 1)) as u8, depth);
+                input >>= depth;
+            }
+        }
+        Ok((channels[0], channels[1], channels[2], channels[3]))
+    }
+}
+
+impl DataSpec<(u8, u8, u8)> for ColorSpec {
+
+    fn permutations(&self) -> Digit {
+        DataSpec::<(u8, u8, u8, u8)>::permutations(self)
+    }
+
+    fn encode(&self, input: &(u8, u8, u8)) -> Result<Digit, Error> {
+        self.encode(&(input.0, input.1, input.2, 255))
+    }
+
+    fn decode(&self, input: Digit) -> Result<(u8, u8, u8), Error> {
+        match self.decode(input)? {
+            (r, g, b, 255) => Ok((r, g, b)),
+            (_, _, _, alpha) => Err(Error::TranslucentColor { alpha }),
+        }
+    }
+}
+
+/// Error for a bit depth that is not from 1 to 8
+fn depth_out_of_range(depth: u8) -> Error {
+    Error::ValueOutOfRange { value: depth.to_string(), min: 1.to_string(), max: 8.to_string() }
+}
+
+/// Round an 8 bit channel to the nearest value with fewer bits
+fn quantize(value: u8, depth: u8) -> u8 {
+    let max = (1u32 << depth) - 1;
+    ((value as u32 * max + 127) / 255) as u8
+}
+
+/// Expand a channel with fewer bits back to 8 bits by repeating its bits
+fn expand(value: u8, depth: u8) -> u8 {
+    let mut expanded: u32 = 0;
+    let mut bits = 0;
+    while bits < 8 {
+        expanded = expanded << depth | value as u32;
+        bits += depth;
+    }
+    (expanded >> (bits - 8)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::string::String;
+
+    use crate::accum::Accumulator;
+    use crate::data::{ColorSpec, DataSpec, SequenceLength, Sequencer};
+    use crate::error::Error;
+
+    use super::{expand, quantize};
+
+    #[test]
+    fn channel() {
+        assert_eq!(expand(0b10110, 5), 0b10110101);
+        assert_eq!(expand(0b1, 1), 0xff);
+        assert_eq!(expand(0b101, 3), 0b10110110);
+        for depth in 1..=8 {
+            assert_eq!(expand(quantize(0, depth), depth), 0);
+            assert_eq!(expand(quantize(255, depth), depth), 255);
+            // Rounding to the nearest value, plus the error from repeating bits
+            let bound = 255.0 / ((1 << depth) - 1) as f64 / 2.0 + 1.0;
+            for value in 0..=255u8 {
+                let decoded = expand(quantize(value, depth), depth);
+                assert!((decoded as f64 - value as f64).abs() <= bound, "{} {} {}", depth, value, decoded);
+            }
+        }
+        for value in 0..=255u8 {
+            assert_eq!(expand(quantize(value, 8), 8), value);
+        }
+    }
+
+    #[test]
+    fn color() {
+        let spec = ColorSpec::rgb565();
+        assert_eq!(DataSpec::<(u8, u8, u8)>::permutations(&spec), 1 << 16);
+        assert_eq!(spec.encode(&(0, 0, 0)), Ok(0));
+        assert_eq!(spec.encode(&(255, 255, 255)), Ok(0xffff));
+        assert_eq!(spec.decode(0), Ok((0, 0, 0)));
+        assert_eq!(spec.decode(0xffff), Ok((255, 255, 255)));
+        assert_eq!(spec.encode(&(255, 0, 0)), Ok(0xf800));
+        assert_eq!(spec.encode(&(0, 255, 0)), Ok(0x07e0));
+        assert_eq!(spec.decode(0x001f), Ok((0, 0, 255)));
+        let decoded: (u8, u8, u8) = spec.decode(spec.encode(&(100, 150, 200)).unwrap()).unwrap();
+        assert!((decoded.0 as i32 - 100).abs() <= 5);
+        assert!((decoded.1 as i32 - 150).abs() <= 3);
+        assert!((decoded.2 as i32 - 200).abs() <= 5);
+        assert!(DataSpec::<(u8, u8, u8)>::decode(&spec, 1 << 16).is_err());
+        assert_eq!(spec.encode(&(1, 2, 3, 255)), spec.encode(&(1, 2, 3)));
+        assert_eq!(spec.encode(&(1, 2, 3, 254)), Err(Error::TranslucentColor { alpha: 254 }));
+        let spec = ColorSpec::rgb888();
+        assert_eq!(spec.decode(spec.encode(&(12, 34, 56)).unwrap()), Ok((12, 34, 56)));
+        let spec = ColorSpec::rgba4444();
+        assert_eq!(DataSpec::<(u8, u8, u8, u8)>::permutations(&spec), 1 << 16);
+        assert_eq!(spec.encode(&(255, 255, 255, 0)), Ok(0xfff0));
+        assert_eq!(spec.decode(0xfff0), Ok((255, 255, 255, 0)));
+        assert_eq!(spec.decode(0x0f0f), Ok((0, 255, 0, 255)));
+        assert_eq!(spec.encode(&(0, 255, 0)), Ok(0x0f0f));
+        assert_eq!(spec.decode(0x0f0f), Ok((0, 255, 0)));
+        assert_eq!(DataSpec::<(u8, u8, u8)>::decode(&spec, 0x0f08), Err(Error::TranslucentColor { alpha: 0x88 }));
+        let spec = ColorSpec::rgb(2, 3, 1);
+        assert_eq!(DataSpec::<(u8, u8, u8)>::permutations(&spec), 64);
+        assert_eq!(spec.decode(63), Ok((255, 255, 255)));
+        let out_of_range = |value: &str| Err(Error::ValueOutOfRange {
+            value: String::from(value),
+            min: String::from("1"),
+            max: String::from("8"),
+        });
+        assert_eq!(ColorSpec::try_rgb(9, 8, 8).map(|_| ()), out_of_range("9"));
+        assert_eq!(ColorSpec::try_rgb(8, 0, 8).map(|_| ()), out_of_range("0"));
+        assert_eq!(ColorSpec::try_rgba(8, 8, 8, 9).map(|_| ()), out_of_range("9"));
+        assert_eq!(ColorSpec::try_rgba(8, 8, 8, 0).map(|_| ()), out_of_range("0"));
+        #[cfg(not(feature = "digit32"))]
+        assert_eq!(DataSpec::<(u8, u8, u8, u8)>::permutations(&ColorSpec::rgba(8, 8, 8, 8)), 1 << 32);
+        #[cfg(feature = "digit32")]
+        assert_eq!(ColorSpec::try_rgba(8, 8, 8, 8).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn color_hex() {
+        let spec = ColorSpec::rgb888();
+        assert_eq!(spec.encode_hex("#ff8000"), spec.encode(&(255, 128, 0)));
+        assert_eq!(spec.encode_hex("#FF8000"), spec.encode(&(255, 128, 0)));
+        assert_eq!(spec.decode_hex(spec.encode_hex("#1a2b3c").unwrap()), Ok(String::from("#1a2b3c")));
+        assert_eq!(spec.encode_hex("#1a2b3cff"), spec.encode_hex("#1a2b3c"));
+        assert_eq!(spec.encode_hex("#1a2b3c80"), Err(Error::TranslucentColor { alpha: 0x80 }));
+        let spec = ColorSpec::rgb565();
+        assert_eq!(spec.decode_hex(spec.encode_hex("#000000").unwrap()), Ok(String::from("#000000")));
+        assert_eq!(spec.decode_hex(spec.encode_hex("#ffffff").unwrap()), Ok(String::from("#ffffff")));
+        assert_eq!(spec.decode_hex(spec.encode_hex("#ff0000").unwrap()), Ok(String::from("#ff0000")));
+        let spec = ColorSpec::rgba4444();
+        assert_eq!(spec.decode_hex(spec.encode_hex("#ffffff00").unwrap()), Ok(String::from("#ffffff00")));
+        assert_eq!(spec.decode_hex(spec.encode_hex("#00ff00").unwrap()), Ok(String::from("#00ff00ff")));
+        for invalid in ["ff8000", "#ff800", "#ff80000", "#gg8000", "#+f8000", ""] {
+            assert_eq!(spec.encode_hex(invalid), Err(Error::InvalidHexColor(String::from(invalid))));
+        }
+    }
+
+    #[test]
+    fn seq_color() {
+        // LED states of a strip
+        let mut a = Accumulator::new();
+        let leds: &[(u8, u8, u8)] = &[(0, 0, 0), (255, 255, 255), (255, 0, 0), (0, 255, 0)];
+        let spec = ColorSpec::rgb565();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(16));
+        sequencer.compress(leds, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), leds);
+        assert!(a.is_empty());
+    }
+}
@@ -0,0 +1,664 @@
+//! Data definitions built from other data definitions
+
+use core::result::Result;
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use crate::accum::Digit;
+use crate::data::{component_error, DataSpec};
+use crate::error::Error;
+
+/// Optional value of any DataSpec, `None` is index zero and every value of
+/// the inner spec is shifted up by one
+///
+/// Costs one more permutation than the inner spec, which is less than a
+/// separate Bool for every inner spec with more than one permutation.
+pub struct OptionSpec<S> {
+    inner: S,
+}
+
+impl<S> OptionSpec<S> {
+
+    pub fn new<T>(inner: S) -> Self where S: DataSpec<T> {
+        Self::try_new(inner).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new OptionSpec, or an error if the inner spec already has the
+    /// maximum number of permutations
+    pub fn try_new<T>(inner: S) -> Result<Self, Error> where S: DataSpec<T> {
+        if inner.permutations() == Digit::MAX {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { inner })
+    }
+}
+
+impl<T, S: DataSpec<T>> DataSpec<Option<T>> for OptionSpec<S> {
+
+    fn permutations(&self) -> Digit {
+        self.inner.permutations() + 1
+    }
+
+    fn encode(&self, input: &Option<T>) -> Result<Digit, Error> {
+        match input {
+            None => Ok(0),
+            Some(value) => Ok(self.inner.encode(value)? + 1),
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<Option<T>, Error> {
+        match input {
+            0 => Ok(None),
+            _ if input < self.permutations() => Ok(Some(self.inner.decode(input - 1)?)),
+            _ => Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() }),
+        }
+    }
+}
+
+/// Value of one of two types, for an EitherSpec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// Value of either one of two DataSpecs (e.g. a numeric ID or a short code),
+/// values of the left spec come first and values of the right spec follow
+///
+/// Costs the permutations of both specs added together, instead of both
+/// multiplied together and a Bool to select one.
+pub struct EitherSpec<A, B> {
+    left: A,
+    right: B,
+}
+
+impl<A, B> EitherSpec<A, B> {
+
+    pub fn new<L, R>(left: A, right: B) -> Self where A: DataSpec<L>, B: DataSpec<R> {
+        Self::try_new(left, right).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new EitherSpec, or an error if the permutations of both specs
+    /// added together do not fit in a Digit
+    pub fn try_new<L, R>(left: A, right: B) -> Result<Self, Error> where A: DataSpec<L>, B: DataSpec<R> {
+        if left.permutations().checked_add(right.permutations()).is_none() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { left, right })
+    }
+}
+
+impl<L, R, A: DataSpec<L>, B: DataSpec<R>> DataSpec<Either<L, R>> for EitherSpec<A, B> {
+
+    fn permutations(&self) -> Digit {
+        self.left.permutations() + self.right.permutations()
+    }
+
+    fn encode(&self, input: &Either<L, R>) -> Result<Digit, Error> {
+        match input {
+            Either::Left(value) => self.left.encode(value),
+            Either::Right(value) => Ok(self.left.permutations() + self.right.encode(value)?),
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<Either<L, R>, Error> {
+        let seam = self.left.permutations();
+        if input < seam {
+            Ok(Either::Left(self.left.decode(input)?))
+        } else if input < self.permutations() {
+            Ok(Either::Right(self.right.decode(input - seam)?))
+        } else {
+            Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() })
+        }
+    }
+}
+
+/// Pair of values of two DataSpecs compressed as a single Digit, the first
+/// value is the most significant
+///
+/// Errors for a value give the index of its component in the tuple.
+pub struct Tuple2Spec<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Tuple2Spec<A, B> {
+
+    pub fn new<TA, TB>(a: A, b: B) -> Self where A: DataSpec<TA>, B: DataSpec<TB> {
+        Self::try_new(a, b).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Tuple2Spec, or an error if the permutations of both specs
+    /// multiplied together do not fit in a Digit
+    pub fn try_new<TA, TB>(a: A, b: B) -> Result<Self, Error> where A: DataSpec<TA>, B: DataSpec<TB> {
+        tuple_permutations(&[a.permutations(), b.permutations()])?;
+        Ok(Self { a, b })
+    }
+}
+
+impl<TA, TB, A: DataSpec<TA>, B: DataSpec<TB>> DataSpec<(TA, TB)> for Tuple2Spec<A, B> {
+
+    fn permutations(&self) -> Digit {
+        self.a.permutations() * self.b.permutations()
+    }
+
+    fn encode(&self, input: &(TA, TB)) -> Result<Digit, Error> {
+        let a = self.a.encode(&input.0).map_err(|e| component_error(0, e))?;
+        let b = self.b.encode(&input.1).map_err(|e| component_error(1, e))?;
+        Ok(a * self.b.permutations() + b)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(TA, TB), Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        let b = self.b.decode(input % self.b.permutations()).map_err(|e| component_error(1, e))?;
+        let a = self.a.decode(input / self.b.permutations()).map_err(|e| component_error(0, e))?;
+        Ok((a, b))
+    }
+}
+
+/// Triple of values of three DataSpecs compressed as a single Digit, the
+/// first value is the most significant
+///
+/// Errors for a value give the index of its component in the tuple.
+pub struct Tuple3Spec<A, B, C> {
+    a: A,
+    b: B,
+    c: C,
+}
+
+impl<A, B, C> Tuple3Spec<A, B, C> {
+
+    pub fn new<TA, TB, TC>(a: A, b: B, c: C) -> Self
+    where A: DataSpec<TA>, B: DataSpec<TB>, C: DataSpec<TC> {
+        Self::try_new(a, b, c).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Tuple3Spec, or an error if the permutations of all specs
+    /// multiplied together do not fit in a Digit
+    pub fn try_new<TA, TB, TC>(a: A, b: B, c: C) -> Result<Self, Error>
+    where A: DataSpec<TA>, B: DataSpec<TB>, C: DataSpec<TC> {
+        tuple_permutations(&[a.permutations(), b.permutations(), c.permutations()])?;
+        Ok(Self { a, b, c })
+    }
+}
+
+impl<TA, TB, TC, A, B, C> DataSpec<(TA, TB, TC)> for Tuple3Spec<A, B, C>
+where A: DataSpec<TA>, B: DataSpec<TB>, C: DataSpec<TC> {
+
+    fn permutations(&self) -> Digit {
+        self.a.permutations() * self.b.permutations() * self.c.permutations()
+    }
+
+    fn encode(&self, input: &(TA, TB, TC)) -> Result<Digit, Error> {
+        let a = self.a.encode(&input.0).map_err(|e| component_error(0, e))?;
+        let b = self.b.encode(&input.1).map_err(|e| component_error(1, e))?;
+        let c = self.c.encode(&input.2).map_err(|e| component_error(2, e))?;
+        Ok((a * self.b.permutations() + b) * self.c.permutations() + c)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(TA, TB, TC), Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        let rest = input / self.c.permutations();
+        let c = self.c.decode(input % self.c.permutations()).map_err(|e| component_error(2, e))?;
+        let b = self.b.decode(rest % self.b.permutations()).map_err(|e| component_error(1, e))?;
+        let a = self.a.decode(rest / self.b.permutations()).map_err(|e| component_error(0, e))?;
+        Ok((a, b, c))
+    }
+}
+
+/// Values of another type compressed with a DataSpec, converted to and from
+/// the values of the spec by a pair of functions (e.g. a newtype, or a Rust
+/// enum through an Enum of its names)
+///
+/// Errors from the conversions are returned as encode and decode errors.
+pub struct MappedSpec<S, T, U> {
+    inner: S,
+    to_inner: ToInner<T, U>,
+    from_inner: FromInner<T, U>,
+}
+
+/// Conversions of a MappedSpec
+type ToInner<T, U> = Box<dyn Fn(&U) -> Result<T, Error>>;
+type FromInner<T, U> = Box<dyn Fn(T) -> Result<U, Error>>;
+
+impl<S: DataSpec<T>, T, U> MappedSpec<S, T, U> {
+
+    pub fn new(
+        inner: S,
+        to_inner: impl Fn(&U) -> Result<T, Error> + 'static,
+        from_inner: impl Fn(T) -> Result<U, Error> + 'static,
+    ) -> Self {
+        Self { inner, to_inner: Box::new(to_inner), from_inner: Box::new(from_inner) }
+    }
+}
+
+impl<S: DataSpec<T>, T, U> DataSpec<U> for MappedSpec<S, T, U> {
+
+    fn permutations(&self) -> Digit {
+        self.inner.permutations()
+    }
+
+    fn encode(&self, input: &U) -> Result<Digit, Error> {
+        self.inner.encode(&(self.to_inner)(input)?)
+    }
+
+    fn decode(&self, input: Digit) -> Result<U, Error> {
+        (self.from_inner)(self.inner.decode(input)?)
+    }
+}
+
+/// DataSpec with an extra requirement for its values (e.g. even numbers only),
+/// checked when encoding and after decoding so corrupted data that decodes to
+/// a value that does not meet it is caught
+///
+/// Errors for values that do not meet the requirement give its description.
+pub struct ValidatedSpec<S, T> {
+    inner: S,
+    predicate: Box<dyn Fn(&T) -> bool>,
+    description: &'static str,
+}
+
+impl<S: DataSpec<T>, T> ValidatedSpec<S, T> {
+
+    pub fn new(inner: S, predicate: impl Fn(&T) -> bool + 'static, description: &'static str) -> Self {
+        Self { inner, predicate: Box::new(predicate), description }
+    }
+
+    fn validate(&self, value: &T) -> Result<(), Error> {
+        match (self.predicate)(value) {
+            true => Ok(()),
+            false => Err(Error::FailedValidation(String::from(self.description))),
+        }
+    }
+}
+
+impl<S: DataSpec<T>, T> DataSpec<T> for ValidatedSpec<S, T> {
+
+    fn permutations(&self) -> Digit {
+        self.inner.permutations()
+    }
+
+    fn encode(&self, input: &T) -> Result<Digit, Error> {
+        self.validate(input)?;
+        self.inner.encode(input)
+    }
+
+    fn decode(&self, input: Digit) -> Result<T, Error> {
+        let value = self.inner.decode(input)?;
+        self.validate(&value)?;
+        Ok(value)
+    }
+}
+
+/// Value of a DataSpec that is usually a default (e.g. an override that is
+/// rarely set), the default is index zero and every value of the inner spec is
+/// shifted up by one
+///
+/// The default is always encoded as index zero, so the inner encoding of the
+/// default is never used and decoding it is an error. Costs one more
+/// permutation than the inner spec, which is less than a separate Bool to flag
+/// the default.
+pub struct DefaultShortcut<S, T> {
+    inner: S,
+    default_value: T,
+}
+
+impl<S: DataSpec<T>, T: Clone + PartialEq> DefaultShortcut<S, T> {
+
+    pub fn new(inner: S, default_value: T) -> Self {
+        Self::try_new(inner, default_value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DefaultShortcut, or an error if the inner spec already
+    /// has the maximum number of permutations
+    pub fn try_new(inner: S, default_value: T) -> Result<Self, Error> {
+        if inner.permutations() == Digit::MAX {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { inner, default_value })
+    }
+}
+
+impl<S: DataSpec<T>, T: Clone + PartialEq> DataSpec<T> for DefaultShortcut<S, T> {
+
+    fn permutations(&self) -> Digit {
+        self.inner.permutations() + 1
+    }
+
+    fn encode(&self, input: &T) -> Result<Digit, Error> {
+        if *input == self.default_value {
+            return Ok(0);
+        }
+        Ok(self.inner.encode(input)? + 1)
+    }
+
+    fn decode(&self, input: Digit) -> Result<T, Error> {
+        if input == 0 {
+            return Ok(self.default_value.clone());
+        }
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        let value = self.inner.decode(input - 1)?;
+        if value == self.default_value {
+            return Err(Error::UnknownVariantIndex(input));
+        }
+        Ok(value)
+    }
+}
+
+/// Permutations of the components of a tuple multiplied together, or an
+/// error if they do not fit in a Digit
+fn tuple_permutations(permutations: &[Digit]) -> Result<Digit, Error> {
+    permutations.iter().try_fold(1 as Digit, |total, p| total.checked_mul(*p).ok_or(Error::TooManyPermutations))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::accum::{Accumulator, Digit};
+    use crate::data::{DataSpec, SequenceLength, Sequencer};
+    use core::fmt;
+    use core::str::FromStr;
+
+    use crate::accum::SignedDigit;
+    use crate::data::{Bool, CharSet, DefaultShortcut, Either, EitherSpec, Enum, IntRange, MappedSpec, OptionSpec};
+    use crate::data::{Tuple2Spec, Tuple3Spec, UIntRange, ValidatedSpec};
+    use crate::error::Error;
+
+    #[test]
+    fn option_spec() {
+        let spec = OptionSpec::new(UIntRange::new(10, 20));
+        assert_eq!(spec.permutations(), 12);
+        assert_eq!(spec.encode(&None), Ok(0));
+        assert_eq!(spec.encode(&Some(10)), Ok(1));
+        assert_eq!(spec.encode(&Some(20)), Ok(11));
+        assert_eq!(spec.decode(0), Ok(None));
+        assert_eq!(spec.decode(1), Ok(Some(10)));
+        assert_eq!(spec.decode(11), Ok(Some(20)));
+        assert!(spec.encode(&Some(21)).is_err());
+        assert_eq!(spec.decode(12), Err(Error::DecodeOutOfBounds { value: 12, permutations: 12 }));
+        let colors = OptionSpec::new(Enum::new(&["red", "green"]));
+        assert_eq!(colors.encode(&Some(String::from("green"))), Ok(2));
+        assert_eq!(colors.encode(&Some(String::from("blue"))), Err(Error::UnknownEnumVariant(String::from("blue"))));
+        assert_eq!(OptionSpec::try_new(UIntRange::new(0, Digit::MAX - 2)).map(|spec| spec.permutations()), Ok(Digit::MAX));
+        assert_eq!(OptionSpec::try_new(UIntRange::new_full()).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn option_spec_nested() {
+        let spec = OptionSpec::new(OptionSpec::new(Bool::new()));
+        assert_eq!(spec.permutations(), 4);
+        let values = [None, Some(None), Some(Some(false)), Some(Some(true))];
+        for (ii, value) in values.iter().enumerate() {
+            assert_eq!(spec.encode(value), Ok(ii as Digit));
+            assert_eq!(spec.decode(ii as Digit), Ok(*value));
+        }
+    }
+
+    #[test]
+    fn seq_option_spec() {
+        let spec = OptionSpec::new(UIntRange::new(0, 1000));
+        let values = vec![Some(0), None, Some(1000), None, None, Some(42)];
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(8));
+        sequencer.compress(&values, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+        let empty: Vec<Option<Digit>> = vec![];
+        sequencer.compress(&empty, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), empty);
+    }
+
+    #[test]
+    fn either_spec() {
+        let spec = EitherSpec::new(UIntRange::new(0, 999), CharSet::alphanumeric());
+        assert_eq!(spec.permutations(), 1062);
+        assert_eq!(spec.encode(&Either::Left(0)), Ok(0));
+        assert_eq!(spec.encode(&Either::Left(999)), Ok(999));
+        assert_eq!(spec.encode(&Either::Right('0')), Ok(1000));
+        assert_eq!(spec.encode(&Either::Right('z')), Ok(1061));
+        assert_eq!(spec.decode(999), Ok(Either::Left(999)));
+        assert_eq!(spec.decode(1000), Ok(Either::Right('0')));
+        assert_eq!(spec.decode(1061), Ok(Either::Right('z')));
+        assert_eq!(spec.decode(1062), Err(Error::DecodeOutOfBounds { value: 1062, permutations: 1062 }));
+        assert!(spec.encode(&Either::Left(1000)).is_err());
+        assert_eq!(spec.encode(&Either::Right('!')), Err(Error::CharNotInSet('!')));
+        assert_eq!(
+            EitherSpec::try_new(UIntRange::new_full(), Bool::new()).err(),
+            Some(Error::TooManyPermutations),
+        );
+    }
+
+    #[test]
+    fn seq_either_spec() {
+        let spec = EitherSpec::new(UIntRange::new(0, 999), Enum::new(&["none", "all"]));
+        let values = vec![
+            Either::Left(999),
+            Either::Right(String::from("none")),
+            Either::Left(0),
+            Either::Right(String::from("all")),
+        ];
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(4));
+        sequencer.compress(&values, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn tuple2_spec() {
+        let spec = Tuple2Spec::new(IntRange::new(-10, 10), Bool::new());
+        assert_eq!(spec.permutations(), 42);
+        assert_eq!(spec.encode(&(-10, false)), Ok(0));
+        assert_eq!(spec.encode(&(-10, true)), Ok(1));
+        assert_eq!(spec.encode(&(10, true)), Ok(41));
+        assert_eq!(spec.decode(0), Ok((-10, false)));
+        assert_eq!(spec.decode(41), Ok((10, true)));
+        assert_eq!(spec.decode(42), Err(Error::DecodeOutOfBounds { value: 42, permutations: 42 }));
+        assert!(matches!(spec.encode(&(11, false)), Err(Error::Component { index: 0, .. })));
+        let spec = Tuple2Spec::new(Bool::new(), CharSet::digits());
+        assert_eq!(
+            spec.encode(&(true, 'a')),
+            Err(Error::Component { index: 1, error: Box::new(Error::CharNotInSet('a')) }),
+        );
+        assert_eq!(
+            Tuple2Spec::try_new(UIntRange::new_full(), Bool::new()).err(),
+            Some(Error::TooManyPermutations),
+        );
+    }
+
+    #[test]
+    fn tuple3_spec() {
+        let spec = Tuple3Spec::new(UIntRange::new(0, 4), Enum::new(&["min", "max"]), IntRange::new(-1, 1));
+        assert_eq!(spec.permutations(), 30);
+        let min = (0, String::from("min"), -1);
+        let max = (4, String::from("max"), 1);
+        assert_eq!(spec.encode(&min), Ok(0));
+        assert_eq!(spec.encode(&max), Ok(29));
+        assert_eq!(spec.encode(&(1, String::from("min"), 0)), Ok(7));
+        assert_eq!(spec.decode(0), Ok(min));
+        assert_eq!(spec.decode(29), Ok(max));
+        assert_eq!(spec.decode(7), Ok((1, String::from("min"), 0)));
+        assert_eq!(spec.decode(30), Err(Error::DecodeOutOfBounds { value: 30, permutations: 30 }));
+        assert_eq!(
+            spec.encode(&(0, String::from("mid"), 0)),
+            Err(Error::Component { index: 1, error: Box::new(Error::UnknownEnumVariant(String::from("mid"))) }),
+        );
+        assert!(matches!(spec.encode(&(0, String::from("min"), 2)), Err(Error::Component { index: 2, .. })));
+        let half = UIntRange::new(0, (1 << (Digit::BITS / 2)) - 1);
+        let quarter = UIntRange::new(0, (1 << (Digit::BITS / 4)) - 1);
+        assert_eq!(
+            Tuple3Spec::try_new(half, quarter, Bool::new()).map(|spec| spec.permutations()),
+            Ok(1 << (Digit::BITS / 4 * 3 + 1)),
+        );
+        let half = UIntRange::new(0, (1 << (Digit::BITS / 2)) - 1);
+        let other_half = UIntRange::new(0, (1 << (Digit::BITS / 2)) - 1);
+        assert_eq!(
+            Tuple3Spec::try_new(half, other_half, Bool::new()).err(),
+            Some(Error::TooManyPermutations),
+        );
+    }
+
+    #[test]
+    fn seq_tuple_spec() {
+        let spec = Tuple2Spec::new(IntRange::new(-100, 100), IntRange::new(-100, 100));
+        let points = vec![(-100, -100), (0, 0), (100, 100), (37, -12)];
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(8));
+        sequencer.compress(&points, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), points);
+        assert!(a.is_empty());
+        let bad = vec![(0, 0), (0, 101)];
+        let e = sequencer.compress(&bad, &mut a).unwrap_err();
+        assert!(matches!(e, Error::Element { index: 1, error } if matches!(*error, Error::Component { index: 1, .. })));
+        assert!(a.is_empty());
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Celsius(SignedDigit);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Suit {
+        Clubs,
+        Diamonds,
+        Hearts,
+        Spades,
+    }
+
+    impl fmt::Display for Suit {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Debug::fmt(self, f)
+        }
+    }
+
+    impl FromStr for Suit {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self, Error> {
+            match s {
+                "Clubs" => Ok(Suit::Clubs),
+                "Diamonds" => Ok(Suit::Diamonds),
+                "Hearts" => Ok(Suit::Hearts),
+                "Spades" => Ok(Suit::Spades),
+                _ => Err(Error::UnknownEnumVariant(String::from(s))),
+            }
+        }
+    }
+
+    #[test]
+    fn mapped_spec() {
+        let spec = MappedSpec::new(
+            IntRange::new(-40, 60),
+            |value: &Celsius| Ok(value.0),
+            |value| Ok(Celsius(value)),
+        );
+        assert_eq!(spec.permutations(), 101);
+        assert_eq!(spec.encode(&Celsius(-40)), Ok(0));
+        assert_eq!(spec.decode(100), Ok(Celsius(60)));
+        assert!(matches!(spec.encode(&Celsius(61)), Err(Error::ValueOutOfRange { .. })));
+        let suits = MappedSpec::new(
+            Enum::new(&["Clubs", "Diamonds", "Hearts", "Spades"]),
+            |suit: &Suit| Ok(suit.to_string()),
+            |name: String| name.parse(),
+        );
+        assert_eq!(suits.permutations(), 4);
+        assert_eq!(suits.encode(&Suit::Hearts), Ok(2));
+        assert_eq!(suits.decode(3), Ok(Suit::Spades));
+        let values = vec![Suit::Spades, Suit::Clubs, Suit::Diamonds];
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&suits, SequenceLength::Fixed(3));
+        sequencer.compress(&values, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        // Errors from the conversions are returned from encode and decode
+        let names = MappedSpec::new(
+            Enum::new(&["Clubs", "Diamonds", "Jokers"]),
+            |suit: &Suit| match suit {
+                Suit::Hearts => Err(Error::UnknownEnumVariant(suit.to_string())),
+                _ => Ok(suit.to_string()),
+            },
+            |name: String| name.parse::<Suit>(),
+        );
+        assert_eq!(names.encode(&Suit::Hearts), Err(Error::UnknownEnumVariant(String::from("Hearts"))));
+        assert_eq!(names.encode(&Suit::Spades), Err(Error::UnknownEnumVariant(String::from("Spades"))));
+        assert_eq!(names.decode(2), Err(Error::UnknownEnumVariant(String::from("Jokers"))));
+        assert_eq!(names.decode(1), Ok(Suit::Diamonds));
+    }
+
+    #[test]
+    fn validated_spec() {
+        let spec = ValidatedSpec::new(UIntRange::new(0, 100), |value| value % 2 == 0, "even numbers only");
+        assert_eq!(spec.permutations(), 101);
+        assert_eq!(spec.encode(&42), Ok(42));
+        assert_eq!(spec.decode(100), Ok(100));
+        assert_eq!(spec.encode(&41), Err(Error::FailedValidation(String::from("even numbers only"))));
+        assert_eq!(spec.decode(41), Err(Error::FailedValidation(String::from("even numbers only"))));
+        // Errors of the inner spec come first
+        assert!(matches!(spec.encode(&102), Err(Error::ValueOutOfRange { .. })));
+        let reserved = [0, 255];
+        let ids = ValidatedSpec::new(UIntRange::new(0, 255), move |id| !reserved.contains(id), "reserved ID");
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&ids, SequenceLength::Variable(4));
+        sequencer.compress(&[1, 254, 17], &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), vec![1, 254, 17]);
+        assert!(matches!(
+            sequencer.compress(&[1, 255], &mut a),
+            Err(Error::Element { index: 1, error }) if *error == Error::FailedValidation(String::from("reserved ID")),
+        ));
+        // Corrupted data that decodes to a reserved ID
+        let mut a = Accumulator::new();
+        a.mul(257);
+        a.add(1);
+        assert!(matches!(sequencer.decompress(&mut a), Err(Error::DecodeElement { index: 0, value: 0, .. })));
+    }
+
+    #[test]
+    fn default_shortcut() {
+        let spec = DefaultShortcut::new(Enum::new(&["auto", "manual", "off"]), String::from("auto"));
+        assert_eq!(spec.permutations(), 4);
+        assert_eq!(spec.encode(&String::from("auto")), Ok(0));
+        assert_eq!(spec.encode(&String::from("manual")), Ok(2));
+        assert_eq!(spec.encode(&String::from("off")), Ok(3));
+        assert_eq!(spec.decode(0), Ok(String::from("auto")));
+        assert_eq!(spec.decode(2), Ok(String::from("manual")));
+        assert_eq!(spec.decode(3), Ok(String::from("off")));
+        // The inner encoding of the default is never used
+        assert_eq!(spec.decode(1), Err(Error::UnknownVariantIndex(1)));
+        assert_eq!(spec.decode(4), Err(Error::DecodeOutOfBounds { value: 4, permutations: 4 }));
+        assert_eq!(spec.encode(&String::from("on")), Err(Error::UnknownEnumVariant(String::from("on"))));
+        // A default that is not a value of the inner spec
+        let spec = DefaultShortcut::new(UIntRange::new(1, 10), 0);
+        assert_eq!(spec.encode(&0), Ok(0));
+        assert_eq!(spec.encode(&1), Ok(1));
+        assert_eq!(spec.decode(10), Ok(10));
+        assert_eq!(DefaultShortcut::try_new(UIntRange::new_full(), 0).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn seq_default_shortcut() {
+        let spec = DefaultShortcut::new(UIntRange::new(0, 9999), 80);
+        let flagged = Tuple2Spec::new(Bool::new(), UIntRange::new(0, 9999));
+        let values: Vec<Digit> = (0..40).map(|ii| if ii % 20 == 7 { 8080 } else { 80 }).collect();
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(values.len()));
+        sequencer.compress(&values, &mut a).unwrap();
+        let shortcut_bytes = a.to_bytes().len();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+        // Compared to a Bool that flags the default next to the value
+        let pairs: Vec<(bool, Digit)> = values.iter().map(|value| (*value == 80, *value)).collect();
+        let mut b = Accumulator::new();
+        Sequencer::new(&flagged, SequenceLength::Fixed(pairs.len())).compress(&pairs, &mut b).unwrap();
+        // 55 bytes instead of 72
+        assert!(shortcut_bytes < b.to_bytes().len());
+    }
+}
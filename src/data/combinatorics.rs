@@ -0,0 +1,493 @@
+//! Set, multiset and permutation data definitions
+
+use core::result::Result;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::accum::{Digit, DoubleDigit};
+use crate::data::DataSpec;
+use crate::error::Error;
+
+/// Number of members of a set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetSize {
+    Exactly(usize),
+    AtMost(usize),
+}
+
+/// Set of members chosen from a universe of options where the order does not
+/// matter (e.g. which 3 of 20 toppings), compressed as the rank of the set in
+/// the combinatorial number system
+///
+/// Costs about `log2(C(n, k))` bits instead of `k * log2(n)` bits for each
+/// member on its own. Smaller sets come before larger sets for `AtMost`, and
+/// sets decode with their members in the order of the universe.
+pub struct SortedSetSpec {
+    options: Vec<String>,
+    lookup: BTreeMap<String, usize>,
+    size: SetSize,
+    /// Index of the first set of each size
+    offsets: Vec<Digit>,
+    permutations: Digit,
+}
+
+impl SortedSetSpec {
+
+    pub fn new(universe: &[&str], size: SetSize) -> Self {
+        Self::try_new(universe, size).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new SortedSetSpec, or an error if an option is repeated, the
+    /// size is larger than the universe or the number of sets does not fit in
+    /// a Digit
+    pub fn try_new(universe: &[&str], size: SetSize) -> Result<Self, Error> {
+        let mut lookup = BTreeMap::new();
+        for (ii, option) in universe.iter().enumerate() {
+            if let Some(first) = lookup.insert(String::from(*option), ii) {
+                return Err(Error::DuplicateOption { value: String::from(*option), index: ii, first });
+            }
+        }
+        let n = universe.len();
+        let (min, max) = match size {
+            SetSize::Exactly(k) => (k, k),
+            SetSize::AtMost(k) => (0, k),
+        };
+        if max > n {
+            return Err(Error::ValueOutOfRange {
+                value: max.to_string(),
+                min: String::from("0"),
+                max: n.to_string(),
+            });
+        }
+        let mut offsets = Vec::with_capacity(max - min + 1);
+        let mut permutations: Digit = 0;
+        for k in min..=max {
+            offsets.push(permutations);
+            let sets = binomial(n as Digit, k as Digit).ok_or(Error::TooManyPermutations)?;
+            permutations = permutations.checked_add(sets).ok_or(Error::TooManyPermutations)?;
+        }
+        Ok(Self {
+            options: universe.iter().map(|option| String::from(*option)).collect(),
+            lookup,
+            size,
+            offsets,
+            permutations,
+        })
+    }
+
+    /// Smallest size of a set
+    fn min_size(&self) -> usize {
+        match self.size {
+            SetSize::Exactly(k) => k,
+            SetSize::AtMost(_) => 0,
+        }
+    }
+}
+
+impl DataSpec<Vec<String>> for SortedSetSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &Vec<String>) -> Result<Digit, Error> {
+        match self.size {
+            SetSize::Exactly(k) if input.len() != k => {
+                return Err(Error::InvalidLength { expected: k, actual: input.len() });
+            },
+            SetSize::AtMost(k) if input.len() > k => {
+                return Err(Error::SequenceTooLong { len: input.len(), max: k });
+            },
+            _ => {},
+        }
+        let mut members: BTreeMap<usize, usize> = BTreeMap::new();
+        for (ii, member) in input.iter().enumerate() {
+            let index = *self.lookup.get(member).ok_or_else(|| Error::UnknownEnumVariant(member.clone()))?;
+            if let Some(first) = members.insert(index, ii) {
+                return Err(Error::DuplicateElement { value: member.clone(), index: ii, first });
+            }
+        }
+        let indices: Vec<usize> = members.into_keys().collect();
+        Ok(self.offsets[input.len() - self.min_size()] + rank_combination(&indices))
+    }
+
+    fn decode(&self, input: Digit) -> Result<Vec<String>, Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let size = self.offsets.partition_point(|offset| *offset <= input) - 1;
+        let indices = unrank_combination(input - self.offsets[size], size + self.min_size(), self.options.len());
+        Ok(indices.into_iter().map(|index| self.options[index].clone()).collect())
+    }
+}
+
+/// Multiset of indices below a universe size where the order does not matter
+/// and indices can repeat (e.g. 3 dice rolled), compressed as its rank in the
+/// combinatorial number system
+///
+/// Values are the sorted indices of the members (e.g. `[0, 0, 4]`), indices
+/// can be given in any order to encode and decode in increasing order.
+pub struct MultisetSpec {
+    universe_size: usize,
+    count: usize,
+    /// Size of the universe of the spread out combinations (`n + k - 1`)
+    spread_size: usize,
+    permutations: Digit,
+}
+
+impl MultisetSpec {
+
+    pub fn new(universe_size: usize, count: usize) -> Self {
+        Self::try_new(universe_size, count).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new MultisetSpec, or an error if the universe is empty or the
+    /// number of multisets `C(n + k - 1, k)` does not fit in a Digit
+    pub fn try_new(universe_size: usize, count: usize) -> Result<Self, Error> {
+        if universe_size == 0 {
+            return Err(Error::ValueOutOfRange {
+                value: String::from("0"),
+                min: String::from("1"),
+                max: usize::MAX.to_string(),
+            });
+        }
+        let spread_size = (universe_size - 1).checked_add(count).ok_or(Error::TooManyPermutations)?;
+        let to_digit = |value: usize| Digit::try_from(value).map_err(|_| Error::TooManyPermutations);
+        let permutations = binomial(to_digit(spread_size)?, to_digit(count)?).ok_or(Error::TooManyPermutations)?;
+        Ok(Self { universe_size, count, spread_size, permutations })
+    }
+}
+
+impl DataSpec<Vec<usize>> for MultisetSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &Vec<usize>) -> Result<Digit, Error> {
+        if input.len() != self.count {
+            return Err(Error::InvalidLength { expected: self.count, actual: input.len() });
+        }
+        if let Some(index) = input.iter().find(|index| **index >= self.universe_size) {
+            return Err(Error::ValueOutOfRange {
+                value: index.to_string(),
+                min: String::from("0"),
+                max: (self.universe_size - 1).to_string(),
+            });
+        }
+        // Spreading the sorted indices out by their position gives a
+        // combination without repeats
+        let mut indices = input.clone();
+        indices.sort_unstable();
+        let spread: Vec<usize> = indices.iter().enumerate().map(|(ii, index)| index + ii).collect();
+        Ok(rank_combination(&spread))
+    }
+
+    fn decode(&self, input: Digit) -> Result<Vec<usize>, Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let spread = unrank_combination(input, self.count, self.spread_size);
+        Ok(spread.iter().enumerate().map(|(ii, index)| index - ii).collect())
+    }
+}
+
+/// Ordering of the indices `0..n` (e.g. the order of a deck of cards),
+/// compressed as its rank from its Lehmer code
+///
+/// The permutations are exactly `n!`, which fits in a Digit for n up to 20
+/// (12 with `digit32`). Larger orderings can be split into several specs.
+pub struct PermutationSpec {
+    n: usize,
+    permutations: Digit,
+}
+
+impl PermutationSpec {
+
+    pub fn new(n: usize) -> Self {
+        Self::try_new(n).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new PermutationSpec, or an error if `n!` does not fit in a
+    /// Digit
+    pub fn try_new(n: usize) -> Result<Self, Error> {
+        let permutations = (1..=n as Digit).try_fold(1 as Digit, |total, ii| total.checked_mul(ii));
+        Ok(Self { n, permutations: permutations.ok_or(Error::TooManyPermutations)? })
+    }
+}
+
+impl DataSpec<Vec<usize>> for PermutationSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &Vec<usize>) -> Result<Digit, Error> {
+        if input.len() != self.n {
+            return Err(Error::InvalidLength { expected: self.n, actual: input.len() });
+        }
+        let mut seen: Vec<Option<usize>> = vec![None; self.n];
+        for (ii, index) in input.iter().enumerate() {
+            if *index >= self.n {
+                return Err(Error::ValueOutOfRange {
+                    value: index.to_string(),
+                    min: String::from("0"),
+                    max: (self.n - 1).to_string(),
+                });
+            }
+            if let Some(first) = seen[*index].replace(ii) {
+                return Err(Error::DuplicateElement { value: index.to_string(), index: ii, first });
+            }
+        }
+        // Each digit of the Lehmer code is the number of later indices that
+        // are smaller, in the factorial number system
+        let mut value: Digit = 0;
+        for (ii, index) in input.iter().enumerate() {
+            let smaller = input[ii + 1..].iter().filter(|later| *later < index).count();
+            value = value * (self.n - ii) as Digit + smaller as Digit;
+        }
+        Ok(value)
+    }
+
+    fn decode(&self, input: Digit) -> Result<Vec<usize>, Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let mut code = vec![0; self.n];
+        let mut value = input;
+        for ii in (0..self.n).rev() {
+            let radix = (self.n - ii) as Digit;
+            code[ii] = (value % radix) as usize;
+            value /= radix;
+        }
+        let mut remaining: Vec<usize> = (0..self.n).collect();
+        Ok(code.into_iter().map(|smaller| remaining.remove(smaller)).collect())
+    }
+}
+
+// Utility functions
+
+/// Binomial coefficient `C(n, k)`, if it fits in a Digit
+pub(crate) fn binomial(n: Digit, k: Digit) -> Option<Digit> {
+    if k > n {
+        return Some(0);
+    }
+    // The coefficients grow up to k = n / 2, so if one on the way overflows
+    // the result does too
+    let k = k.min(n - k);
+    let mut result: Digit = 1;
+    for ii in 0..k {
+        let next = result as DoubleDigit * (n - ii) as DoubleDigit / (ii + 1) as DoubleDigit;
+        result = Digit::try_from(next).ok()?;
+    }
+    Some(result)
+}
+
+/// Rank of a combination of increasing indices in the combinatorial number
+/// system
+pub(crate) fn rank_combination(indices: &[usize]) -> Digit {
+    indices.iter().enumerate().map(|(ii, index)| saturating_binomial(*index, ii + 1)).sum()
+}
+
+/// Increasing indices below n of the combination of k indices with a rank
+pub(crate) fn unrank_combination(mut rank: Digit, k: usize, n: usize) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(k);
+    let mut limit = n;
+    for ii in (1..=k).rev() {
+        // Largest index below the previous one with C(index, ii) <= rank,
+        // C(ii - 1, ii) is zero so there always is one
+        let mut low = ii - 1;
+        let mut high = limit - 1;
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if saturating_binomial(mid, ii) <= rank {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        rank -= saturating_binomial(low, ii);
+        indices.push(low);
+        limit = low;
+    }
+    indices.reverse();
+    indices
+}
+
+/// Binomial coefficient that is `Digit::MAX` if it does not fit, which is
+/// larger than any rank
+fn saturating_binomial(n: usize, k: usize) -> Digit {
+    binomial(n as Digit, k as Digit).unwrap_or(Digit::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::accum::Digit;
+    use crate::data::{DataSpec, MultisetSpec, PermutationSpec, SetSize, SortedSetSpec};
+    use crate::error::Error;
+
+    use super::{binomial, rank_combination, unrank_combination};
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| String::from(*value)).collect()
+    }
+
+    #[test]
+    fn binomials() {
+        assert_eq!(binomial(5, 0), Some(1));
+        assert_eq!(binomial(5, 2), Some(10));
+        assert_eq!(binomial(5, 5), Some(1));
+        assert_eq!(binomial(3, 4), Some(0));
+        assert_eq!(binomial(20, 3), Some(1140));
+        #[cfg(not(feature = "digit32"))]
+        assert_eq!(binomial(60, 30), Some(118264581564861424));
+        assert_eq!(binomial(68, 34), None);
+        assert_eq!(binomial(1000, 999), Some(1000));
+    }
+
+    #[test]
+    fn combinations() {
+        // Hand computed ranks of the 2 element combinations of 4
+        let ranks = [[0, 1], [0, 2], [1, 2], [0, 3], [1, 3], [2, 3]];
+        for (rank, indices) in ranks.iter().enumerate() {
+            assert_eq!(rank_combination(indices), rank as Digit);
+            assert_eq!(unrank_combination(rank as Digit, 2, 4), indices);
+        }
+        assert_eq!(rank_combination(&[]), 0);
+        assert_eq!(unrank_combination(0, 0, 4), Vec::<usize>::new());
+        for rank in 0..1140 {
+            let indices = unrank_combination(rank, 3, 20);
+            assert!(indices.windows(2).all(|pair| pair[0] < pair[1]) && indices[2] < 20);
+            assert_eq!(rank_combination(&indices), rank);
+        }
+    }
+
+    #[test]
+    fn sorted_set() {
+        let toppings = [
+            "cheese", "ham", "pineapple", "mushroom", "olive", "onion", "pepper", "salami", "spinach", "tomato",
+            "anchovy", "bacon", "basil", "chicken", "corn", "egg", "garlic", "jalapeno", "rocket", "tuna",
+        ];
+        let spec = SortedSetSpec::new(&toppings, SetSize::Exactly(3));
+        assert_eq!(spec.permutations(), 1140);
+        assert_eq!(spec.encode(&strings(&["cheese", "ham", "pineapple"])), Ok(0));
+        assert_eq!(spec.encode(&strings(&["pineapple", "cheese", "ham"])), Ok(0));
+        assert_eq!(spec.encode(&strings(&["cheese", "ham", "mushroom"])), Ok(1));
+        assert_eq!(spec.encode(&strings(&["jalapeno", "rocket", "tuna"])), Ok(1139));
+        assert_eq!(spec.decode(1139), Ok(strings(&["jalapeno", "rocket", "tuna"])));
+        assert_eq!(spec.decode(1140), Err(Error::DecodeOutOfBounds { value: 1140, permutations: 1140 }));
+        for value in (0..1140).step_by(7) {
+            assert_eq!(spec.encode(&spec.decode(value).unwrap()), Ok(value));
+        }
+        assert_eq!(
+            spec.encode(&strings(&["cheese", "ham", "cheese"])),
+            Err(Error::DuplicateElement { value: String::from("cheese"), index: 2, first: 0 }),
+        );
+        assert_eq!(
+            spec.encode(&strings(&["cheese", "ham", "kiwi"])),
+            Err(Error::UnknownEnumVariant(String::from("kiwi"))),
+        );
+        assert_eq!(spec.encode(&strings(&["cheese"])), Err(Error::InvalidLength { expected: 3, actual: 1 }));
+    }
+
+    #[test]
+    fn sorted_set_at_most() {
+        let spec = SortedSetSpec::new(&["a", "b", "c", "d"], SetSize::AtMost(2));
+        // 1 + 4 + 6
+        assert_eq!(spec.permutations(), 11);
+        assert_eq!(spec.encode(&strings(&[])), Ok(0));
+        assert_eq!(spec.encode(&strings(&["a"])), Ok(1));
+        assert_eq!(spec.encode(&strings(&["d"])), Ok(4));
+        assert_eq!(spec.encode(&strings(&["a", "b"])), Ok(5));
+        assert_eq!(spec.encode(&strings(&["d", "c"])), Ok(10));
+        for value in 0..11 {
+            assert_eq!(spec.encode(&spec.decode(value).unwrap()), Ok(value));
+        }
+        assert_eq!(spec.decode(0), Ok(Vec::new()));
+        assert_eq!(spec.encode(&strings(&["a", "b", "c"])), Err(Error::SequenceTooLong { len: 3, max: 2 }));
+        assert!(matches!(SortedSetSpec::try_new(&["a", "b"], SetSize::Exactly(3)), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(SortedSetSpec::try_new(&["a", "a"], SetSize::Exactly(1)), Err(Error::DuplicateOption { .. })));
+        let names: Vec<String> = (0..200).map(|ii| ii.to_string()).collect();
+        let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        assert_eq!(SortedSetSpec::try_new(&names, SetSize::AtMost(100)).err(), Some(Error::TooManyPermutations));
+        // Most of the sets are too many to count, but only the few sets of
+        // the largest size are used
+        let spec = SortedSetSpec::new(&names, SetSize::Exactly(199));
+        assert_eq!(spec.permutations(), 200);
+        assert_eq!(spec.encode(&spec.decode(123).unwrap()), Ok(123));
+    }
+
+    #[test]
+    fn multiset() {
+        // Hand computed ranks of the multisets of 3 from 2
+        let spec = MultisetSpec::new(2, 3);
+        assert_eq!(spec.permutations(), 4);
+        let ranks = [[0, 0, 0], [0, 0, 1], [0, 1, 1], [1, 1, 1]];
+        for (rank, indices) in ranks.iter().enumerate() {
+            assert_eq!(spec.encode(&indices.to_vec()), Ok(rank as Digit));
+            assert_eq!(spec.decode(rank as Digit), Ok(indices.to_vec()));
+        }
+        assert_eq!(spec.encode(&vec![1, 0, 1]), spec.encode(&vec![1, 1, 0]));
+        assert_eq!(spec.decode(4), Err(Error::DecodeOutOfBounds { value: 4, permutations: 4 }));
+        // Three dice
+        let dice = MultisetSpec::new(6, 3);
+        assert_eq!(dice.permutations(), 56);
+        assert_eq!(dice.encode(&vec![4, 0, 2]), dice.encode(&vec![0, 2, 4]));
+        assert_eq!(dice.encode(&vec![2, 4, 0]), dice.encode(&vec![4, 2, 0]));
+        for value in 0..56 {
+            let rolled = dice.decode(value).unwrap();
+            assert!(rolled.windows(2).all(|pair| pair[0] <= pair[1]) && rolled[2] < 6);
+            assert_eq!(dice.encode(&rolled), Ok(value));
+        }
+        assert_eq!(dice.encode(&vec![5, 5, 5]), Ok(55));
+        assert!(matches!(dice.encode(&vec![0, 6, 1]), Err(Error::ValueOutOfRange { .. })));
+        assert_eq!(dice.encode(&vec![0, 1]), Err(Error::InvalidLength { expected: 3, actual: 2 }));
+        let empty = MultisetSpec::new(6, 0);
+        assert_eq!(empty.permutations(), 1);
+        assert_eq!(empty.decode(0), Ok(vec![]));
+        assert!(MultisetSpec::try_new(0, 3).is_err());
+        assert_eq!(MultisetSpec::try_new(100, 100).err(), Some(Error::TooManyPermutations));
+        assert_eq!(MultisetSpec::try_new(usize::MAX, 2).err(), Some(Error::TooManyPermutations));
+        // Sizes past a 32 bit Digit are not truncated
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(MultisetSpec::try_new((1 << 32) + 1, 3).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn permutation() {
+        let spec = PermutationSpec::new(4);
+        assert_eq!(spec.permutations(), 24);
+        assert_eq!(spec.encode(&vec![0, 1, 2, 3]), Ok(0));
+        assert_eq!(spec.encode(&vec![3, 2, 1, 0]), Ok(23));
+        // Lehmer code 1, 2, 0, 0 is 1 * 3! + 2 * 2! + 0 * 1!
+        assert_eq!(spec.encode(&vec![1, 3, 0, 2]), Ok(10));
+        assert_eq!(spec.decode(10), Ok(vec![1, 3, 0, 2]));
+        assert_eq!(spec.decode(0), Ok(vec![0, 1, 2, 3]));
+        assert_eq!(spec.decode(23), Ok(vec![3, 2, 1, 0]));
+        assert_eq!(spec.decode(24), Err(Error::DecodeOutOfBounds { value: 24, permutations: 24 }));
+        for value in 0..24 {
+            assert_eq!(spec.encode(&spec.decode(value).unwrap()), Ok(value));
+        }
+        assert_eq!(
+            spec.encode(&vec![1, 3, 1, 2]),
+            Err(Error::DuplicateElement { value: String::from("1"), index: 2, first: 0 }),
+        );
+        assert!(matches!(spec.encode(&vec![0, 1, 2, 4]), Err(Error::ValueOutOfRange { .. })));
+        assert_eq!(spec.encode(&vec![0, 1, 2]), Err(Error::InvalidLength { expected: 4, actual: 3 }));
+        assert_eq!(PermutationSpec::new(0).permutations(), 1);
+        assert_eq!(PermutationSpec::new(0).decode(0), Ok(vec![]));
+        let largest = if Digit::BITS == 64 { 20 } else { 12 };
+        let spec = PermutationSpec::new(largest);
+        let reversed: Vec<usize> = (0..largest).rev().collect();
+        assert_eq!(spec.encode(&reversed), Ok(spec.permutations() - 1));
+        assert_eq!(spec.decode(spec.permutations() - 1), Ok(reversed));
+        assert_eq!(PermutationSpec::try_new(largest + 1).err(), Some(Error::TooManyPermutations));
+    }
+}
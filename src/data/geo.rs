@@ -0,0 +1,578 @@
+//! Geographic data definitions
+
+use core::result::Result;
+use alloc::string::{String, ToString};
+
+use crate::accum::Digit;
+use crate::data::{digit_to_u32, DataSpec};
+use crate::error::Error;
+
+/// Mean radius of the earth in meters
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Length of one degree along a great circle in meters
+const METERS_PER_DEGREE: f64 = EARTH_RADIUS * core::f64::consts::PI / 180.0;
+
+/// Coordinate as a (latitude, longitude) tuple in degrees, on a grid fine
+/// enough that every point decodes to within a distance in meters of itself
+///
+/// Latitude and longitude use the same step in degrees, sized for the
+/// equator where a degree of longitude is longest, so points away from the
+/// equator decode more precisely than promised. Points decode to the center
+/// of their grid cell.
+///
+/// Note: The compression used by this data type is not lossless
+pub struct LatLonSpec {
+    lat_cells: Digit,
+    lon_cells: Digit,
+}
+
+impl LatLonSpec {
+
+    pub fn new(precision_meters: f64) -> Self {
+        Self::try_new(precision_meters).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new LatLonSpec where decoded points are at most
+    /// precision_meters from the encoded point, or an error if the precision
+    /// is not positive or needs too many permutations
+    pub fn try_new(precision_meters: f64) -> Result<Self, Error> {
+        if !precision_meters.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        if precision_meters <= 0.0 {
+            return Err(Error::ZeroStep);
+        }
+        // The farthest point from the center of a cell is a corner
+        let step = precision_meters * core::f64::consts::SQRT_2 / METERS_PER_DEGREE;
+        let cells = |degrees: f64| libm::ceil(degrees / step).max(1.0);
+        let (lat_cells, lon_cells) = (cells(180.0), cells(360.0));
+        if lat_cells * lon_cells >= Digit::MAX as f64 {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { lat_cells: lat_cells as Digit, lon_cells: lon_cells as Digit })
+    }
+
+    /// Largest distance in meters between a point and its decoded point
+    pub fn actual_precision_meters(&self) -> f64 {
+        let lat_step = 180.0 / self.lat_cells as f64;
+        let lon_step = 360.0 / self.lon_cells as f64;
+        libm::sqrt(lat_step * lat_step + lon_step * lon_step) / 2.0 * METERS_PER_DEGREE
+    }
+}
+
+impl DataSpec<(f64, f64)> for LatLonSpec {
+
+    fn permutations(&self) -> Digit {
+        self.lat_cells * self.lon_cells
+    }
+
+    fn encode(&self, input: &(f64, f64)) -> Result<Digit, Error> {
+        let (lat, lon) = *input;
+        let lat = cell(lat, 90.0, self.lat_cells)?;
+        let lon = cell(lon, 180.0, self.lon_cells)?;
+        Ok(lat * self.lon_cells + lon)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(f64, f64), Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        let center = |cell: Digit, limit: f64, cells: Digit| {
+            (cell as f64 + 0.5) * (2.0 * limit / cells as f64) - limit
+        };
+        Ok((
+            center(input / self.lon_cells, 90.0, self.lat_cells),
+            center(input % self.lon_cells, 180.0, self.lon_cells),
+        ))
+    }
+}
+
+/// Angle in degrees (or radians with `radians`) divided into equal buckets 
+/// around a full turn
+///
+/// Any finite angle is accepted and wrapped into a single turn before it is 
+/// encoded (e.g. 360 degrees encodes the same as 0 and -90 the same as 270),
+/// and values decode to the center of their bucket.
+///
+/// Note: The compression used by this data type is not lossless
+pub struct AngleSpec {
+    steps: u32,
+    /// Size of a full turn in the unit of the angle
+    turn: f64,
+}
+
+impl AngleSpec {
+
+    pub fn new(steps: u32) -> Self {
+        Self::try_new(steps).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new AngleSpec in degrees with a number of buckets, or an 
+    /// error if there are no buckets
+    pub fn try_new(steps: u32) -> Result<Self, Error> {
+        if steps == 0 {
+            return Err(Error::ZeroStep);
+        }
+        Ok(Self { steps, turn: 360.0 })
+    }
+
+    pub fn radians(steps: u32) -> Self {
+        Self::try_radians(steps).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new AngleSpec in radians with a number of buckets, or an 
+    /// error if there are no buckets
+    pub fn try_radians(steps: u32) -> Result<Self, Error> {
+        Ok(Self { turn: 2.0 * core::f64::consts::PI, ..Self::try_new(steps)? })
+    }
+}
+
+impl DataSpec<f64> for AngleSpec {
+
+    fn permutations(&self) -> Digit {
+        self.steps as Digit
+    }
+
+    fn encode(&self, input: &f64) -> Result<Digit, Error> {
+        if !input.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        let mut angle = libm::fmod(*input, self.turn);
+        if angle < 0.0 {
+            angle += self.turn;
+        }
+        let bucket = libm::floor(angle / self.turn * self.steps as f64) as Digit;
+        // Angles just below a full turn may round up to it
+        Ok(bucket % self.steps as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<f64, Error> {
+        if input >= self.steps as Digit {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.steps as Digit });
+        }
+        Ok((input as f64 + 0.5) * self.turn / self.steps as f64)
+    }
+}
+
+/// Position on a grid as an (x, y) tuple, with x from 0 to width - 1 and y
+/// from 0 to height - 1
+///
+/// Positions are compressed in row major order (`y * width + x`) by default,
+/// or in column major order (`x * height + y`) with `with_column_major`.
+pub struct GridPositionSpec {
+    width: u32,
+    height: u32,
+    column_major: bool,
+}
+
+impl GridPositionSpec {
+
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::try_new(width, height).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new GridPositionSpec, or an error if the grid is empty or has
+    /// too many positions
+    pub fn try_new(width: u32, height: u32) -> Result<Self, Error> {
+        for size in [width, height] {
+            if size == 0 {
+                return Err(Error::InvalidRange { min: 0.to_string(), max: size.to_string() });
+            }
+        }
+        if Digit::try_from(width as u64 * height as u64).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { width, height, column_major: false })
+    }
+
+    /// Compress positions in column major order
+    pub fn with_column_major(mut self) -> Self {
+        self.column_major = true;
+        self
+    }
+}
+
+impl DataSpec<(u32, u32)> for GridPositionSpec {
+
+    fn permutations(&self) -> Digit {
+        self.width as Digit * self.height as Digit
+    }
+
+    fn encode(&self, input: &(u32, u32)) -> Result<Digit, Error> {
+        let (x, y) = *input;
+        for (value, size) in [(x, self.width), (y, self.height)] {
+            if value >= size {
+                return Err(Error::ValueOutOfRange {
+                    value: value.to_string(),
+                    min: 0.to_string(),
+                    max: (size - 1).to_string(),
+                });
+            }
+        }
+        if self.column_major {
+            Ok(x as Digit * self.height as Digit + y as Digit)
+        } else {
+            Ok(y as Digit * self.width as Digit + x as Digit)
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<(u32, u32), Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        // Both parts are less than the width or height, so always fit
+        let part = |value: Digit| {
+            digit_to_u32(value).ok_or(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() })
+        };
+        if self.column_major {
+            Ok((part(input / self.height as Digit)?, part(input % self.height as Digit)?))
+        } else {
+            Ok((part(input % self.width as Digit)?, part(input / self.width as Digit)?))
+        }
+    }
+}
+
+/// Characters of the geohash base 32 alphabet, in order
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Geohash string of a fixed number of characters
+///
+/// Geohashes are compressed as the number they represent, so a geohash that 
+/// is a prefix of another is also a prefix of it in the compressed number.
+pub struct GeohashSpec {
+    precision_chars: u8,
+}
+
+impl GeohashSpec {
+
+    pub fn new(precision_chars: u8) -> Self {
+        Self::try_new(precision_chars).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new GeohashSpec for geohashes of precision_chars characters 
+    /// (at most 12, or 6 with 32 bit digits), or an error if there are too
+    /// many permutations
+    pub fn try_new(precision_chars: u8) -> Result<Self, Error> {
+        (32 as Digit).checked_pow(precision_chars as u32).ok_or(Error::TooManyPermutations)?;
+        Ok(Self { precision_chars })
+    }
+
+    /// Geohash of a (latitude, longitude) point in degrees
+    pub fn encode_latlon(&self, lat: f64, lon: f64) -> Result<String, Error> {
+        let mut ranges = [(-180.0, 180.0, lon), (-90.0, 90.0, lat)];
+        for (min, max, value) in ranges {
+            if value.is_nan() {
+                return Err(Error::NonFiniteValue);
+            }
+            if !(min..=max).contains(&value) {
+                return Err(Error::ValueOutOfRange { value: value.to_string(), min: min.to_string(), max: max.to_string() });
+            }
+        }
+        let mut geohash = String::with_capacity(self.precision_chars as usize);
+        // Bits alternate between longitude and latitude, starting with longitude
+        let mut bit = 0;
+        for _ in 0..self.precision_chars {
+            let mut index = 0;
+            for _ in 0..5 {
+                let (min, max, value) = &mut ranges[bit % 2];
+                let mid = (*min + *max) / 2.0;
+                index <<= 1;
+                if *value >= mid {
+                    index |= 1;
+                    *min = mid;
+                } else {
+                    *max = mid;
+                }
+                bit += 1;
+            }
+            geohash.push(GEOHASH_ALPHABET[index] as char);
+        }
+        Ok(geohash)
+    }
+
+    /// Center (latitude, longitude) in degrees of the cell of a geohash
+    pub fn decode_latlon(&self, geohash: &str) -> Result<(f64, f64), Error> {
+        let index = self.encode(&String::from(geohash))?;
+        let mut ranges = [(-180.0, 180.0), (-90.0, 90.0)];
+        let bits = self.precision_chars as u32 * 5;
+        for bit in 0..bits {
+            let (min, max) = &mut ranges[bit as usize % 2];
+            let mid = (*min + *max) / 2.0;
+            if (index >> (bits - 1 - bit)) & 1 == 1 {
+                *min = mid;
+            } else {
+                *max = mid;
+            }
+        }
+        let [(lon_min, lon_max), (lat_min, lat_max)] = ranges;
+        Ok(((lat_min + lat_max) / 2.0, (lon_min + lon_max) / 2.0))
+    }
+}
+
+impl DataSpec<String> for GeohashSpec {
+
+    fn permutations(&self) -> Digit {
+        (32 as Digit).pow(self.precision_chars as u32)
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        let length = input.chars().count();
+        if length != self.precision_chars as usize {
+            return Err(Error::InvalidLength { expected: self.precision_chars as usize, actual: length });
+        }
+        let mut index: Digit = 0;
+        for c in input.chars() {
+            let digit = GEOHASH_ALPHABET.iter()
+                .position(|g| *g as char == c)
+                .ok_or(Error::CharNotInSet(c))?;
+            index = index << 5 | digit as Digit;
+        }
+        Ok(index)
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok((0..self.precision_chars as u32)
+            .rev()
+            .map(|ii| GEOHASH_ALPHABET[(input >> (5 * ii)) as usize & 31] as char)
+            .collect())
+    }
+}
+
+/// Cell of a value from -limit to limit (inclusive) split into equal cells
+fn cell(value: f64, limit: f64, cells: Digit) -> Result<Digit, Error> {
+    if value.is_nan() {
+        return Err(Error::NonFiniteValue);
+    }
+    if !(-limit..=limit).contains(&value) {
+        return Err(Error::ValueOutOfRange {
+            value: value.to_string(),
+            min: (-limit).to_string(),
+            max: limit.to_string(),
+        });
+    }
+    let cell = libm::floor((value + limit) / (2.0 * limit) * cells as f64) as Digit;
+    Ok(cell.min(cells - 1))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::accum::{Accumulator, Digit};
+    use crate::data::{AngleSpec, DataSpec, GeohashSpec, GridPositionSpec, LatLonSpec, SequenceLength, Sequencer};
+    use crate::error::Error;
+
+    use super::EARTH_RADIUS;
+
+    /// Great circle distance between two points in meters
+    fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+        let (lat0, lon0) = (a.0.to_radians(), a.1.to_radians());
+        let (lat1, lon1) = (b.0.to_radians(), b.1.to_radians());
+        let h = libm::pow(libm::sin((lat1 - lat0) / 2.0), 2.0)
+            + libm::cos(lat0) * libm::cos(lat1) * libm::pow(libm::sin((lon1 - lon0) / 2.0), 2.0);
+        2.0 * EARTH_RADIUS * libm::asin(libm::sqrt(h))
+    }
+
+    #[test]
+    fn lat_lon() {
+        let points = [
+            (0.0, 0.0),
+            (0.000049, -0.000049),
+            (-0.3, 32.58),
+            (51.4779, -0.0015),
+            (89.9999, 45.0),
+            (90.0, 0.0),
+            (-90.0, 180.0),
+            (-89.95, -179.99),
+            (0.0, 180.0),
+            (0.0, -180.0),
+            (-16.5, 179.9999),
+        ];
+        #[cfg(not(feature = "digit32"))]
+        let precisions = [10.0, 1.0, 1000.0];
+        #[cfg(feature = "digit32")]
+        let precisions = [1000.0, 5000.0];
+        for precision in precisions {
+            let spec = LatLonSpec::new(precision);
+            assert!(spec.actual_precision_meters() <= precision);
+            assert!(spec.actual_precision_meters() > precision * 0.99);
+            for point in points {
+                let decoded = spec.decode(spec.encode(&point).unwrap()).unwrap();
+                let d = distance(point, decoded);
+                assert!(d <= spec.actual_precision_meters() * (1.0 + 1e-9), "{:?} -> {:?} ({} m)", point, decoded, d);
+            }
+        }
+        let spec = LatLonSpec::new(1000.0);
+        assert_eq!(spec.encode(&(-90.0, -180.0)), Ok(0));
+        assert_eq!(spec.encode(&(90.0, 180.0)), Ok(spec.permutations() - 1));
+        assert!(spec.decode(spec.permutations()).is_err());
+        assert!(matches!(spec.encode(&(90.1, 0.0)), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(spec.encode(&(0.0, -180.1)), Err(Error::ValueOutOfRange { .. })));
+        assert_eq!(spec.encode(&(f64::NAN, 0.0)), Err(Error::NonFiniteValue));
+        // Coarser grids need fewer permutations
+        assert!(LatLonSpec::new(10000.0).permutations() < spec.permutations() / 99);
+        assert_eq!(LatLonSpec::new(1e8).permutations(), 1);
+        assert_eq!(LatLonSpec::try_new(0.0).err(), Some(Error::ZeroStep));
+        assert_eq!(LatLonSpec::try_new(f64::NAN).err(), Some(Error::NonFiniteValue));
+        #[cfg(not(feature = "digit32"))]
+        assert!(LatLonSpec::try_new(1e-3).is_err());
+        #[cfg(feature = "digit32")]
+        assert!(LatLonSpec::try_new(100.0).is_err());
+    }
+
+    #[test]
+    fn seq_lat_lon() {
+        let mut a = Accumulator::new();
+        let route = &[(47.6062, -122.3321), (45.5152, -122.6784), (37.7749, -122.4194)];
+        let spec = LatLonSpec::new(1000.0);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(8));
+        sequencer.compress(route, &mut a).unwrap();
+        let decoded = sequencer.decompress(&mut a).unwrap();
+        assert_eq!(decoded.len(), route.len());
+        for (point, decoded) in route.iter().zip(decoded) {
+            assert!(distance(*point, decoded) <= 1000.0);
+        }
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn angle() {
+        let spec = AngleSpec::new(360);
+        assert_eq!(spec.permutations(), 360);
+        assert_eq!(spec.encode(&0.0), Ok(0));
+        assert_eq!(spec.encode(&360.0), spec.encode(&0.0));
+        assert_eq!(spec.encode(&720.5), spec.encode(&0.5));
+        assert_eq!(spec.encode(&-90.0), spec.encode(&270.0));
+        assert_eq!(spec.encode(&-90.0), Ok(270));
+        assert_eq!(spec.encode(&365.0), Ok(5));
+        assert_eq!(spec.encode(&-0.5), Ok(359));
+        // Wrapped into a full turn by rounding, which is the same as 0
+        assert_eq!(spec.encode(&-1e-300), Ok(0));
+        assert_eq!(spec.encode(&359.999999), Ok(359));
+        assert_eq!(spec.decode(0), Ok(0.5));
+        assert_eq!(spec.decode(359), Ok(359.5));
+        assert!(spec.decode(360).is_err());
+        assert_eq!(spec.encode(&f64::NAN), Err(Error::NonFiniteValue));
+        assert_eq!(spec.encode(&f64::INFINITY), Err(Error::NonFiniteValue));
+        let spec = AngleSpec::new(16);
+        assert_eq!(spec.permutations(), 16);
+        for (heading, bucket) in [(0.0, 0), (22.5, 1), (180.0, 8), (337.5, 15), (-22.5, 15)] {
+            assert_eq!(spec.encode(&heading), Ok(bucket), "{}", heading);
+        }
+        let spec = AngleSpec::radians(1024);
+        let pi = core::f64::consts::PI;
+        assert_eq!(spec.permutations(), 1024);
+        assert_eq!(spec.encode(&(2.0 * pi)), Ok(0));
+        assert_eq!(spec.encode(&-pi), spec.encode(&pi));
+        assert_eq!(spec.encode(&(pi / 2.0)), Ok(256));
+        let decoded = spec.decode(spec.encode(&1.0).unwrap()).unwrap();
+        assert!((decoded - 1.0).abs() <= pi / 1024.0);
+        assert_eq!(AngleSpec::try_new(0).err(), Some(Error::ZeroStep));
+        assert_eq!(AngleSpec::try_radians(0).err(), Some(Error::ZeroStep));
+    }
+
+    #[test]
+    fn grid_position() {
+        let spec = GridPositionSpec::new(8, 6);
+        assert_eq!(spec.permutations(), 48);
+        for (position, index) in [((0, 0), 0), ((7, 0), 7), ((0, 5), 40), ((7, 5), 47), ((3, 2), 19)] {
+            assert_eq!(spec.encode(&position), Ok(index));
+            assert_eq!(spec.decode(index), Ok(position));
+        }
+        assert!(spec.decode(48).is_err());
+        assert_eq!(spec.encode(&(8, 0)), Err(Error::ValueOutOfRange {
+            value: String::from("8"),
+            min: String::from("0"),
+            max: String::from("7"),
+        }));
+        assert!(spec.encode(&(0, 6)).is_err());
+        assert!(spec.encode(&(u32::MAX, u32::MAX)).is_err());
+        let spec = GridPositionSpec::new(8, 6).with_column_major();
+        for (position, index) in [((0, 0), 0), ((0, 5), 5), ((7, 0), 42), ((7, 5), 47), ((3, 2), 20)] {
+            assert_eq!(spec.encode(&position), Ok(index));
+            assert_eq!(spec.decode(index), Ok(position));
+        }
+        assert!(spec.encode(&(8, 0)).is_err());
+        assert_eq!(GridPositionSpec::new(1, 1).permutations(), 1);
+        assert!(matches!(GridPositionSpec::try_new(0, 6), Err(Error::InvalidRange { .. })));
+        assert!(matches!(GridPositionSpec::try_new(8, 0), Err(Error::InvalidRange { .. })));
+        #[cfg(not(feature = "digit32"))]
+        assert_eq!(GridPositionSpec::new(u32::MAX, u32::MAX).permutations(), u32::MAX as Digit * u32::MAX as Digit);
+        #[cfg(feature = "digit32")]
+        assert_eq!(GridPositionSpec::try_new(1 << 16, 1 << 16).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn seq_grid_position() {
+        // Path of a knight on a chess board
+        let mut a = Accumulator::new();
+        let path = &[(1, 0), (2, 2), (4, 3), (6, 4), (7, 6), (5, 7)];
+        let spec = GridPositionSpec::new(8, 8);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(64));
+        sequencer.compress(path, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), path);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn geohash() {
+        // Example coordinate from the geohash documentation
+        #[cfg(not(feature = "digit32"))]
+        {
+            let spec = GeohashSpec::new(11);
+            assert_eq!(spec.encode_latlon(57.64911, 10.40744), Ok(String::from("u4pruydqqvj")));
+            let (lat, lon) = spec.decode_latlon("u4pruydqqvj").unwrap();
+            assert!((lat - 57.64911).abs() < 1e-5 && (lon - 10.40744).abs() < 1e-5);
+        }
+        let spec = GeohashSpec::new(6);
+        assert_eq!(spec.encode_latlon(57.64911, 10.40744), Ok(String::from("u4pruy")));
+        let (lat, lon) = spec.decode_latlon("u4pruy").unwrap();
+        assert!((lat - 57.64911).abs() < 0.01 && (lon - 10.40744).abs() < 0.01);
+        assert_eq!(GeohashSpec::new(5).encode_latlon(42.6, -5.6), Ok(String::from("ezs42")));
+        assert_eq!(GeohashSpec::new(1).encode_latlon(-90.0, -180.0), Ok(String::from("0")));
+        assert_eq!(GeohashSpec::new(1).encode_latlon(90.0, 180.0), Ok(String::from("z")));
+        assert!(spec.encode_latlon(90.5, 0.0).is_err());
+        let spec = GeohashSpec::new(5);
+        assert_eq!(spec.permutations(), 1 << 25);
+        for geohash in ["00000", "zzzzz", "ezs42", "u4pru"] {
+            let geohash = String::from(geohash);
+            assert_eq!(spec.decode(spec.encode(&geohash).unwrap()), Ok(geohash));
+        }
+        assert_eq!(spec.encode(&String::from("00000")), Ok(0));
+        assert_eq!(spec.encode(&String::from("zzzzz")), Ok((1 << 25) - 1));
+        assert!(spec.decode(1 << 25).is_err());
+        for c in ['a', 'i', 'l', 'o', 'A', ' '] {
+            let geohash: String = ['e', 'z', 's', '4', c].iter().collect();
+            assert_eq!(spec.encode(&geohash), Err(Error::CharNotInSet(c)));
+        }
+        assert_eq!(spec.encode(&String::from("ezs4")), Err(Error::InvalidLength { expected: 5, actual: 4 }));
+        assert!(spec.encode(&String::from("ezs421")).is_err());
+        #[cfg(not(feature = "digit32"))]
+        assert_eq!(GeohashSpec::new(12).permutations(), 1 << 60);
+        #[cfg(feature = "digit32")]
+        assert_eq!(GeohashSpec::new(6).permutations(), 1 << 30);
+        assert_eq!(GeohashSpec::try_new(13).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn geohash_prefix() {
+        // A prefix of a geohash is a prefix of the compressed number
+        let fine = GeohashSpec::new(6);
+        let coarse = GeohashSpec::new(4);
+        let index = fine.encode(&String::from("u4pruy")).unwrap();
+        assert_eq!(coarse.decode(index >> 10), Ok(String::from("u4pr")));
+        let mut a = Accumulator::new();
+        let cells: Vec<String> = ["u4pruy", "u4pruz", "ezs42e"].iter().map(|s| String::from(*s)).collect();
+        let sequencer = Sequencer::new(&fine, SequenceLength::Variable(4));
+        sequencer.compress(&cells, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), cells);
+        assert!(a.is_empty());
+        assert_eq!(fine.permutations(), 1 << (6 * 5) as Digit);
+    }
+}
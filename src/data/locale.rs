@@ -0,0 +1,815 @@
+//! Money, currency, country, language, phone number and postal code data
+//! definitions
+//!
+//! The order of the code tables is part of the wire format, as a code is
+//! compressed as its index. The tables were alphabetical when they were
+//! created, codes are never removed or reordered, and codes added to a
+//! standard in the future are appended at the end.
+
+use core::result::Result;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::accum::{Accumulator, Digit};
+use crate::data::{decode_wide_index, encode_wide_index, DataSpec, Enum, PatternStringSpec, WideDataSpec};
+use crate::error::Error;
+
+/// ISO 4217 alphabetic currency codes
+const CURRENCY_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD",
+    "BDT", "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP",
+    "BYN", "BZD", "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU",
+    "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR",
+    "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL",
+    "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES",
+    "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD",
+    "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR",
+    "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF",
+    "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN",
+    "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS",
+    "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV",
+    "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR", "XOF",
+    "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+/// ISO 3166-1 alpha-2 country codes
+const COUNTRY_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// ISO 639-1 language codes
+const LANGUAGE_CODES: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bi",
+    "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da", "de",
+    "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr", "fy",
+    "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz", "ia",
+    "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj", "kk",
+    "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln", "lo",
+    "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb", "nd",
+    "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi", "pl",
+    "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk", "sl",
+    "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti", "tk",
+    "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo", "wa",
+    "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+/// Amount of money as a whole number of minor units (e.g. cents), from min to
+/// max (inclusive)
+///
+/// `encode_major` and `decode_major` convert from and to amounts in major
+/// units (e.g. dollars). Amounts in major units are only accepted when they
+/// are a whole number of minor units, they are never rounded.
+pub struct CurrencyAmountSpec {
+    min: i64,
+    max: i64,
+    places: u32,
+}
+
+impl CurrencyAmountSpec {
+
+    pub fn new(min_minor: i64, max_minor: i64) -> Self {
+        Self::try_new(min_minor, max_minor).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new CurrencyAmountSpec for a currency with 2 decimal places
+    /// (100 minor units in a major unit), or an error if the range is not
+    /// valid
+    pub fn try_new(min_minor: i64, max_minor: i64) -> Result<Self, Error> {
+        Self::try_new_with_places(min_minor, max_minor, 2)
+    }
+
+    pub fn new_with_places(min_minor: i64, max_minor: i64, places: u32) -> Self {
+        Self::try_new_with_places(min_minor, max_minor, places).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new CurrencyAmountSpec for a currency with a number of
+    /// decimal places (e.g. 0 for JPY or 3 for BHD), or an error if the range
+    /// is not valid
+    pub fn try_new_with_places(min_minor: i64, max_minor: i64, places: u32) -> Result<Self, Error> {
+        if 10i64.checked_pow(places).is_none() {
+            return Err(Error::InvalidDecimals(places));
+        }
+        if min_minor >= max_minor {
+            return Err(Error::InvalidRange { min: min_minor.to_string(), max: max_minor.to_string() });
+        }
+        if Digit::try_from(max_minor as i128 - min_minor as i128 + 1).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { min: min_minor, max: max_minor, places })
+    }
+
+    /// Compress an amount in major units (e.g. 19.99 dollars)
+    pub fn encode_major(&self, input: f64) -> Result<Digit, Error> {
+        if !input.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        let scale = 10i64.pow(self.places) as f64;
+        let minor = libm::round(input * scale);
+        // Only a whole number of minor units converts back to the same value
+        if minor / scale != input {
+            return Err(Error::ValueNotOnStep {
+                value: input.to_string(),
+                min: 0.to_string(),
+                step: (1.0 / scale).to_string(),
+            });
+        }
+        if minor < self.min as f64 || minor > self.max as f64 {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: (self.min as f64 / scale).to_string(),
+                max: (self.max as f64 / scale).to_string(),
+            });
+        }
+        self.encode(&(minor as i64))
+    }
+
+    /// Decompress an amount in major units
+    pub fn decode_major(&self, input: Digit) -> Result<f64, Error> {
+        let minor: i64 = self.decode(input)?;
+        Ok(minor as f64 / 10i64.pow(self.places) as f64)
+    }
+}
+
+impl DataSpec<i64> for CurrencyAmountSpec {
+
+    fn permutations(&self) -> Digit {
+        (self.max as i128 - self.min as i128 + 1) as Digit
+    }
+
+    fn encode(&self, input: &i64) -> Result<Digit, Error> {
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        Ok((*input as i128 - self.min as i128) as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<i64, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok((self.min as i128 + input as i128) as i64)
+    }
+}
+
+/// ISO 4217 three letter currency code (e.g. "USD")
+///
+/// `new` accepts every active code, indexed by its position in a table that
+/// never changes order. `from_subset` only accepts the given codes, indexed in
+/// the order they are given, which costs fewer bits when only a few
+/// currencies are possible.
+pub struct CurrencyCodeSpec {
+    codes: Enum,
+}
+
+impl CurrencyCodeSpec {
+
+    /// Create a new CurrencyCodeSpec for all active ISO 4217 codes
+    pub fn new() -> Self {
+        Self { codes: Enum::new(CURRENCY_CODES) }
+    }
+
+    pub fn from_subset(codes: &[&str]) -> Self {
+        Self::try_from_subset(codes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new CurrencyCodeSpec for some of the ISO 4217 codes, or an
+    /// error if a code is unknown or repeated
+    pub fn try_from_subset(codes: &[&str]) -> Result<Self, Error> {
+        if let Some(code) = codes.iter().find(|code| !CURRENCY_CODES.contains(code)) {
+            return Err(Error::UnknownEnumVariant(String::from(*code)));
+        }
+        Ok(Self { codes: Enum::try_new(codes)? })
+    }
+}
+
+impl Default for CurrencyCodeSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSpec<String> for CurrencyCodeSpec {
+
+    fn permutations(&self) -> Digit {
+        self.codes.permutations()
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        self.codes.encode(input)
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        self.codes.decode(input)
+    }
+}
+
+/// ISO 3166-1 alpha-2 country code (e.g. "US")
+///
+/// `new` accepts every officially assigned code, indexed by its position in a
+/// table that never changes order. `from_subset` only accepts the given codes,
+/// indexed in the order they are given. Codes must be uppercase unless the
+/// spec is built `with_normalization`, which uppercases them before encoding.
+pub struct CountryCodeSpec {
+    codes: Enum,
+    normalize: bool,
+}
+
+impl CountryCodeSpec {
+
+    /// Create a new CountryCodeSpec for all assigned ISO 3166-1 codes
+    pub fn new() -> Self {
+        Self { codes: Enum::new(COUNTRY_CODES), normalize: false }
+    }
+
+    pub fn from_subset(codes: &[&str]) -> Self {
+        Self::try_from_subset(codes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new CountryCodeSpec for some of the ISO 3166-1 codes, or an
+    /// error if a code is unknown or repeated
+    pub fn try_from_subset(codes: &[&str]) -> Result<Self, Error> {
+        if let Some(code) = codes.iter().find(|code| !COUNTRY_CODES.contains(code)) {
+            return Err(Error::UnknownEnumVariant(String::from(*code)));
+        }
+        Ok(Self { codes: Enum::try_new(codes)?, normalize: false })
+    }
+
+    /// Accept lowercase and mixed case codes, which decode as uppercase
+    pub fn with_normalization(mut self) -> Self {
+        self.normalize = true;
+        self
+    }
+}
+
+impl Default for CountryCodeSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSpec<String> for CountryCodeSpec {
+
+    fn permutations(&self) -> Digit {
+        self.codes.permutations()
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        if self.normalize {
+            self.codes.encode(&input.to_ascii_uppercase())
+        } else {
+            self.codes.encode(input)
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        self.codes.decode(input)
+    }
+}
+
+/// BCP 47 language tag (e.g. "en" or "pt-BR")
+///
+/// `new` accepts the ISO 639-1 primary language subtags, indexed by their
+/// position in a table that never changes order. `from_tags` accepts a list
+/// of full tags, indexed in the order they are given. Tags are matched
+/// without regard to case and decode in their canonical case (language
+/// lowercase, script titlecase and region uppercase).
+pub struct LanguageTagSpec {
+    tags: Enum,
+}
+
+impl LanguageTagSpec {
+
+    /// Create a new LanguageTagSpec for the ISO 639-1 language codes
+    pub fn new() -> Self {
+        Self { tags: Enum::new(LANGUAGE_CODES) }
+    }
+
+    pub fn from_tags(tags: &[&str]) -> Self {
+        Self::try_from_tags(tags).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new LanguageTagSpec for a list of tags, or an error if a tag
+    /// is repeated once normalized to its canonical case
+    pub fn try_from_tags(tags: &[&str]) -> Result<Self, Error> {
+        let canonical: Vec<String> = tags.iter().map(|tag| canonical_tag(tag)).collect();
+        Ok(Self { tags: Enum::try_from_vec(canonical)? })
+    }
+}
+
+impl Default for LanguageTagSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSpec<String> for LanguageTagSpec {
+
+    fn permutations(&self) -> Digit {
+        self.tags.permutations()
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        self.tags.encode(&canonical_tag(input)).map_err(|_| Error::UnknownEnumVariant(input.clone()))
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        self.tags.decode(input)
+    }
+}
+
+/// Most digits in an E.164 phone number, including the country code
+const MAX_PHONE_DIGITS: usize = 15;
+
+/// E.164 phone number (e.g. "+14155550123"), values are compressed as
+/// multiple Digits when needed
+///
+/// The number of digits is compressed along with their value, so leading
+/// zeros are kept. With `with_country_codes` only numbers that start with one
+/// of the country codes are accepted, which costs fewer bits.
+pub struct PhoneNumberSpec {
+    prefixes: Vec<String>,
+}
+
+impl PhoneNumberSpec {
+
+    /// Create a new PhoneNumberSpec for any number of 1 to 15 digits
+    pub fn new() -> Self {
+        Self { prefixes: vec![String::new()] }
+    }
+
+    pub fn with_country_codes(country_codes: &[u16]) -> Self {
+        Self::try_with_country_codes(country_codes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new PhoneNumberSpec for numbers with one of the country codes
+    /// (e.g. 1 or 44), or an error if a code is repeated or longer than 3
+    /// digits
+    pub fn try_with_country_codes(country_codes: &[u16]) -> Result<Self, Error> {
+        let mut prefixes: Vec<String> = Vec::with_capacity(country_codes.len());
+        for (ii, code) in country_codes.iter().enumerate() {
+            if !(1..=999).contains(code) {
+                return Err(Error::ValueOutOfRange { value: code.to_string(), min: 1.to_string(), max: 999.to_string() });
+            }
+            let prefix = code.to_string();
+            if let Some(first) = prefixes.iter().position(|p| *p == prefix) {
+                return Err(Error::DuplicateOption { value: prefix, index: ii, first });
+            }
+            prefixes.push(prefix);
+        }
+        Ok(Self { prefixes })
+    }
+
+    /// Number of numbers that start with a prefix
+    fn prefix_span(prefix: &str) -> u128 {
+        phone_offset(MAX_PHONE_DIGITS - prefix.len() + 1)
+    }
+
+    fn span(&self) -> u128 {
+        self.prefixes.iter().map(|prefix| Self::prefix_span(prefix)).sum::<u128>().saturating_sub(1)
+    }
+}
+
+impl Default for PhoneNumberSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WideDataSpec<String> for PhoneNumberSpec {
+
+    fn encode_into(&self, input: &String, accum: &mut Accumulator) -> Result<(), Error> {
+        let digits = match input.strip_prefix('+') {
+            Some(digits) if (1..=MAX_PHONE_DIGITS).contains(&digits.len())
+                && digits.bytes().all(|b| b.is_ascii_digit()) => digits,
+            _ => return Err(Error::InvalidPhoneNumber(input.clone())),
+        };
+        let mut index: u128 = 0;
+        for prefix in self.prefixes.iter() {
+            match digits.strip_prefix(prefix.as_str()) {
+                Some(national) if !national.is_empty() => {
+                    index += phone_offset(national.len()) + national.parse::<u128>().unwrap_or(0);
+                    return encode_wide_index(index, self.span(), accum);
+                },
+                _ => index += Self::prefix_span(prefix),
+            }
+        }
+        Err(Error::UnknownEnumVariant(input.clone()))
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let mut index = decode_wide_index(self.span(), accum)?;
+        for prefix in self.prefixes.iter() {
+            let span = Self::prefix_span(prefix);
+            if index >= span {
+                index -= span;
+                continue;
+            }
+            let mut length = 1;
+            while phone_offset(length + 1) <= index {
+                length += 1;
+            }
+            let national = index - phone_offset(length);
+            return Ok(format!("+{}{:0width$}", prefix, national, width = length));
+        }
+        Err(Error::DecodeOutOfRange { value: index.to_string(), max: self.span().to_string() })
+    }
+}
+
+/// Postal code that follows one of several named patterns (e.g. US "99999"
+/// and CA "A9A 9A9"), using the pattern syntax of PatternStringSpec
+///
+/// A postal code is compressed as the pattern it matches and its value in
+/// that pattern. When a postal code matches more than one pattern, the first
+/// one is always used.
+pub struct PostalCodeSpec {
+    names: Vec<String>,
+    patterns: Vec<PatternStringSpec>,
+    permutations: Digit,
+}
+
+impl PostalCodeSpec {
+
+    pub fn new(patterns: &[(&str, &str)]) -> Self {
+        Self::try_new(patterns).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new PostalCodeSpec from names and patterns, names do not need
+    /// to be unique (e.g. for the several UK postcode formats), or an error if
+    /// a pattern is invalid or there are too many permutations
+    pub fn try_new(patterns: &[(&str, &str)]) -> Result<Self, Error> {
+        let mut permutations: Digit = 0;
+        let mut specs = Vec::with_capacity(patterns.len());
+        for (_, pattern) in patterns {
+            let spec = PatternStringSpec::try_new(pattern)?;
+            permutations = permutations.checked_add(spec.permutations()).ok_or(Error::TooManyPermutations)?;
+            specs.push(spec);
+        }
+        Ok(Self {
+            names: patterns.iter().map(|(name, _)| String::from(*name)).collect(),
+            patterns: specs,
+            permutations,
+        })
+    }
+
+    /// Name of the first pattern that a postal code matches, or an error if
+    /// it does not match any of them
+    pub fn name_of(&self, input: &str) -> Result<&str, Error> {
+        let input = String::from(input);
+        self.patterns.iter()
+            .position(|pattern| pattern.encode(&input).is_ok())
+            .map(|index| self.names[index].as_str())
+            .ok_or(Error::UnknownEnumVariant(input))
+    }
+}
+
+impl DataSpec<String> for PostalCodeSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        let mut offset: Digit = 0;
+        for pattern in self.patterns.iter() {
+            if let Ok(value) = pattern.encode(input) {
+                return Ok(offset + value);
+            }
+            offset += pattern.permutations();
+        }
+        Err(Error::UnknownEnumVariant(input.clone()))
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        let mut value = input;
+        for pattern in self.patterns.iter() {
+            if value < pattern.permutations() {
+                return pattern.decode(value);
+            }
+            value -= pattern.permutations();
+        }
+        Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations })
+    }
+}
+
+// Utility functions
+
+/// Number of numbers with fewer digits than length (at least one digit)
+fn phone_offset(length: usize) -> u128 {
+    (10u128.pow(length as u32) - 10) / 9
+}
+
+/// Convert a language tag to the case recommended by BCP 47, subtags after
+/// a single letter extension or private use subtag are left lowercase
+fn canonical_tag(tag: &str) -> String {
+    let mut canonical = String::with_capacity(tag.len());
+    let mut extension = false;
+    for (ii, subtag) in tag.split('-').enumerate() {
+        if ii > 0 {
+            canonical.push('-');
+        }
+        if ii > 0 && !extension && subtag.len() == 2 {
+            canonical.push_str(&subtag.to_ascii_uppercase());
+        } else if ii > 0 && !extension && subtag.len() == 4 && subtag.is_ascii() {
+            canonical.push_str(&subtag[..1].to_ascii_uppercase());
+            canonical.push_str(&subtag[1..].to_ascii_lowercase());
+        } else {
+            canonical.push_str(&subtag.to_ascii_lowercase());
+        }
+        extension |= subtag.len() == 1;
+    }
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::accum::{Accumulator, Digit};
+    use crate::data::{PhoneNumberSpec, PostalCodeSpec, WideDataSpec};
+    use crate::data::{CountryCodeSpec, CurrencyAmountSpec, CurrencyCodeSpec, DataSpec, LanguageTagSpec, SequenceLength, Sequencer};
+    use crate::error::Error;
+
+    #[test]
+    fn currency_amount() {
+        let spec = CurrencyAmountSpec::new(-100_000, 100_000);
+        assert_eq!(spec.permutations(), 200_001);
+        assert_eq!(spec.encode(&-100_000), Ok(0));
+        assert_eq!(spec.encode(&100_000), Ok(200_000));
+        assert_eq!(spec.decode(200_000), Ok(100_000));
+        assert!(spec.decode(200_001).is_err());
+        assert!(matches!(spec.encode(&100_001), Err(Error::ValueOutOfRange { .. })));
+        for amount in [19.99, -0.01, 0.0, 0.1, 1000.0, -1000.0, 123.45, 0.07] {
+            let encoded = spec.encode_major(amount).unwrap();
+            assert_eq!(spec.decode_major(encoded), Ok(amount));
+        }
+        assert_eq!(spec.encode_major(19.99), spec.encode(&1999));
+        assert_eq!(spec.encode_major(-0.01), spec.encode(&-1));
+        assert_eq!(spec.encode_major(0.005), Err(Error::ValueNotOnStep {
+            value: String::from("0.005"),
+            min: String::from("0"),
+            step: String::from("0.01"),
+        }));
+        assert!(spec.encode_major(19.999).is_err());
+        assert!(matches!(spec.encode_major(1000.01), Err(Error::ValueOutOfRange { .. })));
+        assert_eq!(spec.encode_major(f64::NAN), Err(Error::NonFiniteValue));
+        // Currencies with no or 3 decimal places
+        let yen = CurrencyAmountSpec::new_with_places(0, 1_000_000, 0);
+        assert_eq!(yen.encode_major(1500.0), Ok(1500));
+        assert!(yen.encode_major(1500.5).is_err());
+        let dinar = CurrencyAmountSpec::new_with_places(0, 1_000_000, 3);
+        assert_eq!(dinar.decode_major(dinar.encode_major(1.234).unwrap()), Ok(1.234));
+        assert!(dinar.encode_major(1.2345).is_err());
+        assert!(matches!(CurrencyAmountSpec::try_new(10, 10), Err(Error::InvalidRange { .. })));
+        assert_eq!(CurrencyAmountSpec::try_new_with_places(0, 10, 19).err(), Some(Error::InvalidDecimals(19)));
+        assert_eq!(CurrencyAmountSpec::try_new(i64::MIN, i64::MAX).err(), Some(Error::TooManyPermutations));
+        #[cfg(not(feature = "digit32"))]
+        assert_eq!(CurrencyAmountSpec::new(i64::MIN + 1, i64::MAX).permutations(), Digit::MAX);
+        #[cfg(feature = "digit32")]
+        assert_eq!(CurrencyAmountSpec::new(0, Digit::MAX as i64 - 1).permutations(), Digit::MAX);
+    }
+
+    #[test]
+    fn seq_currency_amount() {
+        // Purchases and refunds
+        let mut a = Accumulator::new();
+        let amounts: &[i64] = &[1999, -1999, 5, 0, 250_000];
+        let spec = CurrencyAmountSpec::new(-1_000_000, 1_000_000);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(16));
+        sequencer.compress(amounts, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), amounts);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn currency_code() {
+        let spec = CurrencyCodeSpec::new();
+        assert_eq!(spec.permutations(), 179);
+        // Pinned indices, changing any of these breaks existing payloads
+        for (code, index) in [("AED", 0), ("EUR", 47), ("GBP", 50), ("JPY", 70), ("USD", 146), ("ZWG", 178)] {
+            assert_eq!(spec.encode(&String::from(code)), Ok(index));
+            assert_eq!(spec.decode(index), Ok(String::from(code)));
+        }
+        assert_eq!(spec.encode(&String::from("XYZ")), Err(Error::UnknownEnumVariant(String::from("XYZ"))));
+        assert!(spec.encode(&String::from("usd")).is_err());
+        assert!(spec.decode(179).is_err());
+        for index in 0..spec.permutations() {
+            let code = spec.decode(index).unwrap();
+            assert_eq!(spec.encode(&code), Ok(index));
+        }
+        let subset = CurrencyCodeSpec::from_subset(&["USD", "EUR", "GBP"]);
+        assert_eq!(subset.permutations(), 3);
+        assert_eq!(subset.encode(&String::from("USD")), Ok(0));
+        assert_eq!(subset.decode(2), Ok(String::from("GBP")));
+        assert!(subset.encode(&String::from("JPY")).is_err());
+        assert_eq!(
+            CurrencyCodeSpec::try_from_subset(&["USD", "XYZ"]).err(),
+            Some(Error::UnknownEnumVariant(String::from("XYZ")))
+        );
+        assert!(matches!(CurrencyCodeSpec::try_from_subset(&["USD", "USD"]), Err(Error::DuplicateOption { .. })));
+    }
+
+    #[test]
+    fn seq_currency_code() {
+        let mut a = Accumulator::new();
+        let codes: Vec<String> = ["USD", "EUR", "EUR", "XXX"].iter().map(|c| String::from(*c)).collect();
+        let spec = CurrencyCodeSpec::new();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(8));
+        sequencer.compress(&codes, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), codes);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn country_code() {
+        let spec = CountryCodeSpec::new();
+        assert_eq!(spec.permutations(), 249);
+        // Pinned indices, changing any of these breaks existing payloads
+        for (code, index) in [("AD", 0), ("DE", 56), ("FR", 74), ("GB", 76), ("US", 232), ("ZW", 248)] {
+            assert_eq!(spec.encode(&String::from(code)), Ok(index));
+            assert_eq!(spec.decode(index), Ok(String::from(code)));
+        }
+        assert_eq!(spec.encode(&String::from("us")), Err(Error::UnknownEnumVariant(String::from("us"))));
+        assert!(spec.encode(&String::from("XX")).is_err());
+        assert!(spec.decode(249).is_err());
+        let spec = CountryCodeSpec::new().with_normalization();
+        assert_eq!(spec.encode(&String::from("us")), Ok(232));
+        assert_eq!(spec.encode(&String::from("Gb")), Ok(76));
+        assert_eq!(spec.decode(232), Ok(String::from("US")));
+        assert!(spec.encode(&String::from("xx")).is_err());
+        assert_eq!(
+            CountryCodeSpec::try_from_subset(&["US", "us"]).err(),
+            Some(Error::UnknownEnumVariant(String::from("us")))
+        );
+        assert!(matches!(CountryCodeSpec::try_from_subset(&["US", "US"]), Err(Error::DuplicateOption { .. })));
+    }
+
+    #[test]
+    fn seq_country_code() {
+        let mut a = Accumulator::new();
+        let countries: Vec<String> = ["US", "CA", "MX", "CA"].iter().map(|c| String::from(*c)).collect();
+        let spec = CountryCodeSpec::from_subset(&["US", "CA", "MX"]).with_normalization();
+        assert_eq!(spec.permutations(), 3);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(4));
+        sequencer.compress(&countries, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), countries);
+        assert!(a.is_empty());
+        let lowercase: Vec<String> = ["us", "ca", "mx", "ca"].iter().map(|c| String::from(*c)).collect();
+        sequencer.compress(&lowercase, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), countries);
+    }
+
+    #[test]
+    fn language_tag() {
+        let spec = LanguageTagSpec::new();
+        assert_eq!(spec.permutations(), 183);
+        // Pinned indices, changing any of these breaks existing payloads
+        for (code, index) in [("aa", 0), ("de", 31), ("en", 36), ("fr", 46), ("pt", 129), ("zu", 182)] {
+            assert_eq!(spec.encode(&String::from(code)), Ok(index));
+            assert_eq!(spec.decode(index), Ok(String::from(code)));
+        }
+        assert_eq!(spec.encode(&String::from("EN")), Ok(36));
+        assert_eq!(spec.decode(spec.encode(&String::from("En")).unwrap()), Ok(String::from("en")));
+        assert_eq!(spec.encode(&String::from("xx")), Err(Error::UnknownEnumVariant(String::from("xx"))));
+        assert!(spec.encode(&String::from("en-US")).is_err());
+        assert!(spec.decode(183).is_err());
+        // Caller supplied list of full tags
+        let spec = LanguageTagSpec::from_tags(&["en-US", "pt-br", "ZH-HANT-tw", "en", "de-CH-x-PHONEBK"]);
+        assert_eq!(spec.permutations(), 5);
+        assert_eq!(spec.encode(&String::from("EN-us")), Ok(0));
+        assert_eq!(spec.decode(1), Ok(String::from("pt-BR")));
+        assert_eq!(spec.decode(2), Ok(String::from("zh-Hant-TW")));
+        assert_eq!(spec.decode(4), Ok(String::from("de-CH-x-phonebk")));
+        assert_eq!(spec.encode(&String::from("zh-hant-tw")), Ok(2));
+        assert!(spec.encode(&String::from("fr")).is_err());
+        assert_eq!(
+            LanguageTagSpec::try_from_tags(&["en-US", "pt-BR", "EN-us"]).err(),
+            Some(Error::DuplicateOption { value: String::from("en-US"), index: 2, first: 0 })
+        );
+    }
+
+    #[test]
+    fn phone_number() {
+        let spec = PhoneNumberSpec::new();
+        for number in ["+14155550123", "+442079460000", "+390612345678", "+3906", "+1", "+0", "+000000000000000", "+999999999999999"] {
+            let mut a = Accumulator::new();
+            spec.encode_into(&String::from(number), &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(String::from(number)));
+            assert!(a.is_empty());
+        }
+        // Numbers with the same value but a different number of digits
+        let mut a = Accumulator::new();
+        spec.encode_into(&String::from("+101"), &mut a).unwrap();
+        spec.encode_into(&String::from("+1001"), &mut a).unwrap();
+        assert_eq!(spec.decode_from(&mut a), Ok(String::from("+1001")));
+        assert_eq!(spec.decode_from(&mut a), Ok(String::from("+101")));
+        for number in ["14155550123", "+1 415 555 0123", "+1415555012a", "+", "+1234567890123456", "+١٢٣"] {
+            assert_eq!(spec.encode_into(&String::from(number), &mut a), Err(Error::InvalidPhoneNumber(String::from(number))));
+        }
+        // The largest number fits in 50 bits
+        let mut a = Accumulator::new();
+        spec.encode_into(&String::from("+999999999999999"), &mut a).unwrap();
+        assert!(a.to_bytes().len() <= 7);
+    }
+
+    #[test]
+    fn phone_number_country_codes() {
+        let spec = PhoneNumberSpec::with_country_codes(&[1, 44, 353]);
+        for number in ["+14155550123", "+442079460000", "+35312345678", "+35301", "+12", "+44999999999999"] {
+            let mut a = Accumulator::new();
+            spec.encode_into(&String::from(number), &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(String::from(number)));
+            assert!(a.is_empty());
+        }
+        let mut a = Accumulator::new();
+        assert_eq!(spec.encode_into(&String::from("+33123456789"), &mut a), Err(Error::UnknownEnumVariant(String::from("+33123456789"))));
+        assert_eq!(spec.encode_into(&String::from("+1"), &mut a), Err(Error::UnknownEnumVariant(String::from("+1"))));
+        assert!(spec.encode_into(&String::from("+449999999999999"), &mut a).is_ok());
+        assert!(spec.encode_into(&String::from("+4499999999999999"), &mut a).is_err());
+        assert_eq!(
+            PhoneNumberSpec::try_with_country_codes(&[1, 44, 1]).err(),
+            Some(Error::DuplicateOption { value: String::from("1"), index: 2, first: 0 })
+        );
+        assert!(PhoneNumberSpec::try_with_country_codes(&[0]).is_err());
+        assert!(PhoneNumberSpec::try_with_country_codes(&[1000]).is_err());
+        let numbers: Vec<String> = ["+14155550123", "+35312345678"].iter().map(|n| String::from(*n)).collect();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(4));
+        let mut a = Accumulator::new();
+        sequencer.compress(&numbers, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), numbers);
+    }
+
+    #[test]
+    fn postal_code() {
+        let spec = PostalCodeSpec::new(&[
+            ("US", "99999"),
+            ("US", "99999-9999"),
+            ("CA", "A9A 9A9"),
+            ("UK", "A9 9AA"),
+            ("UK", "A99 9AA"),
+            ("UK", "AA9 9AA"),
+            ("UK", "AA99 9AA"),
+            ("UK", "A9A 9AA"),
+            ("UK", "AA9A 9AA"),
+        ]);
+        let (letter, digit): (Digit, Digit) = (26, 10);
+        let inward = digit * letter * letter;
+        let permutations = digit.pow(5) + digit.pow(9) + letter.pow(3) * digit.pow(3)
+            + (letter * digit + letter * digit * digit + letter * letter * digit + letter * letter * digit * digit
+            + letter * digit * letter + letter * letter * digit * letter) * inward;
+        assert_eq!(spec.permutations(), permutations);
+        for (code, name) in [
+            ("90210", "US"),
+            ("00501", "US"),
+            ("20500-0003", "US"),
+            ("K1A 0B1", "CA"),
+            ("M1 1AE", "UK"),
+            ("B33 8TH", "UK"),
+            ("CR2 6XH", "UK"),
+            ("DN55 1PT", "UK"),
+            ("W1A 1HQ", "UK"),
+            ("EC1A 1BB", "UK"),
+        ] {
+            let encoded = spec.encode(&String::from(code)).unwrap();
+            assert_eq!(spec.decode(encoded), Ok(String::from(code)));
+            assert_eq!(spec.name_of(code), Ok(name));
+        }
+        assert_eq!(spec.encode(&String::from("00000")), Ok(0));
+        assert_eq!(spec.encode(&String::from("00000-0000")), Ok(100_000));
+        assert_eq!(spec.decode(permutations - 1), Ok(String::from("ZZ9Z 9ZZ")));
+        for garbage in ["9021", "902101", "k1a 0b1", "K1A0B1", "hello", "", "20500 0003"] {
+            assert_eq!(spec.encode(&String::from(garbage)), Err(Error::UnknownEnumVariant(String::from(garbage))));
+        }
+        assert!(spec.name_of("K1A-0B1").is_err());
+        assert!(spec.decode(permutations).is_err());
+        // The first matching pattern is used
+        let spec = PostalCodeSpec::new(&[("digits", "9999"), ("any", "****")]);
+        assert_eq!(spec.encode(&String::from("1234")), Ok(1234));
+        assert_eq!(spec.name_of("1234"), Ok("digits"));
+        assert_eq!(spec.name_of("12a4"), Ok("any"));
+        assert!(matches!(PostalCodeSpec::try_new(&[("US", "99999"), ("XX", "B9")]), Err(Error::InvalidPattern { .. })));
+    }
+}
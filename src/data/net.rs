@@ -0,0 +1,741 @@
+//! Identifier and network address data definitions
+
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::result::Result;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::accum::{Accumulator, Digit};
+use crate::data::{decode_wide_index, encode_wide_index, DataSpec, Enum, WideDataSpec};
+use crate::error::Error;
+
+/// Span of the index of a version 4 UUID, which has 122 random bits
+const UUID_V4_SPAN: u128 = (1 << 122) - 1;
+
+/// 128 bit UUID as 16 bytes in the usual (big endian) order, values are 
+/// compressed as multiple Digits
+///
+/// With `v4` only random (version 4, variant 1) UUIDs are accepted, and the 6
+/// bits that are the same in every one of them are not compressed.
+pub struct UuidSpec {
+    v4: bool,
+}
+
+impl UuidSpec {
+
+    /// Create a new UuidSpec for any UUID
+    pub fn new() -> Self {
+        Self { v4: false }
+    }
+
+    /// Create a new UuidSpec for version 4 UUIDs only
+    pub fn v4() -> Self {
+        Self { v4: true }
+    }
+}
+
+impl Default for UuidSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WideDataSpec<[u8; 16]> for UuidSpec {
+
+    fn encode_into(&self, input: &[u8; 16], accum: &mut Accumulator) -> Result<(), Error> {
+        let value = u128::from_be_bytes(*input);
+        if !self.v4 {
+            return encode_wide_index(value, u128::MAX, accum);
+        }
+        let version = input[6] >> 4;
+        let variant = input[8] >> 6;
+        if version != 4 || variant != 0b10 {
+            return Err(Error::InvalidUuidVersion { version, variant });
+        }
+        // Remove the version bits (76 to 79) and the variant bits (62 and 63)
+        let index = (value >> 80) << 74 | ((value >> 64) & 0xfff) << 62 | value & ((1 << 62) - 1);
+        encode_wide_index(index, UUID_V4_SPAN, accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<[u8; 16], Error> {
+        if !self.v4 {
+            return Ok(decode_wide_index(u128::MAX, accum)?.to_be_bytes());
+        }
+        let index = decode_wide_index(UUID_V4_SPAN, accum)?;
+        let value = (index >> 74) << 80 | 0x4 << 76 | ((index >> 62) & 0xfff) << 64 
+            | 0b10 << 62 | index & ((1 << 62) - 1);
+        Ok(value.to_be_bytes())
+    }
+}
+
+/// IPv4 address, either any address or only the addresses of a CIDR block, 
+/// values are compressed as multiple Digits when needed
+///
+/// Only the host part of addresses in a CIDR block is compressed (e.g. 8 bits
+/// for a /24 block), including the network and broadcast addresses.
+pub struct Ipv4Spec {
+    network: u32,
+    prefix_len: u8,
+}
+
+impl Ipv4Spec {
+
+    /// Create a new Ipv4Spec for any address (2^32 permutations)
+    pub fn new_full() -> Self {
+        Self { network: 0, prefix_len: 0 }
+    }
+
+    pub fn new_cidr(network: Ipv4Addr, prefix_len: u8) -> Self {
+        Self::try_new_cidr(network, prefix_len).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Ipv4Spec for the addresses of a CIDR block (e.g. 
+    /// 192.168.1.0/24), or an error if the prefix is longer than 32 bits or 
+    /// the network address has host bits set
+    pub fn try_new_cidr(network: Ipv4Addr, prefix_len: u8) -> Result<Self, Error> {
+        if prefix_len > 32 {
+            return Err(Error::RangeExceedsLimit { value: prefix_len.to_string(), limit: 32.to_string() });
+        }
+        let spec = Self { network: network.to_bits(), prefix_len };
+        if spec.network & spec.host_mask() != 0 {
+            return Err(Error::InvalidPrefix { network: network.to_string(), prefix_len });
+        }
+        Ok(spec)
+    }
+
+    /// Mask of the host bits of an address
+    fn host_mask(&self) -> u32 {
+        u32::MAX.checked_shr(self.prefix_len as u32).unwrap_or(0)
+    }
+}
+
+impl WideDataSpec<Ipv4Addr> for Ipv4Spec {
+
+    fn encode_into(&self, input: &Ipv4Addr, accum: &mut Accumulator) -> Result<(), Error> {
+        let address = input.to_bits();
+        if address & !self.host_mask() != self.network {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: Ipv4Addr::from_bits(self.network).to_string(),
+                max: Ipv4Addr::from_bits(self.network | self.host_mask()).to_string(),
+            });
+        }
+        encode_wide_index((address & self.host_mask()) as u128, self.host_mask() as u128, accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Ipv4Addr, Error> {
+        let host = decode_wide_index(self.host_mask() as u128, accum)? as u32;
+        Ok(Ipv4Addr::from_bits(self.network | host))
+    }
+}
+
+/// IPv6 address with a network prefix, values are compressed as multiple 
+/// Digits when needed
+///
+/// Only the host part of addresses is compressed (e.g. the 64 bit interface
+/// identifier for a /64 prefix).
+pub struct Ipv6Spec {
+    network: u128,
+    prefix_len: u8,
+}
+
+impl Ipv6Spec {
+
+    /// Create a new Ipv6Spec for any address (2^128 permutations)
+    pub fn new_full() -> Self {
+        Self { network: 0, prefix_len: 0 }
+    }
+
+    pub fn new_prefix(network: Ipv6Addr, prefix_len: u8) -> Self {
+        Self::try_new_prefix(network, prefix_len).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Ipv6Spec for the addresses with a prefix (e.g. 
+    /// 2001:db8::/64), or an error if the prefix is longer than 128 bits or 
+    /// the network address has host bits set
+    pub fn try_new_prefix(network: Ipv6Addr, prefix_len: u8) -> Result<Self, Error> {
+        if prefix_len > 128 {
+            return Err(Error::RangeExceedsLimit { value: prefix_len.to_string(), limit: 128.to_string() });
+        }
+        let spec = Self { network: network.to_bits(), prefix_len };
+        if spec.network & spec.host_mask() != 0 {
+            return Err(Error::InvalidPrefix { network: network.to_string(), prefix_len });
+        }
+        Ok(spec)
+    }
+
+    /// Mask of the host bits of an address
+    fn host_mask(&self) -> u128 {
+        u128::MAX.checked_shr(self.prefix_len as u32).unwrap_or(0)
+    }
+}
+
+impl WideDataSpec<Ipv6Addr> for Ipv6Spec {
+
+    fn encode_into(&self, input: &Ipv6Addr, accum: &mut Accumulator) -> Result<(), Error> {
+        let address = input.to_bits();
+        if address & !self.host_mask() != self.network {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: Ipv6Addr::from_bits(self.network).to_string(),
+                max: Ipv6Addr::from_bits(self.network | self.host_mask()).to_string(),
+            });
+        }
+        encode_wide_index(address & self.host_mask(), self.host_mask(), accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Ipv6Addr, Error> {
+        let host = decode_wide_index(self.host_mask(), accum)?;
+        Ok(Ipv6Addr::from_bits(self.network | host))
+    }
+}
+
+/// MAC (EUI-48) address as 6 bytes, either any address or only addresses 
+/// with a fixed OUI (the first 3 bytes), values are compressed as multiple 
+/// Digits when needed
+pub struct MacAddressSpec {
+    oui: Option<[u8; 3]>,
+}
+
+impl MacAddressSpec {
+
+    /// Create a new MacAddressSpec for any address (2^48 permutations)
+    pub fn new_full() -> Self {
+        Self { oui: None }
+    }
+
+    /// Create a new MacAddressSpec for the addresses of one OUI (2^24 
+    /// permutations)
+    pub fn new_with_oui(oui: [u8; 3]) -> Self {
+        Self { oui: Some(oui) }
+    }
+
+    /// Parse an address in the `aa:bb:cc:dd:ee:ff` form (`-` may also be used
+    /// as the separator)
+    pub fn parse(input: &str) -> Result<[u8; 6], Error> {
+        let invalid = || Error::InvalidMacAddress(input.to_string());
+        let mut address = [0; 6];
+        let mut parts = input.split([':', '-']);
+        for byte in address.iter_mut() {
+            let part = parts.next().ok_or_else(invalid)?;
+            if part.len() != 2 || !part.bytes().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            *byte = u8::from_str_radix(part, 16).map_err(|_| invalid())?;
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(address)
+    }
+
+    /// Format an address in the `aa:bb:cc:dd:ee:ff` form
+    pub fn format(address: &[u8; 6]) -> String {
+        format!(
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            address[0], address[1], address[2], address[3], address[4], address[5]
+        )
+    }
+
+    /// Span of the compressed part of an address
+    fn span(&self) -> u128 {
+        match self.oui {
+            None => (1 << 48) - 1,
+            Some(_) => (1 << 24) - 1,
+        }
+    }
+}
+
+impl WideDataSpec<[u8; 6]> for MacAddressSpec {
+
+    fn encode_into(&self, input: &[u8; 6], accum: &mut Accumulator) -> Result<(), Error> {
+        if let Some(oui) = self.oui {
+            if input[..3] != oui {
+                return Err(Error::ValueOutOfRange {
+                    value: Self::format(input),
+                    min: Self::format(&[oui[0], oui[1], oui[2], 0x00, 0x00, 0x00]),
+                    max: Self::format(&[oui[0], oui[1], oui[2], 0xff, 0xff, 0xff]),
+                });
+            }
+        }
+        let value = input.iter().fold(0u128, |value, byte| value << 8 | *byte as u128);
+        encode_wide_index(value & self.span(), self.span(), accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<[u8; 6], Error> {
+        let value = decode_wide_index(self.span(), accum)?;
+        let mut address: [u8; 6] = value.to_be_bytes()[10..].try_into().unwrap();
+        if let Some(oui) = self.oui {
+            address[..3].copy_from_slice(&oui);
+        }
+        Ok(address)
+    }
+}
+
+/// Semantic version as a (major, minor, patch) tuple with a maximum for each
+/// component, optionally with a pre-release tag from an Enum
+///
+/// Versions are compressed as a single mixed radix index. Versions with a tag
+/// are encoded after every version without one, so the `(u16, u16, u16)` and 
+/// `(u16, u16, u16, Option<String>)` forms encode releases the same.
+pub struct SemverSpec {
+    max: (u16, u16, u16),
+    tags: Option<Enum>,
+}
+
+impl SemverSpec {
+
+    pub fn new(max_major: u16, max_minor: u16, max_patch: u16) -> Self {
+        Self::try_new(max_major, max_minor, max_patch).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new SemverSpec for versions from 0.0.0 up to the maximum of 
+    /// each component, or an error if there are too many permutations
+    pub fn try_new(max_major: u16, max_minor: u16, max_patch: u16) -> Result<Self, Error> {
+        let spec = Self { max: (max_major, max_minor, max_patch), tags: None };
+        spec.checked_permutations().ok_or(Error::TooManyPermutations)?;
+        Ok(spec)
+    }
+
+    pub fn with_pre_release(self, tags: Enum) -> Self {
+        self.try_with_pre_release(tags).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Allow versions with a pre-release tag (e.g. "alpha", "beta" or "rc"), 
+    /// or an error if there are too many permutations
+    pub fn try_with_pre_release(mut self, tags: Enum) -> Result<Self, Error> {
+        self.tags = Some(tags);
+        self.checked_permutations().ok_or(Error::TooManyPermutations)?;
+        Ok(self)
+    }
+
+    /// Number of versions without a tag
+    fn releases(&self) -> Digit {
+        (self.max.0 as Digit + 1) * (self.max.1 as Digit + 1) * (self.max.2 as Digit + 1)
+    }
+
+    fn checked_permutations(&self) -> Option<Digit> {
+        let releases = (self.max.0 as Digit + 1)
+            .checked_mul(self.max.1 as Digit + 1)?
+            .checked_mul(self.max.2 as Digit + 1)?;
+        let tags = self.tags.as_ref().map_or(0, |tags| tags.permutations());
+        releases.checked_mul(tags.checked_add(1)?)
+    }
+
+    fn encode_release(&self, input: &(u16, u16, u16)) -> Result<Digit, Error> {
+        let components = [(input.0, self.max.0), (input.1, self.max.1), (input.2, self.max.2)];
+        let mut index: Digit = 0;
+        for (value, max) in components {
+            if value > max {
+                return Err(Error::ValueOutOfRange {
+                    value: value.to_string(),
+                    min: 0.to_string(),
+                    max: max.to_string(),
+                });
+            }
+            index = index * (max as Digit + 1) + value as Digit;
+        }
+        Ok(index)
+    }
+
+    fn decode_release(&self, input: Digit) -> (u16, u16, u16) {
+        let patch = input % (self.max.2 as Digit + 1);
+        let input = input / (self.max.2 as Digit + 1);
+        let minor = input % (self.max.1 as Digit + 1);
+        let major = input / (self.max.1 as Digit + 1);
+        (major as u16, minor as u16, patch as u16)
+    }
+}
+
+impl DataSpec<(u16, u16, u16)> for SemverSpec {
+
+    fn permutations(&self) -> Digit {
+        self.checked_permutations().unwrap()
+    }
+
+    fn encode(&self, input: &(u16, u16, u16)) -> Result<Digit, Error> {
+        self.encode_release(input)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(u16, u16, u16), Error> {
+        if input >= self.releases() {
+            if input < DataSpec::<(u16, u16, u16)>::permutations(self) {
+                return Err(Error::DecodeOutOfRange {
+                    value: input.to_string(),
+                    max: (self.releases() - 1).to_string(),
+                });
+            }
+            return Err(Error::DecodeOutOfBounds {
+                value: input,
+                permutations: DataSpec::<(u16, u16, u16)>::permutations(self),
+            });
+        }
+        Ok(self.decode_release(input))
+    }
+}
+
+impl DataSpec<(u16, u16, u16, Option<String>)> for SemverSpec {
+
+    fn permutations(&self) -> Digit {
+        self.checked_permutations().unwrap()
+    }
+
+    fn encode(&self, input: &(u16, u16, u16, Option<String>)) -> Result<Digit, Error> {
+        let release = self.encode_release(&(input.0, input.1, input.2))?;
+        let tag = match (&input.3, &self.tags) {
+            (None, _) => 0,
+            (Some(tag), Some(tags)) => tags.encode(tag)? + 1,
+            (Some(tag), None) => return Err(Error::UnknownEnumVariant(tag.clone())),
+        };
+        Ok(tag * self.releases() + release)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(u16, u16, u16, Option<String>), Error> {
+        let permutations = DataSpec::<(u16, u16, u16)>::permutations(self);
+        if input >= permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations });
+        }
+        let (major, minor, patch) = self.decode_release(input % self.releases());
+        let tag = match (input / self.releases(), &self.tags) {
+            (0, _) | (_, None) => None,
+            (tag, Some(tags)) => Some(tags.decode(tag - 1)?),
+        };
+        Ok((major, minor, patch, tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use core::net::{Ipv4Addr, Ipv6Addr};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::accum::{Accumulator, Digit};
+    use crate::data::{DataSpec, SequenceLength, Sequencer, WideDataSpec};
+    use crate::data::{Bool, Enum, Ipv4Spec, Ipv6Spec, MacAddressSpec, SemverSpec, UInt128Range};
+    use crate::data::{UIntRange, UuidSpec};
+    use crate::error::Error;
+
+    /// Simple deterministic pseudo random number generator (xorshift64*)
+    fn random(state: &mut u64) -> u64 {
+        *state ^= *state >> 12;
+        *state ^= *state << 25;
+        *state ^= *state >> 27;
+        state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    /// Random version 4 UUID
+    fn random_v4(state: &mut u64) -> [u8; 16] {
+        let mut uuid = ((random(state) as u128) << 64 | random(state) as u128).to_be_bytes();
+        uuid[6] = uuid[6] & 0x0f | 0x40;
+        uuid[8] = uuid[8] & 0x3f | 0x80;
+        uuid
+    }
+
+    #[test]
+    fn uuid() {
+        let u = UuidSpec::new();
+        for uuid in [[0; 16], [0xff; 16], *b"0123456789abcdef"] {
+            let mut a = Accumulator::new();
+            u.encode_into(&uuid, &mut a).unwrap();
+            assert!(a.len() <= (128 / Digit::BITS) as usize);
+            assert_eq!(u.decode_from(&mut a), Ok(uuid));
+            assert!(a.is_empty());
+        }
+        let mut state: u64 = 0x5c3e1a9b7d0f2468;
+        let u = UuidSpec::v4();
+        for _ in 0..100 {
+            let uuid = random_v4(&mut state);
+            let mut a = Accumulator::new();
+            u.encode_into(&uuid, &mut a).unwrap();
+            assert_eq!(u.decode_from(&mut a), Ok(uuid));
+            assert!(a.is_empty());
+        }
+        // Largest version 4 UUID
+        let max = {
+            let mut uuid = [0xff; 16];
+            uuid[6] = 0x4f;
+            uuid[8] = 0xbf;
+            uuid
+        };
+        let mut a = Accumulator::new();
+        u.encode_into(&max, &mut a).unwrap();
+        assert_eq!(u.decode_from(&mut a), Ok(max));
+        assert!(a.is_empty());
+        let mut a = Accumulator::new();
+        assert_eq!(u.encode_into(&[0; 16], &mut a), Err(Error::InvalidUuidVersion { version: 0, variant: 0 }));
+        assert_eq!(u.encode_into(&[0xff; 16], &mut a), Err(Error::InvalidUuidVersion { version: 15, variant: 3 }));
+    }
+
+    #[test]
+    fn seq_uuid() {
+        let mut state: u64 = 0x0123456789abcdef;
+        let uuids: Vec<[u8; 16]> = (0..10).map(|_| random_v4(&mut state)).collect();
+        let spec0 = UuidSpec::new();
+        let spec1 = UuidSpec::v4();
+        let full = Sequencer::new(&spec0, SequenceLength::Variable(10));
+        let v4 = Sequencer::new(&spec1, SequenceLength::Variable(10));
+        let mut a = Accumulator::new();
+        let mut b = Accumulator::new();
+        full.compress(&uuids, &mut a).unwrap();
+        v4.compress(&uuids, &mut b).unwrap();
+        assert!(b.to_bytes().len() < a.to_bytes().len());
+        assert_eq!(full.decompress(&mut a).unwrap(), uuids);
+        assert_eq!(v4.decompress(&mut b).unwrap(), uuids);
+        assert!(a.is_empty());
+        assert!(b.is_empty());
+    }
+
+    /// Compress a single address into a new accumulator
+    fn compressed<S: WideDataSpec<T>, T>(spec: &S, value: &T) -> Accumulator {
+        let mut a = Accumulator::new();
+        spec.encode_into(value, &mut a).unwrap();
+        a
+    }
+
+    #[test]
+    fn ipv4() {
+        let spec = Ipv4Spec::new_cidr(Ipv4Addr::new(192, 168, 1, 0), 24);
+        for (address, host) in [
+            (Ipv4Addr::new(192, 168, 1, 0), 0),
+            (Ipv4Addr::new(192, 168, 1, 42), 42),
+            (Ipv4Addr::new(192, 168, 1, 255), 255),
+        ] {
+            let mut a = compressed(&spec, &address);
+            assert_eq!(a.to_bytes(), compressed(&UIntRange::new(0, 255), &host).to_bytes());
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+            assert!(a.is_empty());
+        }
+        let mut a = Accumulator::new();
+        assert_eq!(spec.encode_into(&Ipv4Addr::new(192, 168, 2, 1), &mut a), Err(Error::ValueOutOfRange {
+            value: String::from("192.168.2.1"),
+            min: String::from("192.168.1.0"),
+            max: String::from("192.168.1.255"),
+        }));
+        assert!(spec.encode_into(&Ipv4Addr::new(10, 168, 1, 1), &mut a).is_err());
+        let spec = Ipv4Spec::new_cidr(Ipv4Addr::new(10, 20, 0, 0), 16);
+        for address in [Ipv4Addr::new(10, 20, 0, 0), Ipv4Addr::new(10, 20, 130, 7), Ipv4Addr::new(10, 20, 255, 255)] {
+            let mut a = compressed(&spec, &address);
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+            assert!(a.is_empty());
+        }
+        assert!(spec.encode_into(&Ipv4Addr::new(10, 21, 0, 0), &mut a).is_err());
+        // Single address
+        let spec = Ipv4Spec::new_cidr(Ipv4Addr::new(8, 8, 8, 8), 32);
+        assert!(compressed(&spec, &Ipv4Addr::new(8, 8, 8, 8)).is_empty());
+        assert!(spec.encode_into(&Ipv4Addr::new(8, 8, 8, 9), &mut a).is_err());
+        let spec = Ipv4Spec::new_full();
+        for address in [Ipv4Addr::UNSPECIFIED, Ipv4Addr::BROADCAST, Ipv4Addr::new(172, 16, 0, 1)] {
+            let mut a = compressed(&spec, &address);
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+            assert!(a.is_empty());
+        }
+        assert_eq!(
+            Ipv4Spec::try_new_cidr(Ipv4Addr::new(192, 168, 1, 1), 24).err(),
+            Some(Error::InvalidPrefix { network: String::from("192.168.1.1"), prefix_len: 24 })
+        );
+        assert!(matches!(
+            Ipv4Spec::try_new_cidr(Ipv4Addr::new(192, 168, 1, 1), 33),
+            Err(Error::RangeExceedsLimit { .. })
+        ));
+    }
+
+    #[test]
+    fn seq_ipv4() {
+        // Full range addresses in a variable length sequence, which can not use
+        // one more permutation as a terminator
+        let mut a = Accumulator::new();
+        let addresses = &[Ipv4Addr::UNSPECIFIED, Ipv4Addr::BROADCAST, Ipv4Addr::new(127, 0, 0, 1)];
+        let online = &[true, false, true];
+        let full = Ipv4Spec::new_full();
+        let subnet = Ipv4Spec::new_cidr(Ipv4Addr::new(192, 168, 0, 0), 16);
+        let bool = Bool::new();
+        let subnet_addresses = &[Ipv4Addr::new(192, 168, 0, 0), Ipv4Addr::new(192, 168, 255, 255)];
+        let sequencer0 = Sequencer::new(&full, SequenceLength::Variable(4));
+        let sequencer1 = Sequencer::new(&bool, SequenceLength::Fixed(3));
+        let sequencer2 = Sequencer::new(&subnet, SequenceLength::Variable(4));
+        sequencer0.compress(addresses, &mut a).unwrap();
+        sequencer1.compress(online, &mut a).unwrap();
+        sequencer2.compress(subnet_addresses, &mut a).unwrap();
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), subnet_addresses);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), online);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), addresses);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn ipv6() {
+        let network = Ipv6Addr::new(0x2001, 0xdb8, 0x1234, 0x5678, 0, 0, 0, 0);
+        let spec = Ipv6Spec::new_prefix(network, 64);
+        let ones = Ipv6Addr::new(0x2001, 0xdb8, 0x1234, 0x5678, 0xffff, 0xffff, 0xffff, 0xffff);
+        for address in [network, ones, Ipv6Addr::new(0x2001, 0xdb8, 0x1234, 0x5678, 0, 0, 0, 1)] {
+            let mut a = compressed(&spec, &address);
+            assert!(a.len() <= (64 / Digit::BITS) as usize);
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+            assert!(a.is_empty());
+        }
+        assert_eq!(compressed(&spec, &ones).len(), (64 / Digit::BITS) as usize);
+        let mut a = Accumulator::new();
+        assert_eq!(
+            spec.encode_into(&Ipv6Addr::new(0x2001, 0xdb8, 0x1234, 0x5679, 0, 0, 0, 0), &mut a),
+            Err(Error::ValueOutOfRange {
+                value: String::from("2001:db8:1234:5679::"),
+                min: String::from("2001:db8:1234:5678::"),
+                max: String::from("2001:db8:1234:5678:ffff:ffff:ffff:ffff"),
+            })
+        );
+        // Host part larger than 64 bits
+        let network = Ipv6Addr::new(0x2001, 0xdb8, 0x1234, 0, 0, 0, 0, 0);
+        let spec = Ipv6Spec::new_prefix(network, 48);
+        let ones = Ipv6Addr::new(0x2001, 0xdb8, 0x1234, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff);
+        for address in [network, ones, Ipv6Addr::new(0x2001, 0xdb8, 0x1234, 0xabcd, 0, 0, 0xffff, 1)] {
+            let mut a = compressed(&spec, &address);
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+            assert!(a.is_empty());
+        }
+        assert_eq!(compressed(&spec, &ones).len(), 80_u32.div_ceil(Digit::BITS) as usize);
+        assert!(spec.encode_into(&Ipv6Addr::new(0x2001, 0xdb8, 0x1235, 0, 0, 0, 0, 0), &mut a).is_err());
+        // Point to point link with two addresses
+        let network = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2);
+        let spec = Ipv6Spec::new_prefix(network, 127);
+        for (address, host) in [(network, 0), (Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 3), 1)] {
+            let mut a = compressed(&spec, &address);
+            assert_eq!(a.to_bytes(), compressed(&Bool::new(), &(host == 1)).to_bytes());
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+        }
+        assert!(spec.encode_into(&Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 4), &mut a).is_err());
+        let spec = Ipv6Spec::new_full();
+        for address in [Ipv6Addr::UNSPECIFIED, Ipv6Addr::from_bits(u128::MAX), Ipv6Addr::LOCALHOST] {
+            let mut a = compressed(&spec, &address);
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+            assert!(a.is_empty());
+        }
+        assert!(matches!(
+            Ipv6Spec::try_new_prefix(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 64),
+            Err(Error::InvalidPrefix { prefix_len: 64, .. })
+        ));
+        assert!(matches!(Ipv6Spec::try_new_prefix(Ipv6Addr::UNSPECIFIED, 129), Err(Error::RangeExceedsLimit { .. })));
+    }
+
+    #[test]
+    fn seq_ipv6() {
+        let mut a = Accumulator::new();
+        let network = Ipv6Addr::new(0xfd00, 0, 0, 0x42, 0, 0, 0, 0);
+        let addresses = &[network, Ipv6Addr::new(0xfd00, 0, 0, 0x42, 0x1, 0x2, 0x3, 0x4)];
+        let states = [String::from("Up"), String::from("Down")];
+        let address = Ipv6Spec::new_prefix(network, 64);
+        let state = Enum::new(&["Up", "Down"]);
+        let sequencer0 = Sequencer::new(&address, SequenceLength::Variable(8));
+        let sequencer1 = Sequencer::new(&state, SequenceLength::Fixed(2));
+        sequencer0.compress(addresses, &mut a).unwrap();
+        sequencer1.compress(&states, &mut a).unwrap();
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), states);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), addresses);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn mac_address() {
+        let spec = MacAddressSpec::new_full();
+        let broadcast = [0xff; 6];
+        let local = [0x02, 0x00, 0x5e, 0x10, 0x00, 0x01];
+        for address in [[0; 6], broadcast, local] {
+            let mut a = compressed(&spec, &address);
+            assert_eq!(spec.decode_from(&mut a), Ok(address));
+            assert!(a.is_empty());
+        }
+        // 2^48 permutations
+        assert_eq!(
+            compressed(&spec, &broadcast).to_bytes(),
+            compressed(&UInt128Range::new(0, (1 << 48) - 1), &((1 << 48) - 1)).to_bytes()
+        );
+        let spec = MacAddressSpec::new_with_oui([0x00, 0x1b, 0x63]);
+        let address = [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6];
+        let mut a = compressed(&spec, &address);
+        assert_eq!(a.to_bytes(), compressed(&UIntRange::new(0, (1 << 24) - 1), &0x8445e6).to_bytes());
+        assert_eq!(spec.decode_from(&mut a), Ok(address));
+        let mut a = compressed(&spec, &[0x00, 0x1b, 0x63, 0xff, 0xff, 0xff]);
+        assert_eq!(spec.decode_from(&mut a), Ok([0x00, 0x1b, 0x63, 0xff, 0xff, 0xff]));
+        assert_eq!(spec.encode_into(&local, &mut a), Err(Error::ValueOutOfRange {
+            value: String::from("02:00:5e:10:00:01"),
+            min: String::from("00:1b:63:00:00:00"),
+            max: String::from("00:1b:63:ff:ff:ff"),
+        }));
+        assert!(spec.encode_into(&broadcast, &mut a).is_err());
+    }
+
+    #[test]
+    fn mac_address_text() {
+        let address = [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6];
+        assert_eq!(MacAddressSpec::format(&address), "00:1b:63:84:45:e6");
+        assert_eq!(MacAddressSpec::parse("00:1b:63:84:45:e6"), Ok(address));
+        assert_eq!(MacAddressSpec::parse("00-1B-63-84-45-E6"), Ok(address));
+        assert_eq!(MacAddressSpec::parse(&MacAddressSpec::format(&[0xff; 6])), Ok([0xff; 6]));
+        for invalid in ["", "00:1b:63:84:45", "00:1b:63:84:45:e6:00", "00:1b:63:84:45:g6", "0:1b:63:84:45:e6", "+0:1b:63:84:45:e6"] {
+            assert_eq!(MacAddressSpec::parse(invalid), Err(Error::InvalidMacAddress(String::from(invalid))));
+        }
+    }
+
+    #[test]
+    fn semver() {
+        let spec = SemverSpec::new(15, 63, 255);
+        assert_eq!(DataSpec::<(u16, u16, u16)>::permutations(&spec), 16 * 64 * 256);
+        assert_eq!(spec.encode(&(0, 0, 0)), Ok(0));
+        assert_eq!(spec.encode(&(0, 0, 1)), Ok(1));
+        assert_eq!(spec.encode(&(0, 1, 0)), Ok(256));
+        assert_eq!(spec.encode(&(1, 0, 0)), Ok(64 * 256));
+        assert_eq!(spec.encode(&(15, 63, 255)), Ok(16 * 64 * 256 - 1));
+        assert_eq!(spec.decode(16 * 64 * 256 - 1), Ok((15, 63, 255)));
+        assert_eq!(spec.decode(spec.encode(&(2, 7, 19)).unwrap()), Ok((2, 7, 19)));
+        assert!(DataSpec::<(u16, u16, u16)>::decode(&spec, 16 * 64 * 256).is_err());
+        assert_eq!(spec.encode(&(16, 0, 0)), Err(Error::ValueOutOfRange {
+            value: String::from("16"),
+            min: String::from("0"),
+            max: String::from("15"),
+        }));
+        assert!(spec.encode(&(0, 64, 0)).is_err());
+        assert!(spec.encode(&(0, 0, 256)).is_err());
+        assert_eq!(
+            spec.encode(&(1, 2, 3, Some(String::from("rc")))),
+            Err(Error::UnknownEnumVariant(String::from("rc")))
+        );
+        #[cfg(not(feature = "digit32"))]
+        assert_eq!(DataSpec::<(u16, u16, u16)>::permutations(&SemverSpec::new(u16::MAX, u16::MAX, u16::MAX)), 1 << 48);
+        #[cfg(feature = "digit32")]
+        assert!(matches!(SemverSpec::try_new(u16::MAX, u16::MAX, u16::MAX), Err(Error::TooManyPermutations)));
+    }
+
+    #[test]
+    fn semver_pre_release() {
+        let spec = SemverSpec::new(3, 9, 9).with_pre_release(Enum::new(&["alpha", "beta", "rc"]));
+        assert_eq!(DataSpec::<(u16, u16, u16, Option<String>)>::permutations(&spec), 4 * 10 * 10 * 4);
+        let versions = [
+            (0, 0, 0, None),
+            (3, 9, 9, None),
+            (1, 2, 3, Some(String::from("alpha"))),
+            (3, 9, 9, Some(String::from("rc"))),
+        ];
+        for version in versions {
+            assert_eq!(spec.decode(spec.encode(&version).unwrap()), Ok(version));
+        }
+        assert_eq!(spec.encode(&(3, 9, 9, Some(String::from("rc")))), Ok(4 * 10 * 10 * 4 - 1));
+        // Releases encode the same in both forms
+        assert_eq!(spec.encode(&(1, 2, 3)), spec.encode(&(1, 2, 3, None)));
+        assert_eq!(DataSpec::<(u16, u16, u16)>::decode(&spec, 123), Ok((1, 2, 3)));
+        assert!(matches!(DataSpec::<(u16, u16, u16)>::decode(&spec, 400), Err(Error::DecodeOutOfRange { .. })));
+        assert!(DataSpec::<(u16, u16, u16, Option<String>)>::decode(&spec, 4 * 10 * 10 * 4).is_err());
+        assert!(spec.encode(&(1, 2, 3, Some(String::from("gamma")))).is_err());
+    }
+
+    #[test]
+    fn seq_semver() {
+        let mut a = Accumulator::new();
+        let versions = &[(1, 0, 0), (1, 2, 0), (2, 0, 17)];
+        let spec = SemverSpec::new(15, 63, 255);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(4));
+        sequencer.compress(versions, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), versions);
+        assert!(a.is_empty());
+    }
+}
@@ -0,0 +1,1221 @@
+//! Numeric data definitions beyond simple ranges
+
+use core::fmt;
+use core::result::Result;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::accum::{Accumulator, Digit, DoubleDigit, SignedDigit, SignedDoubleDigit};
+use crate::data::{digit_to_u32, range_permutations, DataSpec, RoundingMode, WideDataSpec};
+use crate::error::Error;
+
+/// Number range on a logarithmic scale, for values where the relative
+/// precision matters more than the absolute precision
+///
+/// Values are placed in one of `steps + 1` buckets evenly spaced on a log
+/// scale from min to max, and decode to the geometric center of their bucket
+/// (min and max decode exactly). The relative error of a decoded value is at
+/// most `(max / min)^(1 / (2 * steps)) - 1`.
+///
+/// Note: The compression used by this data type is not lossless
+pub struct LogScaleRange {
+    min: f64,
+    max: f64,
+    steps: Digit,
+    zero: bool,
+}
+
+impl LogScaleRange {
+
+    pub fn new(min: f64, max: f64, steps: Digit) -> Self {
+        Self::try_new(min, max, steps).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new LogScaleRange with a total number of steps from min to
+    /// max, or an error if the range is not valid
+    pub fn try_new(min: f64, max: f64, steps: Digit) -> Result<Self, Error> {
+        if !min.is_finite() || !max.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        if min <= 0.0 {
+            return Err(Error::RangeExceedsLimit { value: min.to_string(), limit: 0.0.to_string() });
+        }
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        if steps == 0 {
+            return Err(Error::ZeroStep);
+        }
+        // One more permutation than steps, and one for the zero bucket
+        if steps > Digit::MAX - 2 {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { min, max, steps, zero: false })
+    }
+
+    pub fn new_per_decade(min: f64, max: f64, steps_per_decade: f64) -> Self {
+        Self::try_new_per_decade(min, max, steps_per_decade).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new LogScaleRange with at least a number of steps for each
+    /// factor of 10 from min to max, or an error if the range is not valid
+    pub fn try_new_per_decade(min: f64, max: f64, steps_per_decade: f64) -> Result<Self, Error> {
+        if !steps_per_decade.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        let steps = libm::ceil(libm::log10(max / min) * steps_per_decade);
+        if steps.is_nan() || steps < 1.0 {
+            // Let try_new report what is wrong with the range
+            return Self::try_new(min, max, 0);
+        }
+        if steps >= Digit::MAX as f64 {
+            return Err(Error::TooManyPermutations);
+        }
+        Self::try_new(min, max, steps as Digit)
+    }
+
+    /// Add a bucket for the value zero, which is otherwise outside of the
+    /// range
+    pub fn with_zero(mut self) -> Self {
+        self.zero = true;
+        self
+    }
+}
+
+impl DataSpec<f64> for LogScaleRange {
+
+    fn permutations(&self) -> Digit {
+        self.steps + 1 + Digit::from(self.zero)
+    }
+
+    fn encode(&self, input: &f64) -> Result<Digit, Error> {
+        if input.is_nan() {
+            return Err(Error::NonFiniteValue);
+        }
+        if self.zero && *input == 0.0 {
+            return Ok(0);
+        }
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        let position = libm::log(input / self.min) / libm::log(self.max / self.min);
+        let index = (libm::round(position * self.steps as f64) as Digit).min(self.steps);
+        Ok(index + Digit::from(self.zero))
+    }
+
+    fn decode(&self, input: Digit) -> Result<f64, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        if self.zero && input == 0 {
+            return Ok(0.0);
+        }
+        let index = input - Digit::from(self.zero);
+        if index == self.steps {
+            return Ok(self.max);
+        }
+        let position = index as f64 / self.steps as f64;
+        Ok(self.min * libm::pow(self.max / self.min, position))
+    }
+}
+
+/// Unsigned integer biased towards small values, using bucketing similar to
+/// exponential-Golomb coding
+///
+/// Values are split into a bucket and an offset within the bucket. Bucket k 
+/// holds the 2^k values from 2^k - 1 to 2^(k+1) - 2 ([0], [1, 2], [3, 6], 
+/// [7, 14], ...), so small values take only a few bits for the offset. The 
+/// bucket and offset are compressed separately, so this is only a 
+/// WideDataSpec.
+pub struct BiasedUInt {
+    max: Digit,
+}
+
+impl BiasedUInt {
+
+    /// Create a new BiasedUInt for values from 0 to max
+    pub fn new(max: Digit) -> Self {
+        Self { max }
+    }
+
+    /// Bucket of a value, and the first value in the bucket
+    fn bucket(value: Digit) -> (u32, Digit) {
+        let shifted = value as DoubleDigit + 1;
+        let bucket = DoubleDigit::BITS - shifted.leading_zeros() - 1;
+        let start = ((1 as DoubleDigit) << bucket) - 1;
+        (bucket, start as Digit)
+    }
+
+    /// Number of values in a bucket, the last bucket ends at max
+    fn bucket_size(&self, bucket: u32) -> Digit {
+        let (last, start) = Self::bucket(self.max);
+        if bucket == last {
+            self.max - start + 1
+        } else {
+            1 << bucket
+        }
+    }
+
+    fn buckets(&self) -> Digit {
+        Self::bucket(self.max).0 as Digit + 1
+    }
+}
+
+impl WideDataSpec<Digit> for BiasedUInt {
+
+    fn encode_into(&self, input: &Digit, accum: &mut Accumulator) -> Result<(), Error> {
+        if *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: 0.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        // The bucket is compressed last, so it is the first to be decoded
+        let (bucket, start) = Self::bucket(*input);
+        accum.checked_mul(self.bucket_size(bucket))?;
+        accum.add(*input - start);
+        accum.checked_mul(self.buckets())?;
+        accum.add(bucket as Digit);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Digit, Error> {
+        let bucket = accum.checked_div(self.buckets())?;
+        let bucket = digit_to_u32(bucket).ok_or(Error::DecodeOutOfBounds { value: bucket, permutations: self.buckets() })?;
+        let offset = accum.checked_div(self.bucket_size(bucket))?;
+        let start = ((1 as DoubleDigit) << bucket) - 1;
+        Ok(start as Digit + offset)
+    }
+}
+
+/// Unsigned integer without a fixed maximum, compressed as a variable number 
+/// of base B digits
+///
+/// Values below B are compressed as a single draw of B + 1 permutations. 
+/// Larger values use the extra permutation as an escape, followed by the 
+/// base B digits of the rest of the value, each with one more permutation to
+/// mark the end of the digits (like a variable length Sequencer).
+pub struct VarUInt {
+    base: Digit,
+}
+
+impl VarUInt {
+
+    pub fn new(base: Digit) -> Self {
+        Self::try_new(base).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new VarUInt with digits of a base, or an error if the base 
+    /// is less than 2 or does not leave room for the escape
+    pub fn try_new(base: Digit) -> Result<Self, Error> {
+        if base < 2 || base == Digit::MAX {
+            return Err(Error::InvalidBase(base));
+        }
+        Ok(Self { base })
+    }
+}
+
+impl WideDataSpec<Digit> for VarUInt {
+
+    fn encode_into(&self, input: &Digit, accum: &mut Accumulator) -> Result<(), Error> {
+        let permutations = self.base + 1;
+        if *input < self.base {
+            accum.checked_mul(permutations)?;
+            accum.add(*input);
+            return Ok(());
+        }
+        let mut rest = *input - self.base;
+        let mut digits: Vec<Digit> = Vec::new();
+        while rest > 0 {
+            digits.push(rest % self.base);
+            rest /= self.base;
+        }
+        // Zero to indicate the end of the digits, then the most significant
+        // digit first so the least significant is decoded first
+        accum.checked_mul(permutations)?;
+        for digit in digits.iter().rev() {
+            accum.checked_mul(permutations)?;
+            accum.add(digit + 1);
+        }
+        accum.checked_mul(permutations)?;
+        accum.add(self.base);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Digit, Error> {
+        let permutations = self.base + 1;
+        let first = accum.checked_div(permutations)?;
+        if first < self.base {
+            return Ok(first);
+        }
+        let max = (Digit::MAX - self.base) as DoubleDigit;
+        let mut rest: DoubleDigit = 0;
+        let mut scale: DoubleDigit = 1;
+        for _ in 0..=Digit::BITS {
+            let coded_digit = accum.checked_div(permutations)?;
+            if coded_digit == 0 {
+                return Ok(rest as Digit + self.base);
+            }
+            let digit = (coded_digit - 1) as DoubleDigit;
+            if digit > 0 && (scale > max || rest + digit * scale > max) {
+                return Err(Error::DecodeOutOfRange {
+                    value: String::from("more than ") + &Digit::MAX.to_string(),
+                    max: Digit::MAX.to_string(),
+                });
+            }
+            rest += digit * scale;
+            scale = scale.saturating_mul(self.base as DoubleDigit);
+        }
+        Err(Error::UnterminatedSequence { max: Digit::BITS as usize })
+    }
+}
+
+/// Number range with a fixed number of base 10 decimal places, for amounts 
+/// like money where values such as 0.01 need to be exact
+///
+/// Values are encoded as a whole number of the smallest decimal unit (e.g. 
+/// cents with 2 decimal places). As a `DataSpec<f64>` values are rounded to 
+/// the nearest unit, as a `DataSpec<SignedDigit>` values are already in the 
+/// smallest units.
+pub struct DecimalRange {
+    min: SignedDigit,
+    max: SignedDigit,
+    places: u32,
+}
+
+impl DecimalRange {
+
+    pub fn new(min: f64, max: f64, places: u32) -> Self {
+        Self::try_new(min, max, places).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DecimalRange, or an error if the range scaled to the 
+    /// smallest units can not be encoded
+    pub fn try_new(min: f64, max: f64, places: u32) -> Result<Self, Error> {
+        if (10 as SignedDigit).checked_pow(places).is_none() {
+            return Err(Error::InvalidDecimals(places));
+        }
+        if !min.is_finite() || !max.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        let limit = SignedDigit::MAX as f64;
+        let scale = pow10(places);
+        let (min_scaled, max_scaled) = (libm::round(min * scale), libm::round(max * scale));
+        if max_scaled >= limit {
+            return Err(Error::RangeExceedsLimit { value: max.to_string(), limit: (limit / scale).to_string() });
+        }
+        if min_scaled <= -limit {
+            return Err(Error::RangeExceedsLimit { value: min.to_string(), limit: (-limit / scale).to_string() });
+        }
+        if min_scaled >= max_scaled {
+            return Err(Error::CollapsedRange { min: min.to_string(), max: max.to_string() });
+        }
+        let (min, max) = (min_scaled as SignedDigit, max_scaled as SignedDigit);
+        if range_permutations(min, max).is_none() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { min, max, places })
+    }
+
+    fn encode_scaled(&self, scaled: SignedDigit, input: &dyn fmt::Display) -> Result<Digit, Error> {
+        if scaled < self.min || scaled > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: (self.min as f64 / pow10(self.places)).to_string(),
+                max: (self.max as f64 / pow10(self.places)).to_string(),
+            });
+        }
+        Ok((scaled as SignedDoubleDigit - self.min as SignedDoubleDigit) as Digit)
+    }
+
+    fn decode_scaled(&self, input: Digit) -> Result<SignedDigit, Error> {
+        let permutations = range_permutations(self.min, self.max).unwrap();
+        if input >= permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations });
+        }
+        Ok((input as SignedDoubleDigit + self.min as SignedDoubleDigit) as SignedDigit)
+    }
+}
+
+impl DataSpec<f64> for DecimalRange {
+
+    fn permutations(&self) -> Digit {
+        range_permutations(self.min, self.max).unwrap()
+    }
+
+    fn encode(&self, input: &f64) -> Result<Digit, Error> {
+        if !input.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        let scaled = libm::round(*input * pow10(self.places));
+        // Saturates for values far out of range, which are still rejected
+        self.encode_scaled(scaled as SignedDigit, input)
+    }
+
+    fn decode(&self, input: Digit) -> Result<f64, Error> {
+        Ok(self.decode_scaled(input)? as f64 / pow10(self.places))
+    }
+}
+
+impl DataSpec<SignedDigit> for DecimalRange {
+
+    fn permutations(&self) -> Digit {
+        range_permutations(self.min, self.max).unwrap()
+    }
+
+    fn encode(&self, input: &SignedDigit) -> Result<Digit, Error> {
+        self.encode_scaled(*input, &(*input as f64 / pow10(self.places)))
+    }
+
+    fn decode(&self, input: Digit) -> Result<SignedDigit, Error> {
+        self.decode_scaled(input)
+    }
+}
+
+/// Number from 0 to 1 (inclusive) divided into equal buckets, for 
+/// probabilities and other normalized values
+///
+/// 0 is in the first bucket and 1 in the last, and values decode to the 
+/// center of their bucket.
+///
+/// Note: The compression used by this data type is not lossless
+pub struct UnitInterval {
+    steps: u32,
+    clamp: bool,
+}
+
+impl UnitInterval {
+
+    pub fn new(steps: u32) -> Self {
+        Self::try_new(steps).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new UnitInterval with a number of buckets, or an error if 
+    /// there are no buckets
+    pub fn try_new(steps: u32) -> Result<Self, Error> {
+        if steps == 0 {
+            return Err(Error::ZeroStep);
+        }
+        Ok(Self { steps, clamp: false })
+    }
+
+    /// Clamp values outside of 0 to 1 (e.g. from floating point noise) to the
+    /// first or last bucket, instead of returning an error
+    pub fn with_clamping(mut self) -> Self {
+        self.clamp = true;
+        self
+    }
+
+    fn encode_f64(&self, input: f64) -> Result<Digit, Error> {
+        if input.is_nan() {
+            return Err(Error::NonFiniteValue);
+        }
+        if !self.clamp && !(0.0..=1.0).contains(&input) {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: 0.to_string(),
+                max: 1.to_string(),
+            });
+        }
+        let bucket = libm::floor(input * self.steps as f64);
+        Ok((bucket.max(0.0) as Digit).min(self.steps as Digit - 1))
+    }
+
+    fn decode_f64(&self, input: Digit) -> Result<f64, Error> {
+        if input >= self.steps as Digit {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.steps as Digit });
+        }
+        Ok((input as f64 + 0.5) / self.steps as f64)
+    }
+}
+
+impl DataSpec<f64> for UnitInterval {
+
+    fn permutations(&self) -> Digit {
+        self.steps as Digit
+    }
+
+    fn encode(&self, input: &f64) -> Result<Digit, Error> {
+        self.encode_f64(*input)
+    }
+
+    fn decode(&self, input: Digit) -> Result<f64, Error> {
+        self.decode_f64(input)
+    }
+}
+
+impl DataSpec<f32> for UnitInterval {
+
+    fn permutations(&self) -> Digit {
+        self.steps as Digit
+    }
+
+    fn encode(&self, input: &f32) -> Result<Digit, Error> {
+        self.encode_f64(*input as f64)
+    }
+
+    fn decode(&self, input: Digit) -> Result<f32, Error> {
+        Ok(self.decode_f64(input)? as f32)
+    }
+}
+
+/// Percentage from 0 to 100 (inclusive) in steps that divide 100 evenly
+///
+/// Values not on a step are rejected unless a rounding mode is set with 
+/// `with_rounding`. Decoded values are computed as `index * 100 / steps`, so 
+/// every percent that can be represented exactly as an f64 (e.g. all whole 
+/// percents) decodes exactly.
+pub struct Percent {
+    steps: Digit,
+    rounding: Option<RoundingMode>,
+}
+
+impl Percent {
+
+    /// Create a new Percent for whole percents (101 permutations)
+    pub fn whole() -> Self {
+        Self { steps: 100, rounding: None }
+    }
+
+    pub fn with_step(step: f64) -> Self {
+        Self::try_with_step(step).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Percent with a step (e.g. 0.5 for half percents), or an 
+    /// error if the step does not divide 100 evenly
+    pub fn try_with_step(step: f64) -> Result<Self, Error> {
+        if !step.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        if step <= 0.0 {
+            return Err(Error::ZeroStep);
+        }
+        let steps = libm::round(100.0 / step);
+        if steps < 1.0 || steps >= Digit::MAX as f64 || libm::fabs(steps * step - 100.0) > 1e-9 {
+            return Err(Error::ValueNotOnStep {
+                value: 100.to_string(),
+                min: 0.to_string(),
+                step: step.to_string(),
+            });
+        }
+        Ok(Self { steps: steps as Digit, rounding: None })
+    }
+
+    /// Round values that are not on a step instead of returning an error
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = Some(rounding);
+        self
+    }
+
+    /// Size of a step in percent
+    pub fn step(&self) -> f64 {
+        100.0 / self.steps as f64
+    }
+}
+
+impl DataSpec<f64> for Percent {
+
+    fn permutations(&self) -> Digit {
+        self.steps + 1
+    }
+
+    fn encode(&self, input: &f64) -> Result<Digit, Error> {
+        if input.is_nan() {
+            return Err(Error::NonFiniteValue);
+        }
+        if !(0.0..=100.0).contains(input) {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: 0.to_string(),
+                max: 100.to_string(),
+            });
+        }
+        let index = *input * self.steps as f64 / 100.0;
+        let index = match self.rounding {
+            None => {
+                let nearest = libm::round(index);
+                if libm::fabs(index - nearest) > 1e-9 {
+                    return Err(Error::ValueNotOnStep {
+                        value: input.to_string(),
+                        min: 0.to_string(),
+                        step: self.step().to_string(),
+                    });
+                }
+                nearest
+            },
+            Some(RoundingMode::Truncate) | Some(RoundingMode::Floor) => libm::floor(index),
+            Some(RoundingMode::Nearest) => libm::round(index),
+            Some(RoundingMode::Ceil) => libm::ceil(index),
+        };
+        Ok((index as Digit).min(self.steps))
+    }
+
+    fn decode(&self, input: Digit) -> Result<f64, Error> {
+        if input > self.steps {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok(input as f64 * 100.0 / self.steps as f64)
+    }
+}
+
+/// Largest maximum denominator for reduced fractions, which keep a count of 
+/// fractions for every denominator
+const MAX_REDUCED_DENOMINATOR: Digit = 1 << 16;
+
+/// Exact fraction as a (numerator, denominator) pair, with a range of 
+/// numerators and denominators from 1 to a maximum
+///
+/// Fractions are numbered by denominator first, then by numerator. With 
+/// `new_reduced` only fractions in lowest terms are valid (e.g. 2/4 is not, 
+/// 1/2 is), so no permutations are spent on the same number twice.
+pub struct RationalSpec {
+    min: SignedDigit,
+    max: SignedDigit,
+    max_denominator: Digit,
+    /// For reduced fractions, the number of fractions with a denominator less
+    /// than or equal to the index
+    counts: Option<Vec<Digit>>,
+}
+
+impl RationalSpec {
+
+    pub fn new(min: SignedDigit, max: SignedDigit, max_denominator: Digit) -> Self {
+        Self::try_new(min, max, max_denominator).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new RationalSpec for every numerator and denominator pair, or 
+    /// an error if the range is not valid
+    pub fn try_new(min: SignedDigit, max: SignedDigit, max_denominator: Digit) -> Result<Self, Error> {
+        if min > max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        if max_denominator == 0 {
+            return Err(Error::ZeroStep);
+        }
+        let numerators = max as SignedDoubleDigit - min as SignedDoubleDigit + 1;
+        if Digit::try_from(numerators * max_denominator as SignedDoubleDigit).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { min, max, max_denominator, counts: None })
+    }
+
+    pub fn new_reduced(min: SignedDigit, max: SignedDigit, max_denominator: Digit) -> Self {
+        Self::try_new_reduced(min, max, max_denominator).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new RationalSpec for fractions in lowest terms, or an error 
+    /// if the range is not valid
+    pub fn try_new_reduced(min: SignedDigit, max: SignedDigit, max_denominator: Digit) -> Result<Self, Error> {
+        let mut spec = Self::try_new(min, max, max_denominator)?;
+        if max_denominator > MAX_REDUCED_DENOMINATOR {
+            return Err(Error::RangeExceedsLimit {
+                value: max_denominator.to_string(),
+                limit: MAX_REDUCED_DENOMINATOR.to_string(),
+            });
+        }
+        let mut counts: Vec<Digit> = Vec::with_capacity(max_denominator as usize + 1);
+        counts.push(0);
+        for denominator in 1..=max_denominator {
+            let count = coprime_count(min as SignedDoubleDigit, max as SignedDoubleDigit + 1, denominator);
+            counts.push(counts[counts.len() - 1] + count as Digit);
+        }
+        if counts[counts.len() - 1] == 0 {
+            return Err(Error::CollapsedRange { min: min.to_string(), max: max.to_string() });
+        }
+        spec.counts = Some(counts);
+        Ok(spec)
+    }
+
+    /// Number of valid numerators from min up to (not including) a value
+    fn numerators_below(&self, value: SignedDoubleDigit, denominator: Digit) -> Digit {
+        match self.counts {
+            None => (value - self.min as SignedDoubleDigit) as Digit,
+            Some(_) => coprime_count(self.min as SignedDoubleDigit, value, denominator) as Digit,
+        }
+    }
+
+    /// Number of fractions with a denominator less than a value
+    fn fractions_below(&self, denominator: Digit) -> Digit {
+        match &self.counts {
+            None => (self.max as SignedDoubleDigit - self.min as SignedDoubleDigit + 1) as Digit * (denominator - 1),
+            Some(counts) => counts[denominator as usize - 1],
+        }
+    }
+}
+
+impl DataSpec<(SignedDigit, Digit)> for RationalSpec {
+
+    fn permutations(&self) -> Digit {
+        self.fractions_below(self.max_denominator + 1)
+    }
+
+    fn encode(&self, input: &(SignedDigit, Digit)) -> Result<Digit, Error> {
+        let (numerator, denominator) = *input;
+        if numerator < self.min || numerator > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: numerator.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        if denominator == 0 || denominator > self.max_denominator {
+            return Err(Error::ValueOutOfRange {
+                value: denominator.to_string(),
+                min: 1.to_string(),
+                max: self.max_denominator.to_string(),
+            });
+        }
+        if self.counts.is_some() && gcd(numerator.unsigned_abs() as Digit, denominator) != 1 {
+            return Err(Error::FractionNotReduced {
+                numerator: numerator.to_string(),
+                denominator: denominator.to_string(),
+            });
+        }
+        Ok(self.fractions_below(denominator) + self.numerators_below(numerator as SignedDoubleDigit, denominator))
+    }
+
+    fn decode(&self, input: Digit) -> Result<(SignedDigit, Digit), Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        // Largest denominator with fewer fractions below it than the input
+        let (mut low, mut high) = (1, self.max_denominator);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if self.fractions_below(mid) <= input {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        let denominator = low;
+        let rank = input - self.fractions_below(denominator);
+        // Largest numerator with rank valid numerators below it
+        let (mut low, mut high) = (self.min as SignedDoubleDigit, self.max as SignedDoubleDigit);
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if self.numerators_below(mid, denominator) <= rank {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok((low as SignedDigit, denominator))
+    }
+}
+
+/// Greatest common divisor
+fn gcd(mut a: Digit, mut b: Digit) -> Digit {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Count the integers in [low, high) that are coprime with a value, using 
+/// inclusion-exclusion over the prime factors of the value
+fn coprime_count(low: SignedDoubleDigit, high: SignedDoubleDigit, value: Digit) -> SignedDoubleDigit {
+    let mut primes: Vec<SignedDoubleDigit> = Vec::new();
+    let mut rest = value;
+    let mut factor: Digit = 2;
+    while factor * factor <= rest {
+        if rest.is_multiple_of(factor) {
+            primes.push(factor as SignedDoubleDigit);
+            while rest.is_multiple_of(factor) {
+                rest /= factor;
+            }
+        }
+        factor += 1;
+    }
+    if rest > 1 {
+        primes.push(rest as SignedDoubleDigit);
+    }
+    let multiples = |m: SignedDoubleDigit| (high - 1).div_euclid(m) - (low - 1).div_euclid(m);
+    let mut count = 0;
+    for subset in 0..(1u32 << primes.len()) {
+        let product: SignedDoubleDigit = primes.iter()
+            .enumerate()
+            .filter(|(ii, _)| subset & (1 << ii) != 0)
+            .map(|(_, p)| p)
+            .product();
+        if subset.count_ones() % 2 == 0 {
+            count += multiples(product);
+        } else {
+            count -= multiples(product);
+        }
+    }
+    count
+}
+
+/// Calculate 10^exponent exactly for exponents that fit in a SignedDigit
+fn pow10(exponent: u32) -> f64 {
+    (10 as SignedDigit).pow(exponent) as f64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::string::{String, ToString};
+
+    use crate::accum::{Accumulator, Digit, SignedDigit};
+    use crate::data::{DataSpec, WideDataSpec, SequenceLength, Sequencer};
+    use crate::data::{BiasedUInt, DecimalRange, FixedPointRange, IntRange, LogScaleRange};
+    use crate::data::{Bool, Percent, RationalSpec, RoundingMode, UIntRange, UnitInterval, VarUInt};
+    use crate::error::Error;
+
+    use super::MAX_REDUCED_DENOMINATOR;
+
+    #[test]
+    fn log_scale_range() {
+        // Latencies from 1 microsecond to 1 hour
+        let r = LogScaleRange::new(1e-6, 3600.0, 1000);
+        assert_eq!(r.permutations(), 1001);
+        assert_eq!(r.encode(&1e-6).unwrap(), 0);
+        assert_eq!(r.encode(&3600.0).unwrap(), 1000);
+        assert_eq!(r.decode(0).unwrap(), 1e-6);
+        assert_eq!(r.decode(1000).unwrap(), 3600.0);
+        assert!(r.decode(1001).is_err());
+        assert_eq!(r.encode(&0.0), Err(Error::ValueOutOfRange {
+            value: String::from("0"),
+            min: String::from("0.000001"),
+            max: String::from("3600"),
+        }));
+        assert!(r.encode(&-1.0).is_err());
+        assert!(r.encode(&3601.0).is_err());
+        assert_eq!(r.encode(&f64::NAN), Err(Error::NonFiniteValue));
+    }
+
+    #[test]
+    fn log_scale_range_relative_error() {
+        let steps: Digit = 500;
+        let (min, max) = (1.0, 1e12);
+        let r = LogScaleRange::new(min, max, steps);
+        let bound = libm::pow(max / min, 0.5 / steps as f64) - 1.0;
+        let mut value = min;
+        while value <= max {
+            let decoded = r.decode(r.encode(&value).unwrap()).unwrap();
+            let error = libm::fabs(decoded - value) / value;
+            assert!(error <= bound * (1.0 + 1e-9), "{} decoded as {}", value, decoded);
+            value *= 1.01;
+        }
+    }
+
+    #[test]
+    fn log_scale_range_per_decade() {
+        let r = LogScaleRange::new_per_decade(1.0, 1e6, 10.0);
+        assert_eq!(r.permutations(), 61);
+        assert_eq!(r.encode(&10.0).unwrap(), 10);
+        assert_eq!(r.encode(&1000.0).unwrap(), 30);
+        assert!(libm::fabs(r.decode(20).unwrap() - 100.0) < 1e-9);
+        // Partial decades round up the number of steps
+        let r = LogScaleRange::new_per_decade(1.0, 500.0, 4.0);
+        assert_eq!(r.permutations(), 12);
+    }
+
+    #[test]
+    fn log_scale_range_zero() {
+        let r = LogScaleRange::new(1.0, 1024.0, 10).with_zero();
+        assert_eq!(r.permutations(), 12);
+        assert_eq!(r.encode(&0.0).unwrap(), 0);
+        assert_eq!(r.encode(&1.0).unwrap(), 1);
+        assert_eq!(r.encode(&2.0).unwrap(), 2);
+        assert_eq!(r.encode(&1024.0).unwrap(), 11);
+        assert_eq!(r.decode(0).unwrap(), 0.0);
+        assert_eq!(r.decode(1).unwrap(), 1.0);
+        assert!(libm::fabs(r.decode(2).unwrap() - 2.0) < 1e-9);
+        assert!(r.encode(&0.5).is_err());
+        assert!(r.encode(&-1.0).is_err());
+    }
+
+    #[test]
+    fn log_scale_range_try_new() {
+        assert!(matches!(LogScaleRange::try_new(0.0, 10.0, 10), Err(Error::RangeExceedsLimit { .. })));
+        assert!(matches!(LogScaleRange::try_new(-1.0, 10.0, 10), Err(Error::RangeExceedsLimit { .. })));
+        assert!(matches!(LogScaleRange::try_new(10.0, 10.0, 10), Err(Error::InvalidRange { .. })));
+        assert_eq!(LogScaleRange::try_new(1.0, f64::INFINITY, 10).err(), Some(Error::NonFiniteValue));
+        assert_eq!(LogScaleRange::try_new(1.0, 10.0, 0).err(), Some(Error::ZeroStep));
+        assert_eq!(LogScaleRange::try_new(1.0, 10.0, Digit::MAX).err(), Some(Error::TooManyPermutations));
+        assert!(matches!(LogScaleRange::try_new_per_decade(10.0, 1.0, 10.0), Err(Error::InvalidRange { .. })));
+        assert_eq!(LogScaleRange::try_new_per_decade(1.0, 10.0, f64::NAN).err(), Some(Error::NonFiniteValue));
+    }
+
+    #[test]
+    fn seq_log_scale_range() {
+        let mut a = Accumulator::new();
+        let r = LogScaleRange::new(1.0, 1e9, 90).with_zero();
+        let sizes = &[0.0, 1.0, 1e3, 1e6, 1e9];
+        let sequencer = Sequencer::new(&r, SequenceLength::Variable(10));
+        sequencer.compress(sizes, &mut a).unwrap();
+        let decoded = sequencer.decompress(&mut a).unwrap();
+        for (value, decoded) in sizes.iter().zip(decoded.iter()) {
+            assert!(libm::fabs(value - decoded) <= value * 1e-9);
+        }
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn biased_uint() {
+        let spec = BiasedUInt::new(1_000_000);
+        let mut a = Accumulator::new();
+        // Values on both sides of bucket boundaries
+        for value in [0, 1, 2, 3, 6, 7, 14, 15, 1022, 1023, 524286, 524287, 999_999, 1_000_000] {
+            spec.encode_into(&value, &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a).unwrap(), value);
+            assert!(a.is_empty());
+        }
+        for value in 0..300 {
+            spec.encode_into(&value, &mut a).unwrap();
+        }
+        for value in (0..300).rev() {
+            assert_eq!(spec.decode_from(&mut a).unwrap(), value);
+        }
+        assert!(a.is_empty());
+        assert!(spec.encode_into(&1_000_001, &mut a).is_err());
+        // Full range
+        let spec = BiasedUInt::new(Digit::MAX);
+        for value in [0, 1, Digit::MAX / 2, Digit::MAX - 1, Digit::MAX] {
+            spec.encode_into(&value, &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a).unwrap(), value);
+            assert!(a.is_empty());
+        }
+    }
+
+    #[test]
+    fn seq_biased_uint() {
+        let small: &[Digit] = &[0, 3, 1, 0, 10, 2, 7, 5, 0, 1, 4, 9, 0, 0, 2, 8];
+        let biased = BiasedUInt::new(u32::MAX as Digit);
+        let flat = UIntRange::new(0, u32::MAX as Digit - 1);
+        let mut a = Accumulator::new();
+        let mut b = Accumulator::new();
+        for _ in 0..8 {
+            Sequencer::new(&biased, SequenceLength::Fixed(small.len())).compress(small, &mut a).unwrap();
+            Sequencer::new(&flat, SequenceLength::Fixed(small.len())).compress(small, &mut b).unwrap();
+        }
+        assert!(a.len() * 3 < b.len(), "{} digits vs {} digits", a.len(), b.len());
+        // Spikes still work
+        let deltas: &[Digit] = &[0, 1, 3_000_000, 2, u32::MAX as Digit];
+        let sequencer = Sequencer::new(&biased, SequenceLength::Variable(10));
+        sequencer.compress(deltas, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), deltas);
+        let sequencer = Sequencer::new(&biased, SequenceLength::Fixed(small.len()));
+        for _ in 0..8 {
+            assert_eq!(sequencer.decompress(&mut a).unwrap(), small);
+        }
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn var_uint() {
+        let spec = VarUInt::new(16);
+        let mut a = Accumulator::new();
+        for value in [0, 1, 15, 16, 17, 16 + 15, 16 + 16, 1000, Digit::MAX / 2, Digit::MAX / 2 + 1, Digit::MAX - 1, Digit::MAX] {
+            spec.encode_into(&value, &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a).unwrap(), value);
+            assert!(a.is_empty());
+        }
+        // Small values are a single draw
+        spec.encode_into(&15, &mut a).unwrap();
+        assert_eq!(a.div(17), 15);
+        assert!(a.is_empty());
+        assert_eq!(VarUInt::try_new(1).err(), Some(Error::InvalidBase(1)));
+        assert_eq!(VarUInt::try_new(Digit::MAX).err(), Some(Error::InvalidBase(Digit::MAX)));
+        assert!(VarUInt::try_new(Digit::MAX - 1).is_ok());
+        // Values too large for a Digit
+        let spec = VarUInt::new(2);
+        a.mul(3); // end of digits
+        for _ in 0..Digit::BITS {
+            a.mul(3);
+            a.add(2);
+        }
+        a.mul(3);
+        a.add(2); // escape
+        assert!(matches!(spec.decode_from(&mut a), Err(Error::DecodeOutOfRange { .. })));
+        // Digits without an end
+        let mut a = Accumulator::new();
+        for _ in 0..(Digit::BITS + 2) {
+            a.mul(3);
+            a.add(1);
+        }
+        a.mul(3);
+        a.add(2);
+        assert!(matches!(spec.decode_from(&mut a), Err(Error::UnterminatedSequence { .. })));
+    }
+
+    #[test]
+    fn seq_var_uint() {
+        let mut a = Accumulator::new();
+        let base = 1000;
+        let var = VarUInt::new(base);
+        let int = IntRange::new(-5, 5);
+        let counts: &[Digit] = &[0, 1, base - 1, base, base + 1, Digit::MAX / 3, 3];
+        let ints = &[-5, 0, 5];
+        let sequencer0 = Sequencer::new(&int, SequenceLength::Fixed(3));
+        let sequencer1 = Sequencer::new(&var, SequenceLength::Fixed(counts.len()));
+        let sequencer2 = Sequencer::new(&var, SequenceLength::Variable(10));
+        sequencer0.compress(ints, &mut a).unwrap();
+        sequencer1.compress(counts, &mut a).unwrap();
+        sequencer0.compress(ints, &mut a).unwrap();
+        sequencer2.compress(counts, &mut a).unwrap();
+        sequencer0.compress(ints, &mut a).unwrap();
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), ints);
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), counts);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), ints);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), counts);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), ints);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn decimal_range() {
+        let r = DecimalRange::new(0.0, 1000.0, 2);
+        assert_eq!(DataSpec::<f64>::permutations(&r), 100_001);
+        for value in [0.0, 0.01, 0.1, 0.29, 19.99, 999.99, 1000.0] {
+            assert_eq!(r.decode(r.encode(&value).unwrap()), Ok(value));
+        }
+        assert_eq!(r.encode(&19.99).unwrap(), 1999);
+        assert_eq!(r.encode(&0.014).unwrap(), 1);
+        assert_eq!(r.encode(&0.015).unwrap(), 2);
+        // The binary fixed point spec can not represent these exactly
+        let f = FixedPointRange::new(0.0, 1000.0, 7);
+        assert_ne!(f.decode(f.encode(&19.99).unwrap()), Ok(19.99));
+        assert_ne!(f.decode(f.encode(&0.01).unwrap()), Ok(0.01));
+        // Minor units
+        let r = DecimalRange::new(-50.0, 50.0, 2);
+        assert_eq!(r.encode(&-5000).unwrap(), 0);
+        assert_eq!(r.encode(&1999).unwrap(), 6999);
+        assert_eq!(r.decode(6999), Ok(19.99));
+        assert_eq!(DataSpec::<SignedDigit>::decode(&r, 6999), Ok(1999));
+        assert!(r.encode(&5001).is_err());
+        assert!(r.encode(&50.01).is_err());
+        assert_eq!(r.encode(&f64::NAN), Err(Error::NonFiniteValue));
+        assert_eq!(r.encode(&f64::INFINITY), Err(Error::NonFiniteValue));
+        assert_eq!(r.encode(&-1e300), Err(Error::ValueOutOfRange {
+            value: (-1e300f64).to_string(),
+            min: String::from("-50"),
+            max: String::from("50"),
+        }));
+    }
+
+    #[test]
+    fn decimal_range_try_new() {
+        assert_eq!(DecimalRange::try_new(0.0, 1.0, 30).err(), Some(Error::InvalidDecimals(30)));
+        assert_eq!(DecimalRange::try_new(f64::NAN, 1.0, 2).err(), Some(Error::NonFiniteValue));
+        assert!(matches!(DecimalRange::try_new(1.0, 1.0, 2), Err(Error::InvalidRange { .. })));
+        // Both bounds round to the same unit
+        assert!(matches!(DecimalRange::try_new(1.001, 1.002, 2), Err(Error::CollapsedRange { .. })));
+        assert!(matches!(DecimalRange::try_new(0.0, 1e30, 2), Err(Error::RangeExceedsLimit { .. })));
+        assert!(matches!(DecimalRange::try_new(-1e30, 0.0, 2), Err(Error::RangeExceedsLimit { .. })));
+    }
+
+    #[test]
+    fn seq_decimal_range() {
+        let mut a = Accumulator::new();
+        let r = DecimalRange::new(0.0, 10_000.0, 2);
+        let prices = &[19.99, 0.01, 4.95, 9999.99, 0.1];
+        let sequencer = Sequencer::new(&r, SequenceLength::Fixed(prices.len()));
+        sequencer.compress(prices, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), prices);
+        let cents = &[1999, 1, 495];
+        let sequencer = Sequencer::new(&r, SequenceLength::Variable(5));
+        sequencer.compress(cents, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), cents);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn unit_interval() {
+        let r = UnitInterval::new(4);
+        assert_eq!(DataSpec::<f64>::permutations(&r), 4);
+        for (value, bucket) in [(0.0, 0), (0.2499, 0), (0.25, 1), (0.5, 2), (0.7499, 2), (0.75, 3), (1.0, 3)] {
+            assert_eq!(r.encode(&value), Ok(bucket), "{}", value);
+        }
+        assert_eq!(r.decode(0), Ok(0.125));
+        assert_eq!(r.decode(3), Ok(0.875));
+        assert!(DataSpec::<f64>::decode(&r, 4).is_err());
+        assert_eq!(r.encode(&0.5f32), Ok(2));
+        assert_eq!(r.decode(1), Ok(0.375f32));
+        // Values just outside of the interval
+        assert_eq!(r.encode(&-1e-12), Err(Error::ValueOutOfRange {
+            value: (-1e-12).to_string(),
+            min: String::from("0"),
+            max: String::from("1"),
+        }));
+        assert!(r.encode(&1.000001).is_err());
+        assert_eq!(r.encode(&f64::NAN), Err(Error::NonFiniteValue));
+        assert_eq!(r.encode(&f32::NAN), Err(Error::NonFiniteValue));
+        let r = UnitInterval::new(4).with_clamping();
+        assert_eq!(r.encode(&-1e-12), Ok(0));
+        assert_eq!(r.encode(&1.000001), Ok(3));
+        assert_eq!(r.encode(&-1.0f32), Ok(0));
+        assert_eq!(r.encode(&f64::INFINITY), Ok(3));
+        assert_eq!(r.encode(&f64::NAN), Err(Error::NonFiniteValue));
+        assert_eq!(UnitInterval::try_new(0).err(), Some(Error::ZeroStep));
+    }
+
+    #[test]
+    fn seq_unit_interval() {
+        let mut a = Accumulator::new();
+        let scores: &[f64] = &[0.0, 0.01, 0.5, 0.999, 1.0];
+        let volumes: &[f32] = &[0.0, 0.33, 0.66, 1.0];
+        let r100 = UnitInterval::new(100);
+        let r1024 = UnitInterval::new(1024);
+        let sequencer0 = Sequencer::new(&r100, SequenceLength::Fixed(scores.len()));
+        let sequencer1 = Sequencer::new(&r1024, SequenceLength::Variable(10));
+        sequencer0.compress(scores, &mut a).unwrap();
+        sequencer1.compress(volumes, &mut a).unwrap();
+        let decoded = sequencer1.decompress(&mut a).unwrap();
+        for (value, decoded) in volumes.iter().zip(decoded.iter()) {
+            assert!((value - decoded).abs() <= 0.5 / 1024.0);
+        }
+        let decoded = sequencer0.decompress(&mut a).unwrap();
+        for (value, decoded) in scores.iter().zip(decoded.iter()) {
+            assert!(libm::fabs(value - decoded) <= 0.5 / 100.0 + 1e-12);
+        }
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn percent() {
+        let p = Percent::whole();
+        assert_eq!(p.permutations(), 101);
+        for ii in 0..=100 {
+            assert_eq!(p.encode(&(ii as f64)), Ok(ii as Digit));
+            assert_eq!(p.decode(ii as Digit), Ok(ii as f64));
+        }
+        assert!(p.decode(101).is_err());
+        assert_eq!(p.encode(&50.5), Err(Error::ValueNotOnStep {
+            value: String::from("50.5"),
+            min: String::from("0"),
+            step: String::from("1"),
+        }));
+        assert!(matches!(p.encode(&100.5), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(p.encode(&-0.1), Err(Error::ValueOutOfRange { .. })));
+        assert_eq!(p.encode(&f64::NAN), Err(Error::NonFiniteValue));
+        let p = Percent::with_step(0.5);
+        assert_eq!(p.permutations(), 201);
+        assert_eq!(p.step(), 0.5);
+        assert_eq!(p.encode(&0.0), Ok(0));
+        assert_eq!(p.encode(&12.5), Ok(25));
+        assert_eq!(p.encode(&100.0), Ok(200));
+        assert_eq!(p.decode(25), Ok(12.5));
+        assert!(p.encode(&12.25).is_err());
+        let p = Percent::with_step(0.1);
+        assert_eq!(p.permutations(), 1001);
+        assert_eq!(p.encode(&33.3), Ok(333));
+        assert_eq!(p.decode(333), Ok(33.3));
+        // Rounding
+        let p = Percent::with_step(0.5).with_rounding(RoundingMode::Nearest);
+        assert_eq!(p.encode(&12.3), Ok(25));
+        assert_eq!(p.encode(&99.9), Ok(200));
+        assert!(p.encode(&100.1).is_err());
+        let p = Percent::with_step(0.5).with_rounding(RoundingMode::Ceil);
+        assert_eq!(p.encode(&12.1), Ok(25));
+        let p = Percent::with_step(0.5).with_rounding(RoundingMode::Truncate);
+        assert_eq!(p.encode(&12.9), Ok(25));
+        // Steps that do not divide 100
+        assert!(matches!(Percent::try_with_step(0.3), Err(Error::ValueNotOnStep { .. })));
+        assert!(matches!(Percent::try_with_step(200.0), Err(Error::ValueNotOnStep { .. })));
+        assert_eq!(Percent::try_with_step(0.0).err(), Some(Error::ZeroStep));
+        assert_eq!(Percent::try_with_step(f64::NAN).err(), Some(Error::NonFiniteValue));
+        assert_eq!(Percent::with_step(25.0).permutations(), 5);
+    }
+
+    #[test]
+    fn seq_percent() {
+        // Battery level, charging flag and temperature for a few devices
+        let mut a = Accumulator::new();
+        let levels: &[f64] = &[0.0, 12.5, 99.5, 100.0];
+        let charging = &[true, false, false, true];
+        let temperatures: &[SignedDigit] = &[-20, 25, 40, 60];
+        let percent = Percent::with_step(0.5);
+        let bool = Bool::new();
+        let temperature = IntRange::new(-40, 85);
+        let sequencer0 = Sequencer::new(&percent, SequenceLength::Variable(8));
+        let sequencer1 = Sequencer::new(&bool, SequenceLength::Fixed(4));
+        let sequencer2 = Sequencer::new(&temperature, SequenceLength::Fixed(4));
+        sequencer0.compress(levels, &mut a).unwrap();
+        sequencer1.compress(charging, &mut a).unwrap();
+        sequencer2.compress(temperatures, &mut a).unwrap();
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), temperatures);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), charging);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), levels);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn rational() {
+        let r = RationalSpec::new(-16, 16, 16);
+        assert_eq!(r.permutations(), 33 * 16);
+        for fraction in [(16, 9), (3, 4), (-3, 4), (0, 1), (2, 4), (-16, 1), (16, 16), (-16, 16)] {
+            assert_eq!(r.decode(r.encode(&fraction).unwrap()), Ok(fraction));
+        }
+        assert_eq!(r.encode(&(-16, 1)), Ok(0));
+        assert_eq!(r.encode(&(16, 16)), Ok(33 * 16 - 1));
+        assert!(matches!(r.encode(&(17, 1)), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(r.encode(&(1, 0)), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(r.encode(&(1, 17)), Err(Error::ValueOutOfRange { .. })));
+        assert!(r.decode(33 * 16).is_err());
+        assert!(matches!(RationalSpec::try_new(1, 0, 4), Err(Error::InvalidRange { .. })));
+        assert_eq!(RationalSpec::try_new(0, 1, 0).err(), Some(Error::ZeroStep));
+        assert_eq!(RationalSpec::try_new(0, SignedDigit::MAX, 4).err(), Some(Error::TooManyPermutations));
+    }
+
+    #[test]
+    fn rational_reduced() {
+        let r = RationalSpec::new_reduced(-16, 16, 16);
+        let mut count = 0;
+        for denominator in 1..=16 {
+            for numerator in -16..=16 {
+                let fraction = (numerator, denominator);
+                let encoded = r.encode(&fraction);
+                if gcd_test(numerator.unsigned_abs(), denominator) == 1 {
+                    assert_eq!(encoded, Ok(count));
+                    assert_eq!(r.decode(count), Ok(fraction));
+                    count += 1;
+                } else {
+                    assert!(matches!(encoded, Err(Error::FractionNotReduced { .. })), "{:?}", fraction);
+                }
+            }
+        }
+        assert_eq!(r.permutations(), count);
+        assert!(r.permutations() < RationalSpec::new(-16, 16, 16).permutations());
+        assert_eq!(r.encode(&(2, 4)), Err(Error::FractionNotReduced {
+            numerator: String::from("2"),
+            denominator: String::from("4"),
+        }));
+        assert!(r.encode(&(0, 2)).is_err());
+        assert!(r.decode(count).is_err());
+        // Time signatures
+        let r = RationalSpec::new_reduced(1, 16, 16);
+        for fraction in [(3, 4), (7, 8), (5, 4), (16, 9)] {
+            assert_eq!(r.decode(r.encode(&fraction).unwrap()), Ok(fraction));
+        }
+        assert!(matches!(
+            RationalSpec::try_new_reduced(0, 10, MAX_REDUCED_DENOMINATOR + 1),
+            Err(Error::RangeExceedsLimit { .. })
+        ));
+    }
+
+    fn gcd_test(a: Digit, b: Digit) -> Digit {
+        if b == 0 { a } else { gcd_test(b, a % b) }
+    }
+
+    #[test]
+    fn seq_rational() {
+        let mut a = Accumulator::new();
+        let ratios = &[(16, 9), (4, 3), (21, 9), (1, 1)];
+        let spec = RationalSpec::new(1, 32, 16);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(8));
+        sequencer.compress(ratios, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), ratios);
+        assert!(a.is_empty());
+    }
+}
@@ -0,0 +1,330 @@
+//! Records of named fields of different types
+
+use core::result::Result;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::accum::Accumulator;
+use crate::data::{max_bytes, SequenceLength, Sequencer, WideDataSpec};
+use crate::error::Error;
+
+/// Record of fields of different types (e.g. a bool, two ints and a string),
+/// compressed to and from a tuple of values in the order the fields were
+/// added
+///
+/// Fields are compressed last to first so they decompress first to last, the
+/// order is handled by the schema. If any field can not be compressed or
+/// decompressed the accumulator is left unchanged and the error gives the name
+/// of the field. Schemas have at most 12 fields, a field can be a Tuple2Spec
+/// or Tuple3Spec of several values to fit more.
+pub struct Schema<F> {
+    fields: F,
+}
+
+impl Schema<()> {
+
+    pub fn new() -> Self {
+        Self { fields: () }
+    }
+}
+
+impl Default for Schema<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Fields> Schema<F> {
+
+    /// Add a field compressed with a spec
+    pub fn field<'a, T, S>(self, name: &str, spec: S) -> Schema<F::Output>
+    where F: PushField<'a, T>, S: WideDataSpec<T> + 'a {
+        Schema { fields: self.fields.push(Field { name: String::from(name), spec: Box::new(spec) }) }
+    }
+
+    /// Add a field of a sequence of values of a spec
+    pub fn field_seq<'a, T: 'a, S>(self, name: &str, spec: S, length: SequenceLength) -> Schema<F::Output>
+    where F: PushField<'a, Vec<T>>, S: WideDataSpec<T> + 'a {
+        let spec = Sequencer::owned(spec, length);
+        Schema { fields: self.fields.push(Field { name: String::from(name), spec: Box::new(spec) }) }
+    }
+
+    /// Compress the values of every field into the accumulator, leaving it
+    /// unchanged on error
+    pub fn compress(&self, values: &F::Values, accum: &mut Accumulator) -> Result<(), Error> {
+        let snapshot = accum.clone();
+        let result = self.fields.compress(values, accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    /// Number of bits the values of every field add to the accumulator in the
+    /// worst case, or None if any field is not bounded
+    pub fn max_bits(&self) -> Option<f64> {
+        self.fields.max_bits()
+    }
+
+    /// Number of bytes of the accumulator (see `Accumulator::to_bytes`) in
+    /// the worst case, if the values are the only thing in it
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bits().map(max_bytes)
+    }
+
+    /// Decompress the values of every field from the accumulator, leaving it
+    /// unchanged on error
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<F::Values, Error> {
+        let snapshot = accum.clone();
+        let result = self.fields.decompress(accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+}
+
+/// Named field of a Schema
+pub struct Field<'a, T> {
+    name: String,
+    spec: Box<dyn WideDataSpec<T> + 'a>,
+}
+
+impl<T> Field<'_, T> {
+
+    fn compress(&self, value: &T, accum: &mut Accumulator) -> Result<(), Error> {
+        self.spec.encode_into(value, accum).map_err(|error| self.error(error))
+    }
+
+    fn decompress(&self, accum: &mut Accumulator) -> Result<T, Error> {
+        self.spec.decode_from(accum).map_err(|error| self.error(error))
+    }
+
+    fn max_bits(&self) -> Option<f64> {
+        self.spec.max_bits()
+    }
+
+    fn error(&self, error: Error) -> Error {
+        field_error(&self.name, error)
+    }
+}
+
+/// Error for a single field of a schema or a struct with `#[derive(Adamas)]`
+#[doc(hidden)]
+pub fn field_error(name: &str, error: Error) -> Error {
+    Error::Field { name: String::from(name), error: Box::new(error) }
+}
+
+/// Tuple of the fields of a Schema
+pub trait Fields {
+    /// Tuple of the values of the fields
+    type Values;
+
+    fn compress(&self, values: &Self::Values, accum: &mut Accumulator) -> Result<(), Error>;
+    fn decompress(&self, accum: &mut Accumulator) -> Result<Self::Values, Error>;
+    fn max_bits(&self) -> Option<f64>;
+}
+
+/// Tuple of the fields of a Schema that can have another field added
+pub trait PushField<'a, T>: Fields {
+    type Output: Fields;
+
+    fn push(self, field: Field<'a, T>) -> Self::Output;
+}
+
+impl Fields for () {
+    type Values = ();
+
+    fn compress(&self, _values: &(), _accum: &mut Accumulator) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn decompress(&self, _accum: &mut Accumulator) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn max_bits(&self) -> Option<f64> {
+        Some(0.0)
+    }
+}
+
+impl<'a, T> PushField<'a, T> for () {
+    type Output = (Field<'a, T>,);
+
+    fn push(self, field: Field<'a, T>) -> Self::Output {
+        (field,)
+    }
+}
+
+/// Implement Fields for a tuple of fields, given its types and indices first
+/// to last and last to first
+macro_rules! impl_fields {
+    (($($t:ident $i:tt $v:ident),+), ($($ri:tt),+)) => {
+        impl<'a, $($t),+> Fields for ($(Field<'a, $t>,)+) {
+            type Values = ($($t,)+);
+
+            fn compress(&self, values: &Self::Values, accum: &mut Accumulator) -> Result<(), Error> {
+                $(self.$ri.compress(&values.$ri, accum)?;)+
+                Ok(())
+            }
+
+            fn decompress(&self, accum: &mut Accumulator) -> Result<Self::Values, Error> {
+                $(let $v = self.$i.decompress(accum)?;)+
+                Ok(($($v,)+))
+            }
+
+            fn max_bits(&self) -> Option<f64> {
+                Some(0.0 $(+ self.$i.max_bits()?)+)
+            }
+        }
+    };
+}
+
+/// Implement PushField for a tuple of fields that is not full
+macro_rules! impl_push_field {
+    ($($t:ident $i:tt),+) => {
+        impl<'a, $($t,)+ T> PushField<'a, T> for ($(Field<'a, $t>,)+) {
+            type Output = ($(Field<'a, $t>,)+ Field<'a, T>);
+
+            fn push(self, field: Field<'a, T>) -> Self::Output {
+                ($(self.$i,)+ field)
+            }
+        }
+    };
+}
+
+impl_fields!((A 0 a), (0));
+impl_fields!((A 0 a, B 1 b), (1, 0));
+impl_fields!((A 0 a, B 1 b, C 2 c), (2, 1, 0));
+impl_fields!((A 0 a, B 1 b, C 2 c, D 3 d), (3, 2, 1, 0));
+impl_fields!((A 0 a, B 1 b, C 2 c, D 3 d, E 4 e), (4, 3, 2, 1, 0));
+impl_fields!((A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f), (5, 4, 3, 2, 1, 0));
+impl_fields!((A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f, G 6 g), (6, 5, 4, 3, 2, 1, 0));
+impl_fields!(
+    (A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f, G 6 g, H 7 h),
+    (7, 6, 5, 4, 3, 2, 1, 0)
+);
+impl_fields!(
+    (A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f, G 6 g, H 7 h, I 8 i),
+    (8, 7, 6, 5, 4, 3, 2, 1, 0)
+);
+impl_fields!(
+    (A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f, G 6 g, H 7 h, I 8 i, J 9 j),
+    (9, 8, 7, 6, 5, 4, 3, 2, 1, 0)
+);
+impl_fields!(
+    (A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f, G 6 g, H 7 h, I 8 i, J 9 j, K 10 k),
+    (10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0)
+);
+impl_fields!(
+    (A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f, G 6 g, H 7 h, I 8 i, J 9 j, K 10 k, L 11 l),
+    (11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0)
+);
+
+impl_push_field!(A 0);
+impl_push_field!(A 0, B 1);
+impl_push_field!(A 0, B 1, C 2);
+impl_push_field!(A 0, B 1, C 2, D 3);
+impl_push_field!(A 0, B 1, C 2, D 3, E 4);
+impl_push_field!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_push_field!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_push_field!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_push_field!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_push_field!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_push_field!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::boxed::Box;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::accum::{Accumulator, Digit};
+    use crate::data::{Bool, CharSet, Enum, IntRange, OptionSpec, Schema, SequenceLength, StringSpec};
+    use crate::data::{UIntRange, VarUInt, VersionedEnum};
+    use crate::error::Error;
+
+    #[test]
+    fn schema() {
+        let schema = Schema::new()
+            .field("active", Bool::new())
+            .field("level", IntRange::new(-100, 100))
+            .field("name", StringSpec::new(CharSet::lowercase_letter(), SequenceLength::Variable(16)))
+            .field("status", Enum::new(&["ok", "warning", "error"]))
+            .field("parent", OptionSpec::new(UIntRange::new(0, 9999)))
+            .field_seq("scores", UIntRange::new(0, 10), SequenceLength::Variable(8));
+        let values = (true, -37, String::from("adamas"), String::from("warning"), Some(1234), vec![0, 10, 3]);
+        let mut a = Accumulator::new();
+        schema.compress(&values, &mut a).unwrap();
+        let bytes = a.to_bytes();
+        assert_eq!(schema.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+        // The same values always compress the same
+        schema.compress(&values, &mut a).unwrap();
+        assert_eq!(a.to_bytes(), bytes);
+        let other = (false, 100, String::new(), String::from("ok"), None, vec![]);
+        schema.compress(&other, &mut a).unwrap();
+        assert_eq!(schema.decompress(&mut a).unwrap(), other);
+        assert_eq!(schema.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn schema_errors() {
+        let schema = Schema::new()
+            .field("active", Bool::new())
+            .field("status", Enum::new(&["ok", "error"]))
+            .field_seq("tag", CharSet::lowercase_letter(), SequenceLength::Variable(4));
+        let mut a = Accumulator::new();
+        let values = (true, String::from("fine"), vec!['a']);
+        assert_eq!(schema.compress(&values, &mut a), Err(Error::Field {
+            name: String::from("status"),
+            error: Box::new(Error::UnknownEnumVariant(String::from("fine"))),
+        }));
+        assert!(a.is_empty());
+        let values = (true, String::from("ok"), vec!['a', 'B']);
+        assert_eq!(schema.compress(&values, &mut a), Err(Error::Field {
+            name: String::from("tag"),
+            error: Box::new(Error::Element { index: 1, error: Box::new(Error::CharNotInSet('B')) }),
+        }));
+        assert!(a.is_empty());
+        // The second field has an index that is not defined yet
+        let schema = Schema::new().field("flag", Bool::new()).field("kind", VersionedEnum::new(&["a", "b"], 4));
+        a.add(3);
+        a.mul(2);
+        a.add(1);
+        let bytes = a.to_bytes();
+        assert_eq!(schema.decompress(&mut a), Err(Error::Field {
+            name: String::from("kind"),
+            error: Box::new(Error::UnknownVariantIndex(3)),
+        }));
+        assert_eq!(a.to_bytes(), bytes);
+        a = Accumulator::new();
+        let empty: Vec<char> = Vec::new();
+        let schema = Schema::default().field_seq("empty", CharSet::digits(), SequenceLength::Fixed(0));
+        schema.compress(&(empty.clone(),), &mut a).unwrap();
+        assert_eq!(schema.decompress(&mut a), Ok((empty,)));
+    }
+
+    #[test]
+    fn schema_max_bits() {
+        let schema = Schema::new()
+            .field("active", Bool::new())
+            .field("level", IntRange::new(-100, 100))
+            .field("name", StringSpec::new(CharSet::lowercase_letter(), SequenceLength::Variable(16)))
+            .field_seq("scores", UIntRange::new(0, 10), SequenceLength::Fixed(8));
+        let worst = (true, 100, String::from("zzzzzzzzzzzzzzzz"), vec![10; 8]);
+        let mut a = Accumulator::new();
+        schema.compress(&worst, &mut a).unwrap();
+        let estimate = schema.max_bytes().unwrap();
+        let bytes = a.to_bytes().len();
+        assert!(bytes <= estimate);
+        assert!(estimate - bytes <= (Digit::BITS / 8) as usize);
+        assert_eq!(Schema::new().max_bits(), Some(0.0));
+        let schema = Schema::new().field("flag", Bool::new()).field("count", VarUInt::new(16));
+        assert_eq!(schema.max_bits(), None);
+    }
+}
@@ -0,0 +1,1460 @@
+//! Text and string data definitions
+
+use core::result::Result;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::accum::{Accumulator, Digit};
+use crate::data::{digit_to_u32, fold_case, permutation_bits, CharSet, DataSpec, Enum, SequenceLength, Sequencer, WideDataSpec};
+use crate::error::Error;
+
+/// Number of Unicode scalar values, every code point except the surrogates
+const UNICODE_SCALARS: Digit = 0x110000 - 0x800;
+
+/// Character from a CharSet, or any other character behind an escape, values
+/// are compressed as multiple Digits when escaped
+///
+/// Characters in the set cost the same as the set with one extra character.
+/// That extra permutation marks an escaped character, which is followed by
+/// its Unicode scalar value.
+pub struct EscapingCharSet {
+    charset: CharSet,
+    escape: Digit,
+}
+
+impl EscapingCharSet {
+
+    pub fn new(charset: CharSet) -> Self {
+        Self::try_new(charset).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new EscapingCharSet, or an error if there is no permutation
+    /// left for the escape
+    pub fn try_new(charset: CharSet) -> Result<Self, Error> {
+        let escape = charset.permutations();
+        escape.checked_add(1).ok_or(Error::TooManyPermutations)?;
+        Ok(Self { charset, escape })
+    }
+}
+
+impl WideDataSpec<char> for EscapingCharSet {
+
+    fn encode_into(&self, input: &char, accum: &mut Accumulator) -> Result<(), Error> {
+        let value = match self.charset.encode(input) {
+            Ok(value) => value,
+            Err(Error::CharNotInSet(_)) => {
+                accum.checked_mul(UNICODE_SCALARS)?;
+                accum.add(scalar_to_index(*input));
+                self.escape
+            },
+            Err(error) => return Err(error),
+        };
+        accum.checked_mul(self.escape + 1)?;
+        accum.add(value);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<char, Error> {
+        let value = accum.checked_div(self.escape + 1)?;
+        if value == self.escape {
+            index_to_scalar(accum.checked_div(UNICODE_SCALARS)?)
+        } else {
+            self.charset.decode(value)
+        }
+    }
+}
+
+/// Any character, indexed by its code point with the surrogate range
+/// (U+D800 to U+DFFF) skipped so that every index is a valid character
+pub struct UnicodeScalar {}
+
+impl UnicodeScalar {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for UnicodeScalar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSpec<char> for UnicodeScalar {
+
+    fn permutations(&self) -> Digit {
+        UNICODE_SCALARS
+    }
+
+    fn encode(&self, input: &char) -> Result<Digit, Error> {
+        Ok(scalar_to_index(*input))
+    }
+
+    fn decode(&self, input: Digit) -> Result<char, Error> {
+        index_to_scalar(input)
+    }
+}
+
+/// Set of characters with a weight for each, characters with larger weights
+/// are cheaper to compress, values are compressed directly into the
+/// accumulator
+///
+/// A character with weight `w` out of a total weight `W` costs about
+/// `log2(W / w)` bits. Each character divides the accumulator by its weight
+/// and multiplies it by the total, keeping the remainder of the division and
+/// the start of the character's share of the total, so it is undone exactly.
+pub struct WeightedCharSet {
+    chars: Vec<char>,
+    weights: Vec<Digit>,
+    starts: Vec<Digit>,
+    lookup: BTreeMap<char, usize>,
+    total: Digit,
+}
+
+impl WeightedCharSet {
+
+    pub fn new(pairs: &[(char, u32)]) -> Self {
+        Self::try_new(pairs).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new WeightedCharSet, or an error if a character is repeated,
+    /// has a weight of zero or the total weight does not fit in a Digit
+    pub fn try_new(pairs: &[(char, u32)]) -> Result<Self, Error> {
+        let mut lookup = BTreeMap::new();
+        let mut starts = Vec::with_capacity(pairs.len());
+        let mut total: Digit = 0;
+        for (ii, (c, weight)) in pairs.iter().enumerate() {
+            if *weight == 0 {
+                return Err(Error::ZeroWeight(*c));
+            }
+            if let Some(first) = lookup.insert(*c, ii) {
+                return Err(Error::DuplicateChar { value: *c, index: ii, first });
+            }
+            starts.push(total);
+            total = total.checked_add(*weight as Digit).ok_or(Error::TooManyPermutations)?;
+        }
+        Ok(Self {
+            chars: pairs.iter().map(|(c, _)| *c).collect(),
+            weights: pairs.iter().map(|(_, weight)| *weight as Digit).collect(),
+            starts,
+            lookup,
+            total,
+        })
+    }
+}
+
+impl WideDataSpec<char> for WeightedCharSet {
+
+    fn encode_into(&self, input: &char, accum: &mut Accumulator) -> Result<(), Error> {
+        let index = *self.lookup.get(input).ok_or(Error::CharNotInSet(*input))?;
+        let remainder = accum.checked_div(self.weights[index])?;
+        accum.checked_mul(self.total)?;
+        accum.add(self.starts[index] + remainder);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<char, Error> {
+        if self.chars.is_empty() {
+            return Err(Error::DecodeOutOfBounds { value: 0, permutations: 0 });
+        }
+        let slot = accum.checked_div(self.total)?;
+        let index = self.starts.partition_point(|start| *start <= slot) - 1;
+        accum.checked_mul(self.weights[index])?;
+        accum.add(slot - self.starts[index]);
+        Ok(self.chars[index])
+    }
+
+    /// Bits of the rarest character, `log2(total / min_weight)`
+    fn max_bits(&self) -> Option<f64> {
+        let min_weight = self.weights.iter().min()?;
+        Some(permutation_bits(self.total as f64 / *min_weight as f64))
+    }
+}
+
+/// String of characters from a CharSet, compressed as a sequence of
+/// characters
+///
+/// Errors for characters that are not in the CharSet give the index of the
+/// character in the string (counted in characters, not bytes).
+pub struct StringSpec {
+    charset: CharSet,
+    length: SequenceLength,
+}
+
+impl StringSpec {
+
+    pub fn new(charset: CharSet, length: SequenceLength) -> Self {
+        Self { charset, length }
+    }
+
+    /// Compress a string into the accumulator, leaving it unchanged on error
+    pub fn compress(&self, input: &str, accum: &mut Accumulator) -> Result<(), Error> {
+        let chars: Vec<char> = input.chars().collect();
+        Sequencer::new(&self.charset, self.length).compress(&chars, accum)
+    }
+
+    /// Decompress a string from the accumulator, leaving it unchanged on error
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let chars = Sequencer::new(&self.charset, self.length).decompress(accum)?;
+        Ok(chars.into_iter().collect())
+    }
+}
+
+impl WideDataSpec<String> for StringSpec {
+
+    fn encode_into(&self, input: &String, accum: &mut Accumulator) -> Result<(), Error> {
+        self.compress(input, accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        self.decompress(accum)
+    }
+
+    fn max_bits(&self) -> Option<f64> {
+        Sequencer::new(&self.charset, self.length).max_bits()
+    }
+}
+
+/// String compressed in lowercase against a CharSet, with the positions of
+/// its capital letters compressed separately
+///
+/// Mostly lowercase text costs much less than with a CharSet of both cases,
+/// each capital letter costs about the bits needed for its position.
+/// Characters without a single character lowercase and uppercase form (e.g.
+/// digits or 'ß') are compressed as they are, so they must be in the CharSet.
+pub struct CaseMaskString {
+    charset: CharSet,
+    max_length: usize,
+}
+
+impl CaseMaskString {
+
+    pub fn new(charset: CharSet, max_length: usize) -> Self {
+        Self { charset, max_length }
+    }
+
+    /// Compress a string into the accumulator, leaving it unchanged on error
+    pub fn compress(&self, input: &str, accum: &mut Accumulator) -> Result<(), Error> {
+        let mut lower: Vec<char> = Vec::new();
+        let mut capitals: Vec<usize> = Vec::new();
+        for (ii, c) in input.chars().enumerate() {
+            let folded = fold_case(c);
+            if folded != c && upper_case(folded) == c {
+                capitals.push(ii);
+                lower.push(folded);
+            } else {
+                lower.push(c);
+            }
+        }
+        let snapshot = accum.clone();
+        let result = self.compress_parts(&lower, &capitals, accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    /// The capital positions go first so that the length of the string is
+    /// known when they are decompressed, each position is counted from the
+    /// one before it
+    fn compress_parts(&self, lower: &[char], capitals: &[usize], accum: &mut Accumulator) -> Result<(), Error> {
+        let count = lower.len();
+        if count <= self.max_length {
+            for (ii, position) in capitals.iter().enumerate().rev() {
+                let first = if ii == 0 { 0 } else { capitals[ii - 1] + 1 };
+                accum.checked_mul((count - first) as Digit)?;
+                accum.add((position - first) as Digit);
+            }
+            accum.checked_mul(count as Digit + 1)?;
+            accum.add(capitals.len() as Digit);
+        }
+        Sequencer::new(&self.charset, SequenceLength::Variable(self.max_length)).compress(lower, accum)
+    }
+
+    /// Decompress a string from the accumulator, leaving it unchanged on error
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let snapshot = accum.clone();
+        let result = self.decompress_parts(accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    fn decompress_parts(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let mut chars = Sequencer::new(&self.charset, SequenceLength::Variable(self.max_length)).decompress(accum)?;
+        let count = chars.len();
+        let capitals = accum.checked_div(count as Digit + 1)? as usize;
+        let mut first = 0;
+        for _ in 0..capitals {
+            if first >= count {
+                return Err(Error::DecodeOutOfRange { value: first.to_string(), max: (count - 1).to_string() });
+            }
+            let position = first + accum.checked_div((count - first) as Digit)? as usize;
+            chars[position] = upper_case(chars[position]);
+            first = position + 1;
+        }
+        Ok(chars.into_iter().collect())
+    }
+}
+
+/// Most likely characters to follow each character in English text, used by
+/// `BigramCharSet::english`
+const ENGLISH_BIGRAMS: &[(char, &str)] = &[
+    (' ', "tawsohib"), ('a', "ntlrsdc "), ('b', "eloauy"), ('c', "oehatkir"), ('d', " eiaosu"),
+    ('e', " rsnadlt"), ('f', " oeirfau"), ('g', " ehraiou"), ('h', "eaiot "), ('i', "ntscolmd"),
+    ('j', "uoea"), ('k', "ei sn"), ('l', "eliayo d"), ('m', "eaoipu "), ('n', " dgetosa"),
+    ('o', "nfru mtw"), ('p', "eroalhi"), ('q', "u"), ('r', " eoiasyt"), ('s', " teihsoa"),
+    ('t', "h eiorsa"), ('u', "rsntlmcp"), ('v', "eiao"), ('w', "aheion"), ('x', "pt ia"),
+    ('y', " oes"), ('z', "eaz"),
+];
+
+/// Character model where the characters that are likely to follow the
+/// previous character are cheaper to compress, used with a TextSequencer
+///
+/// Each character has a list of likely next characters, plus an escape to
+/// any character of the alphabet. The first character of a text is compressed
+/// as if it followed a space.
+pub struct BigramCharSet {
+    alphabet: CharSet,
+    followers: BTreeMap<char, CharSet>,
+}
+
+impl BigramCharSet {
+
+    pub fn new(alphabet: CharSet, transitions: &[(char, &str)]) -> Self {
+        Self::try_new(alphabet, transitions).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new BigramCharSet from the likely next characters of some of
+    /// the characters of the alphabet, most likely first, or an error if a
+    /// character is not in the alphabet or is repeated
+    pub fn try_new(alphabet: CharSet, transitions: &[(char, &str)]) -> Result<Self, Error> {
+        let mut followers = BTreeMap::new();
+        for (ii, (previous, next)) in transitions.iter().enumerate() {
+            alphabet.encode(previous)?;
+            let next = CharSet::try_new(next)?;
+            for index in 0..next.permutations() {
+                alphabet.encode(&next.decode(index)?)?;
+            }
+            if followers.insert(*previous, next).is_some() {
+                let first = transitions.iter().position(|(c, _)| c == previous).unwrap_or(ii);
+                return Err(Error::DuplicateChar { value: *previous, index: ii, first });
+            }
+        }
+        Ok(Self { alphabet, followers })
+    }
+
+    /// Create a new BigramCharSet over `CharSet::lowercase_ascii` with a
+    /// built in table of common English letter pairs
+    pub fn english() -> Self {
+        Self::new(CharSet::lowercase_ascii(), ENGLISH_BIGRAMS)
+    }
+
+    fn encode_into(&self, previous: char, input: char, accum: &mut Accumulator) -> Result<(), Error> {
+        let Some(followers) = self.followers.get(&previous) else {
+            return self.alphabet.encode_into(&input, accum);
+        };
+        let value = match followers.encode(&input) {
+            Ok(value) => value,
+            Err(_) => {
+                self.alphabet.encode_into(&input, accum)?;
+                followers.permutations()
+            },
+        };
+        accum.checked_mul(followers.permutations() + 1)?;
+        accum.add(value);
+        Ok(())
+    }
+
+    fn decode_from(&self, previous: char, accum: &mut Accumulator) -> Result<char, Error> {
+        let Some(followers) = self.followers.get(&previous) else {
+            return self.alphabet.decode_from(accum);
+        };
+        let value = accum.checked_div(followers.permutations() + 1)?;
+        if value == followers.permutations() {
+            self.alphabet.decode_from(accum)
+        } else {
+            followers.decode(value)
+        }
+    }
+}
+
+/// Sequencer for text compressed with a BigramCharSet, which depends on the
+/// previous character of the text
+pub struct TextSequencer<'a> {
+    model: &'a BigramCharSet,
+    length: SequenceLength,
+}
+
+impl<'a> TextSequencer<'a> {
+
+    pub fn new(model: &'a BigramCharSet, length: SequenceLength) -> Self {
+        Self { model, length }
+    }
+
+    /// Compress text into the accumulator. If the text can not be compressed
+    /// the accumulator is left unchanged and the error reports the index of
+    /// the offending character
+    pub fn compress(&self, input: &str, accum: &mut Accumulator) -> Result<(), Error> {
+        let chars: Vec<char> = input.chars().collect();
+        let count = chars.len();
+        match self.length {
+            SequenceLength::Fixed(length) if count != length => {
+                return Err(Error::LengthMismatch { expected: length, actual: count });
+            },
+            SequenceLength::Variable(max) | SequenceLength::Prefixed(max) if count > max => {
+                return Err(Error::SequenceTooLong { len: count, max });
+            },
+            _ => {},
+        }
+        let snapshot = accum.clone();
+        let result = self.compress_chars(&chars, accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    /// Characters are compressed last to first, so that they decompress in
+    /// order with the previous character known
+    fn compress_chars(&self, chars: &[char], accum: &mut Accumulator) -> Result<(), Error> {
+        for index in (0..chars.len()).rev() {
+            let previous = if index == 0 { ' ' } else { chars[index - 1] };
+            self.model.encode_into(previous, chars[index], accum)
+                .map_err(|error| Error::Element { index, error: Box::new(error) })?;
+        }
+        // The length is always stored once, so Variable and Prefixed are the
+        // same
+        if let SequenceLength::Variable(max) | SequenceLength::Prefixed(max) = self.length {
+            accum.checked_mul(max as Digit + 1)?;
+            accum.add(chars.len() as Digit);
+        }
+        Ok(())
+    }
+
+    /// Decompress text from the accumulator. If the text can not be
+    /// decompressed the accumulator is left unchanged and the error reports
+    /// the index of the offending character
+    pub fn decompress(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let snapshot = accum.clone();
+        let result = self.decompress_chars(accum);
+        if result.is_err() {
+            *accum = snapshot;
+        }
+        result
+    }
+
+    fn decompress_chars(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let count = match self.length {
+            SequenceLength::Fixed(length) => length,
+            SequenceLength::Variable(max) | SequenceLength::Prefixed(max) => {
+                let count = accum.checked_div(max as Digit + 1)? as usize;
+                if count > max {
+                    return Err(Error::SequenceTooLong { len: count, max });
+                }
+                count
+            },
+        };
+        let mut text = String::new();
+        let mut previous = ' ';
+        for index in 0..count {
+            if accum.is_guarded() && accum.is_exhausted() {
+                return Err(Error::Exhausted { index });
+            }
+            previous = self.model.decode_from(previous, accum)
+                .map_err(|error| Error::Element { index, error: Box::new(error) })?;
+            text.push(previous);
+        }
+        Ok(text)
+    }
+}
+
+/// Word from a list of words, for large vocabularies (e.g. a 2048 word list
+/// costs 11 bits per word)
+///
+/// A case insensitive spec accepts words in any case and decodes them in
+/// lowercase.
+pub struct DictionaryWordSpec {
+    words: Enum,
+    fold: bool,
+}
+
+impl DictionaryWordSpec {
+
+    pub fn new(words: &[&str]) -> Self {
+        Self::try_new(words).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DictionaryWordSpec, or an error if a word is repeated
+    pub fn try_new(words: &[&str]) -> Result<Self, Error> {
+        Ok(Self { words: Enum::try_new(words)?, fold: false })
+    }
+
+    pub fn new_case_insensitive(words: &[&str]) -> Self {
+        Self::try_new_case_insensitive(words).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new case insensitive DictionaryWordSpec, or an error if a
+    /// word is repeated once in lowercase
+    pub fn try_new_case_insensitive(words: &[&str]) -> Result<Self, Error> {
+        let lower: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+        Ok(Self { words: Enum::try_from_vec(lower)?, fold: true })
+    }
+
+    pub fn from_lines(lines: &str) -> Self {
+        Self::try_from_lines(lines).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DictionaryWordSpec from a word on each line, ignoring
+    /// surrounding whitespace and blank lines, or an error if a word is
+    /// repeated (the index of the error counts words, not lines)
+    pub fn try_from_lines(lines: &str) -> Result<Self, Error> {
+        let words: Vec<&str> = lines.lines().map(|line| line.trim()).filter(|line| !line.is_empty()).collect();
+        Self::try_new(&words)
+    }
+}
+
+impl DataSpec<String> for DictionaryWordSpec {
+
+    fn permutations(&self) -> Digit {
+        self.words.permutations()
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        if self.fold {
+            self.words.encode(&input.to_lowercase()).map_err(|_| Error::UnknownEnumVariant(input.clone()))
+        } else {
+            self.words.encode(input)
+        }
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        self.words.decode(input)
+    }
+}
+
+/// Word from a DictionaryWordSpec, or any other word spelled out with a
+/// StringSpec behind an escape, values are compressed as multiple Digits
+/// when spelled out
+pub struct DictionaryOrSpelled {
+    words: DictionaryWordSpec,
+    spelled: StringSpec,
+    escape: Digit,
+}
+
+impl DictionaryOrSpelled {
+
+    pub fn new(words: DictionaryWordSpec, spelled: StringSpec) -> Self {
+        Self::try_new(words, spelled).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DictionaryOrSpelled, or an error if there is no
+    /// permutation left for the escape
+    pub fn try_new(words: DictionaryWordSpec, spelled: StringSpec) -> Result<Self, Error> {
+        let escape = words.permutations();
+        escape.checked_add(1).ok_or(Error::TooManyPermutations)?;
+        Ok(Self { words, spelled, escape })
+    }
+}
+
+impl WideDataSpec<String> for DictionaryOrSpelled {
+
+    fn encode_into(&self, input: &String, accum: &mut Accumulator) -> Result<(), Error> {
+        let value = match self.words.encode(input) {
+            Ok(value) => value,
+            Err(Error::UnknownEnumVariant(_)) => {
+                self.spelled.compress(input, accum)?;
+                self.escape
+            },
+            Err(error) => return Err(error),
+        };
+        accum.checked_mul(self.escape + 1)?;
+        accum.add(value);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let value = accum.checked_div(self.escape + 1)?;
+        if value == self.escape {
+            self.spelled.decompress(accum)
+        } else {
+            self.words.decode(value)
+        }
+    }
+}
+
+/// Option of an Enum, or any other string spelled out with a CharSet behind
+/// an extra "other" option, values are compressed as multiple Digits when
+/// spelled out
+///
+/// Options cost the same as an Enum with one more option, other strings cost
+/// that plus a variable length sequence of characters.
+pub struct EnumWithFallback {
+    inner: DictionaryOrSpelled,
+}
+
+impl EnumWithFallback {
+
+    pub fn new(options: &[&str], fallback_charset: CharSet, max_fallback_len: usize) -> Self {
+        Self::try_new(options, fallback_charset, max_fallback_len).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new EnumWithFallback, or an error if an option is repeated
+    pub fn try_new(options: &[&str], fallback_charset: CharSet, max_fallback_len: usize) -> Result<Self, Error> {
+        let spelled = StringSpec::new(fallback_charset, SequenceLength::Variable(max_fallback_len));
+        Ok(Self { inner: DictionaryOrSpelled::try_new(DictionaryWordSpec::try_new(options)?, spelled)? })
+    }
+}
+
+impl WideDataSpec<String> for EnumWithFallback {
+
+    fn encode_into(&self, input: &String, accum: &mut Accumulator) -> Result<(), Error> {
+        self.inner.encode_into(input, accum)
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        self.inner.decode_from(accum)
+    }
+}
+
+/// Part of the pattern of a PatternStringSpec
+enum PatternPart {
+    Class(char, CharSet),
+    Literal(char),
+}
+
+/// String that follows a pattern of character classes and literal characters
+/// (e.g. "AA-9999"), literal characters are not compressed
+///
+/// In the pattern 'A' is an uppercase letter, 'a' a lowercase letter, '9' a
+/// digit and '*' a letter of either case or a digit. Any other letter or digit
+/// must be escaped with a backslash to be a literal, other characters are
+/// always literals.
+pub struct PatternStringSpec {
+    parts: Vec<PatternPart>,
+    permutations: Digit,
+}
+
+impl PatternStringSpec {
+
+    pub fn new(pattern: &str) -> Self {
+        Self::try_new(pattern).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new PatternStringSpec, or an error if the pattern has a letter
+    /// or digit that is not a class or has too many permutations
+    pub fn try_new(pattern: &str) -> Result<Self, Error> {
+        let mut parts = Vec::new();
+        let mut permutations: Digit = 1;
+        let mut chars = pattern.chars().enumerate();
+        while let Some((index, c)) = chars.next() {
+            let class = match c {
+                'A' => CharSet::uppercase_letter(),
+                'a' => CharSet::lowercase_letter(),
+                '9' => CharSet::digits(),
+                '*' => CharSet::alphanumeric(),
+                '\\' => match chars.next() {
+                    Some((_, literal)) => {
+                        parts.push(PatternPart::Literal(literal));
+                        continue;
+                    },
+                    None => return Err(Error::InvalidPattern { pattern: String::from(pattern), index }),
+                },
+                c if c.is_alphanumeric() => {
+                    return Err(Error::InvalidPattern { pattern: String::from(pattern), index });
+                },
+                c => {
+                    parts.push(PatternPart::Literal(c));
+                    continue;
+                },
+            };
+            permutations = permutations.checked_mul(class.permutations()).ok_or(Error::TooManyPermutations)?;
+            parts.push(PatternPart::Class(c, class));
+        }
+        Ok(Self { parts, permutations })
+    }
+}
+
+impl DataSpec<String> for PatternStringSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        let length = input.chars().count();
+        if length != self.parts.len() {
+            return Err(Error::InvalidLength { expected: self.parts.len(), actual: length });
+        }
+        let mut index: Digit = 0;
+        for (ii, (c, part)) in input.chars().zip(self.parts.iter()).enumerate() {
+            match part {
+                PatternPart::Class(class_char, class) => {
+                    let value = class.encode(&c).map_err(|_| Error::PatternMismatch {
+                        index: ii,
+                        value: c,
+                        pattern: *class_char,
+                    })?;
+                    index = index * class.permutations() + value;
+                },
+                PatternPart::Literal(literal) if *literal != c => {
+                    return Err(Error::PatternMismatch { index: ii, value: c, pattern: *literal });
+                },
+                PatternPart::Literal(_) => {},
+            }
+        }
+        Ok(index)
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let mut index = input;
+        let mut chars: Vec<char> = Vec::with_capacity(self.parts.len());
+        for part in self.parts.iter().rev() {
+            match part {
+                PatternPart::Class(_, class) => {
+                    chars.push(class.decode(index % class.permutations())?);
+                    index /= class.permutations();
+                },
+                PatternPart::Literal(literal) => chars.push(*literal),
+            }
+        }
+        Ok(chars.into_iter().rev().collect())
+    }
+}
+
+/// Hexadecimal digits that fit in a single Digit
+const HEX_CHUNK: usize = Digit::BITS as usize / 4;
+
+/// Fixed length string of hexadecimal digits (e.g. a short git hash),
+/// compressed as its value in as many Digits as needed
+///
+/// Digits of either case are accepted, values decode in lowercase with their
+/// leading zeros.
+pub struct HexStringSpec {
+    hex_chars: usize,
+}
+
+impl HexStringSpec {
+
+    pub fn new(hex_chars: usize) -> Self {
+        Self { hex_chars }
+    }
+
+    /// Number of hexadecimal digits of each Digit compressed, most
+    /// significant first
+    fn chunks(&self) -> impl DoubleEndedIterator<Item = usize> {
+        let full = self.hex_chars / HEX_CHUNK;
+        let partial = self.hex_chars % HEX_CHUNK;
+        (0..full).map(|_| HEX_CHUNK).chain((partial > 0).then_some(partial))
+    }
+}
+
+impl WideDataSpec<String> for HexStringSpec {
+
+    fn encode_into(&self, input: &String, accum: &mut Accumulator) -> Result<(), Error> {
+        let digits: Vec<Digit> = input.chars()
+            .map(|c| c.to_digit(16).map(|d| d as Digit).ok_or(Error::CharNotInSet(c)))
+            .collect::<Result<_, _>>()?;
+        if digits.len() != self.hex_chars {
+            return Err(Error::InvalidLength { expected: self.hex_chars, actual: digits.len() });
+        }
+        let mut start = 0;
+        for length in self.chunks() {
+            let value = digits[start..start + length].iter().fold(0, |value, digit| value << 4 | digit);
+            if length == HEX_CHUNK {
+                accum.checked_shl(Digit::BITS as usize)?;
+            } else {
+                accum.checked_mul(1 << (4 * length))?;
+            }
+            accum.add(value);
+            start += length;
+        }
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<String, Error> {
+        let mut chunks: Vec<String> = Vec::new();
+        for length in self.chunks().rev() {
+            let value = if length == HEX_CHUNK {
+                accum.checked_shr(Digit::BITS as usize)?
+            } else {
+                accum.checked_div(1 << (4 * length))?
+            };
+            chunks.push(format!("{:0width$x}", value, width = length));
+        }
+        Ok(chunks.into_iter().rev().collect())
+    }
+}
+
+/// Fixed length string of digits in the base of an alphabet (e.g. base 36),
+/// compressed as its value
+///
+/// The first character of the alphabet is the zero digit, so leading zeros
+/// are kept by the fixed length.
+pub struct RadixStringSpec {
+    alphabet: CharSet,
+    length: usize,
+    permutations: Digit,
+}
+
+impl RadixStringSpec {
+
+    pub fn new(alphabet: CharSet, length: usize) -> Self {
+        Self::try_new(alphabet, length).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new RadixStringSpec, or an error if the alphabet has fewer
+    /// than 2 characters or the strings have more permutations than fit in a
+    /// Digit
+    pub fn try_new(alphabet: CharSet, length: usize) -> Result<Self, Error> {
+        let base = alphabet.permutations();
+        if base < 2 {
+            return Err(Error::InvalidBase(base));
+        }
+        let permutations = u32::try_from(length).ok()
+            .and_then(|length| base.checked_pow(length))
+            .ok_or(Error::TooManyPermutations)?;
+        Ok(Self { alphabet, length, permutations })
+    }
+}
+
+impl DataSpec<String> for RadixStringSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        let length = input.chars().count();
+        if length != self.length {
+            return Err(Error::InvalidLength { expected: self.length, actual: length });
+        }
+        let base = self.alphabet.permutations();
+        input.chars().try_fold(0, |value, c| Ok(value * base + self.alphabet.encode(&c)?))
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let base = self.alphabet.permutations();
+        let mut value = input;
+        let mut chars: Vec<char> = Vec::with_capacity(self.length);
+        for _ in 0..self.length {
+            chars.push(self.alphabet.decode(value % base)?);
+            value /= base;
+        }
+        Ok(chars.into_iter().rev().collect())
+    }
+}
+
+/// Fixed length string of decimal digits ending in a Luhn check digit (e.g.
+/// a payment card number or an IMEI), the check digit is not compressed
+///
+/// Values with a wrong check digit are rejected, and the check digit is
+/// calculated again when decoding.
+pub struct LuhnDigitsSpec {
+    length: usize,
+    permutations: Digit,
+}
+
+impl LuhnDigitsSpec {
+
+    pub fn new(length: usize) -> Self {
+        Self::try_new(length).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new LuhnDigitsSpec for strings of a length that includes the
+    /// check digit, or an error if there are no digits or the digits before
+    /// the check digit do not fit in a Digit
+    pub fn try_new(length: usize) -> Result<Self, Error> {
+        let max = Digit::MAX.ilog10() as usize + 1;
+        if !(1..=max).contains(&length) {
+            return Err(Error::ValueOutOfRange { value: length.to_string(), min: 1.to_string(), max: max.to_string() });
+        }
+        Ok(Self { length, permutations: (10 as Digit).pow(length as u32 - 1) })
+    }
+}
+
+impl DataSpec<String> for LuhnDigitsSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &String) -> Result<Digit, Error> {
+        let digits: Vec<u8> = input.chars()
+            .map(|c| match c { '0'..='9' => Ok(c as u8 - b'0'), _ => Err(Error::CharNotInSet(c)) })
+            .collect::<Result<_, _>>()?;
+        if digits.len() != self.length {
+            return Err(Error::InvalidLength { expected: self.length, actual: digits.len() });
+        }
+        let (check, payload) = digits.split_last().unwrap_or((&0, &[]));
+        let expected = luhn_check_digit(payload);
+        if *check != expected {
+            return Err(Error::InvalidCheckDigit { expected, actual: *check });
+        }
+        Ok(payload.iter().fold(0, |value, digit| value * 10 + *digit as Digit))
+    }
+
+    fn decode(&self, input: Digit) -> Result<String, Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let mut value = input;
+        let mut digits: Vec<u8> = vec![0; self.length - 1];
+        for digit in digits.iter_mut().rev() {
+            *digit = (value % 10) as u8;
+            value /= 10;
+        }
+        let check = luhn_check_digit(&digits);
+        Ok(digits.iter().chain([check].iter()).map(|digit| (b'0' + digit) as char).collect())
+    }
+}
+
+// Utility functions
+
+/// Luhn check digit to append to decimal digits
+fn luhn_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter().rev().enumerate().map(|(ii, digit)| {
+        let digit = *digit as u32;
+        if ii % 2 == 0 { (digit * 2) / 10 + (digit * 2) % 10 } else { digit }
+    }).sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// Uppercase form of a character, characters that uppercase to more than one
+/// character are left as they are
+fn upper_case(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) => upper,
+        _ => c,
+    }
+}
+
+/// Index of a character among the Unicode scalar values, skipping the
+/// surrogates
+fn scalar_to_index(c: char) -> Digit {
+    let code = c as Digit;
+    if code < 0xD800 { code } else { code - 0x800 }
+}
+
+/// Character at an index among the Unicode scalar values, skipping the
+/// surrogates
+fn index_to_scalar(index: Digit) -> Result<char, Error> {
+    if index >= UNICODE_SCALARS {
+        return Err(Error::DecodeOutOfBounds { value: index, permutations: UNICODE_SCALARS });
+    }
+    let code = if index < 0xD800 { index } else { index + 0x800 };
+    digit_to_u32(code).and_then(char::from_u32).ok_or(Error::DecodeOutOfBounds { value: index, permutations: UNICODE_SCALARS })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::boxed::Box;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::accum::{Accumulator, Digit};
+    use crate::data::{BigramCharSet, DictionaryOrSpelled, DictionaryWordSpec, EnumWithFallback, PatternStringSpec};
+    use crate::data::{HexStringSpec, LuhnDigitsSpec, RadixStringSpec, TextSequencer, WeightedCharSet};
+    use crate::data::{CaseMaskString, CharSet, DataSpec, EscapingCharSet, SequenceLength, Sequencer, StringSpec};
+    use crate::data::{UnicodeScalar, WideDataSpec};
+    use crate::error::Error;
+
+    #[test]
+    fn escaping_charset() {
+        let spec = EscapingCharSet::new(CharSet::lowercase_ascii());
+        for c in ['a', ' ', '"', 'A', 'é', '\0', '\u{D7FF}', '\u{E000}', '😀', '\u{10FFFF}'] {
+            let mut a = Accumulator::new();
+            spec.encode_into(&c, &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(c));
+            assert!(a.is_empty());
+        }
+        // Characters in the set only cost one Digit
+        let mut a = Accumulator::new();
+        spec.encode_into(&'z', &mut a).unwrap();
+        assert_eq!(a.to_bytes(), [25]);
+    }
+
+    #[test]
+    fn seq_escaping_charset() {
+        let spec = EscapingCharSet::new(CharSet::lowercase_letter());
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(64));
+        for text in ["", "plain", "café 😀 crème brûlée", "ÀÉÎÕÜ", "a\u{10FFFF}b𝄞c"] {
+            let mut a = Accumulator::new();
+            let chars: Vec<char> = text.chars().collect();
+            sequencer.compress(&chars, &mut a).unwrap();
+            assert_eq!(sequencer.decompress(&mut a).unwrap(), chars);
+            assert!(a.is_empty());
+        }
+        // Text with no escapes costs log2(27) bits per character
+        let text: Vec<char> = "thequickbrownfoxjumpsoverthelazydogxyzzy".chars().collect();
+        let mut a = Accumulator::new();
+        Sequencer::new(&spec, SequenceLength::Fixed(40)).compress(&text, &mut a).unwrap();
+        let bits = libm::ceil(40.0 * libm::log2(27.0)) as usize;
+        assert!(a.to_bytes().len() <= bits.div_ceil(8));
+    }
+
+    #[test]
+    fn unicode_scalar() {
+        let spec = UnicodeScalar::new();
+        assert_eq!(spec.permutations(), 1_112_064);
+        let pinned = [('\0', 0), ('\u{D7FF}', 0xD7FF), ('\u{E000}', 0xD800), ('😀', 0x1F600 - 0x800), ('\u{10FFFF}', 1_112_063)];
+        for (c, index) in pinned {
+            assert_eq!(spec.encode(&c), Ok(index));
+            assert_eq!(spec.decode(index), Ok(c));
+        }
+        // Every index around the surrogate gap is a valid character
+        for index in 0xD000..0xE000 as Digit {
+            assert_eq!(spec.encode(&spec.decode(index).unwrap()), Ok(index));
+        }
+        assert_eq!(spec.decode(1_112_064), Err(Error::DecodeOutOfBounds { value: 1_112_064, permutations: 1_112_064 }));
+        assert!(spec.decode(Digit::MAX).is_err());
+        let mut a = Accumulator::new();
+        let chars: Vec<char> = "x😀\u{10FFFF}".chars().collect();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(4));
+        sequencer.compress(&chars, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), chars);
+    }
+
+    #[test]
+    fn string_spec() {
+        let spec = StringSpec::new(CharSet::lowercase_ascii(), SequenceLength::Variable(12));
+        for text in ["", "hello world!", "a", "(555) 0123"] {
+            let mut a = Accumulator::new();
+            spec.compress(text, &mut a).unwrap();
+            assert_eq!(spec.decompress(&mut a).unwrap(), text);
+            assert!(a.is_empty());
+        }
+        let mut a = Accumulator::new();
+        assert_eq!(spec.compress("hello world!!", &mut a), Err(Error::SequenceTooLong { len: 13, max: 12 }));
+        assert_eq!(spec.compress("hello World", &mut a), Err(Error::Element {
+            index: 6,
+            error: Box::new(Error::CharNotInSet('W')),
+        }));
+        assert!(a.is_empty());
+        // Multi-byte characters are counted as characters
+        let spec = StringSpec::new(CharSet::new("あいうえおかきくけこ"), SequenceLength::Fixed(4));
+        spec.compress("こかあお", &mut a).unwrap();
+        assert_eq!(spec.decompress(&mut a).unwrap(), "こかあお");
+        assert_eq!(spec.compress("あいう", &mut a), Err(Error::LengthMismatch { expected: 4, actual: 3 }));
+        assert!(matches!(spec.compress("あいxう", &mut a), Err(Error::Element { index: 2, .. })));
+    }
+
+    #[test]
+    fn case_mask_string() {
+        let spec = CaseMaskString::new(CharSet::lowercase_ascii(), 64);
+        for text in ["Hello World", "HELLO WORLD", "hello world", "", "A", "x", "NASA's 2 'Apollo' (1969)", "mY cAmEl cAsE!"] {
+            let mut a = Accumulator::new();
+            spec.compress(text, &mut a).unwrap();
+            assert_eq!(spec.decompress(&mut a).unwrap(), text);
+            assert!(a.is_empty());
+        }
+        // Characters with no case are unchanged, others must be in the set
+        let spec = CaseMaskString::new(CharSet::new("abcdeßéσ "), 16);
+        let mut a = Accumulator::new();
+        for text in ["ßÉ Σa", "ÉÉÉ"] {
+            spec.compress(text, &mut a).unwrap();
+            assert_eq!(spec.decompress(&mut a).unwrap(), text);
+        }
+        assert!(matches!(spec.compress("abcF", &mut a), Err(Error::Element { index: 3, .. })));
+        assert_eq!(CaseMaskString::new(CharSet::lowercase_letter(), 3).compress("Abcd", &mut a),
+            Err(Error::SequenceTooLong { len: 4, max: 3 }));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn case_mask_string_size() {
+        let spec = CaseMaskString::new(CharSet::new("abcdefghijklmnopqrstuvwxyz ,."), 128);
+        let mixed = StringSpec::new(
+            CharSet::new("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ ,."),
+            SequenceLength::Variable(128),
+        );
+        let mut masked_bytes = 0;
+        let mut mixed_bytes = 0;
+        for text in [
+            "The quick brown fox jumps over the lazy dog.",
+            "Meet me in Paris on Tuesday, said Alice.",
+            "It was the best of times, it was the worst of times.",
+        ] {
+            let mut a = Accumulator::new();
+            spec.compress(text, &mut a).unwrap();
+            masked_bytes += a.to_bytes().len();
+            assert_eq!(spec.decompress(&mut a).unwrap(), text);
+            let mut a = Accumulator::new();
+            mixed.compress(text, &mut a).unwrap();
+            mixed_bytes += a.to_bytes().len();
+        }
+        // 90 bytes instead of 99
+        assert!(masked_bytes * 100 < mixed_bytes * 95);
+    }
+
+    #[test]
+    fn bigram_charset() {
+        let model = BigramCharSet::english();
+        let sequencer = TextSequencer::new(&model, SequenceLength::Variable(64));
+        for text in ["", "q", "queen", "zzz 123 (?!)", "\"qwxz\" @ #9", "the end."] {
+            let mut a = Accumulator::new();
+            sequencer.compress(text, &mut a).unwrap();
+            assert_eq!(sequencer.decompress(&mut a).unwrap(), text);
+            assert!(a.is_empty());
+        }
+        // 'u' after 'q' costs a single bit
+        let model = BigramCharSet::new(CharSet::lowercase_letter(), &[('q', "u")]);
+        let sequencer = TextSequencer::new(&model, SequenceLength::Fixed(2));
+        let mut a = Accumulator::new();
+        sequencer.compress("qu", &mut a).unwrap();
+        assert_eq!(a.to_bytes(), [16]);
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), "qu");
+        assert!(a.is_empty());
+        assert_eq!(sequencer.compress("q", &mut a), Err(Error::LengthMismatch { expected: 2, actual: 1 }));
+        assert_eq!(sequencer.compress("qU", &mut a), Err(Error::Element {
+            index: 1,
+            error: Box::new(Error::CharNotInSet('U')),
+        }));
+        assert!(a.is_empty());
+        assert_eq!(BigramCharSet::try_new(CharSet::lowercase_letter(), &[('q', "u!")]).err(), Some(Error::CharNotInSet('!')));
+        assert_eq!(
+            BigramCharSet::try_new(CharSet::lowercase_letter(), &[('q', "u"), ('x', "e"), ('q', "a")]).err(),
+            Some(Error::DuplicateChar { value: 'q', index: 2, first: 0 })
+        );
+        assert!(BigramCharSet::try_new(CharSet::lowercase_letter(), &[('q', "uu")]).is_err());
+    }
+
+    #[test]
+    fn bigram_charset_size() {
+        let model = BigramCharSet::english();
+        let sequencer = TextSequencer::new(&model, SequenceLength::Variable(128));
+        let plain = StringSpec::new(CharSet::lowercase_ascii(), SequenceLength::Variable(128));
+        let mut bigram_bytes = 0;
+        let mut plain_bytes = 0;
+        for text in [
+            "the quick brown fox jumps over the lazy dog.",
+            "meet me at the station on tuesday at noon.",
+            "it was the best of times and it was the worst of times.",
+            "please send the report to the team when it is ready.",
+        ] {
+            let mut a = Accumulator::new();
+            sequencer.compress(text, &mut a).unwrap();
+            bigram_bytes += a.to_bytes().len();
+            assert_eq!(sequencer.decompress(&mut a).unwrap(), text);
+            let mut a = Accumulator::new();
+            plain.compress(text, &mut a).unwrap();
+            plain_bytes += a.to_bytes().len();
+        }
+        // 102 bytes instead of 138
+        assert!(bigram_bytes * 100 < plain_bytes * 80);
+    }
+
+    #[test]
+    fn weighted_charset() {
+        let spec = WeightedCharSet::new(&[('a', 1), ('b', 5), ('c', 2), ('d', 1)]);
+        for c in ['a', 'b', 'c', 'd'] {
+            let mut a = Accumulator::new();
+            spec.encode_into(&c, &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(c));
+            assert!(a.is_empty());
+        }
+        let mut a = Accumulator::from_bytes(&[0xAB, 0xCD, 0xEF]);
+        spec.encode_into(&'c', &mut a).unwrap();
+        assert_eq!(spec.decode_from(&mut a), Ok('c'));
+        assert_eq!(a.to_bytes(), Accumulator::from_bytes(&[0xAB, 0xCD, 0xEF]).to_bytes());
+        let mut a = Accumulator::new();
+        assert_eq!(spec.encode_into(&'e', &mut a), Err(Error::CharNotInSet('e')));
+        assert_eq!(WeightedCharSet::try_new(&[('a', 1), ('b', 0)]).err(), Some(Error::ZeroWeight('b')));
+        assert_eq!(
+            WeightedCharSet::try_new(&[('a', 1), ('b', 2), ('a', 3)]).err(),
+            Some(Error::DuplicateChar { value: 'a', index: 2, first: 0 })
+        );
+        assert!(WeightedCharSet::new(&[]).decode_from(&mut a).is_err());
+    }
+
+    #[test]
+    fn seq_weighted_charset() {
+        // English letter frequencies in tenths of a percent
+        let weights: &[(char, u32)] = &[
+            (' ', 180), ('e', 102), ('t', 75), ('a', 65), ('o', 62), ('i', 57), ('n', 57), ('s', 53),
+            ('h', 50), ('r', 50), ('d', 34), ('l', 33), ('c', 22), ('u', 22), ('m', 20), ('w', 19),
+            ('f', 18), ('g', 16), ('y', 16), ('p', 15), ('b', 12), ('v', 8), ('k', 6), ('j', 1),
+            ('x', 1), ('q', 1), ('z', 1),
+        ];
+        let weighted = WeightedCharSet::new(weights);
+        let uniform = CharSet::new(" etaoinshrdlcumwfgypbvkjxqz");
+        let text: Vec<char> = "these are the times that try the hearts of men and women".chars().collect();
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&weighted, SequenceLength::Fixed(text.len()));
+        sequencer.compress(&text, &mut a).unwrap();
+        let weighted_bytes = a.to_bytes().len();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), text);
+        assert!(a.is_empty());
+        let mut b = Accumulator::new();
+        Sequencer::new(&uniform, SequenceLength::Fixed(text.len())).compress(&text, &mut b).unwrap();
+        // 28 bytes instead of 33
+        assert!(weighted_bytes * 100 < b.to_bytes().len() * 90);
+        // Rare characters still round trip in variable length sequences
+        let text: Vec<char> = "jazz quiz".chars().collect();
+        let sequencer = Sequencer::new(&weighted, SequenceLength::Variable(16));
+        sequencer.compress(&text, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), text);
+        assert!(a.is_empty());
+        // The rarest characters are the worst case
+        let total: u32 = weights.iter().map(|(_, weight)| *weight).sum();
+        assert_eq!(weighted.max_bits(), Some(libm::log2(total as f64)));
+        assert_eq!(WeightedCharSet::new(&[]).max_bits(), None);
+        let rarest = vec!['z'; 16];
+        let sequencer = Sequencer::new(&weighted, SequenceLength::Fixed(rarest.len()));
+        sequencer.compress(&rarest, &mut a).unwrap();
+        assert!(a.to_bytes().len() <= sequencer.max_bytes().unwrap());
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), rarest);
+    }
+
+    #[test]
+    fn dictionary_word() {
+        // Same size as the BIP-39 word list
+        let words: Vec<String> = (0..2048).map(|ii| format!("word{}", ii)).collect();
+        let words: Vec<&str> = words.iter().map(|word| word.as_str()).collect();
+        let spec = DictionaryWordSpec::new(&words);
+        assert_eq!(spec.permutations(), 2048);
+        assert_eq!(spec.encode(&String::from("word0")), Ok(0));
+        assert_eq!(spec.encode(&String::from("word2047")), Ok(2047));
+        assert_eq!(spec.decode(1234), Ok(String::from("word1234")));
+        assert!(spec.decode(2048).is_err());
+        assert_eq!(spec.encode(&String::from("Word1")), Err(Error::UnknownEnumVariant(String::from("Word1"))));
+        let mut a = Accumulator::new();
+        spec.encode_into(&String::from("word2047"), &mut a).unwrap();
+        assert_eq!(a.to_bytes(), [0xFF, 0x07]);
+        // Case insensitive words decode in lowercase
+        let spec = DictionaryWordSpec::new_case_insensitive(&["Online", "OFFLINE", "degraded"]);
+        assert_eq!(spec.encode(&String::from("ONLINE")), Ok(0));
+        assert_eq!(spec.encode(&String::from("Degraded")), Ok(2));
+        assert_eq!(spec.decode(1), Ok(String::from("offline")));
+        assert_eq!(spec.encode(&String::from("Down")), Err(Error::UnknownEnumVariant(String::from("Down"))));
+        assert_eq!(
+            DictionaryWordSpec::try_new_case_insensitive(&["online", "offline", "Online"]).err(),
+            Some(Error::DuplicateOption { value: String::from("online"), index: 2, first: 0 })
+        );
+        assert!(DictionaryWordSpec::try_new(&["online", "offline", "Online"]).is_ok());
+        // Word lists from text
+        let spec = DictionaryWordSpec::from_lines("abandon\nability\r\n  able \n\nabout\n");
+        assert_eq!(spec.permutations(), 4);
+        assert_eq!(spec.encode(&String::from("able")), Ok(2));
+        assert_eq!(
+            DictionaryWordSpec::try_from_lines("abandon\n\nability\nabandon").err(),
+            Some(Error::DuplicateOption { value: String::from("abandon"), index: 2, first: 0 })
+        );
+    }
+
+    #[test]
+    fn dictionary_or_spelled() {
+        let spec = DictionaryOrSpelled::new(
+            DictionaryWordSpec::new(&["ok", "warning", "error", "offline"]),
+            StringSpec::new(CharSet::lowercase_letter(), SequenceLength::Variable(16)),
+        );
+        for word in ["ok", "offline", "rebooting", "", "warnings"] {
+            let mut a = Accumulator::new();
+            spec.encode_into(&String::from(word), &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(String::from(word)));
+            assert!(a.is_empty());
+        }
+        let mut a = Accumulator::new();
+        spec.encode_into(&String::from("error"), &mut a).unwrap();
+        assert_eq!(a.to_bytes(), [2]);
+        assert!(spec.encode_into(&String::from("Rebooting"), &mut a).is_err());
+        let words: Vec<String> = ["ok", "ok", "stuck", "error"].iter().map(|word| String::from(*word)).collect();
+        let mut a = Accumulator::new();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(8));
+        sequencer.compress(&words, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), words);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn enum_with_fallback() {
+        let spec = EnumWithFallback::new(&["red", "green", "blue"], CharSet::lowercase_letter(), 8);
+        for value in ["red", "blue", "magenta", "", "reddish", "abcdefgh"] {
+            let mut a = Accumulator::new();
+            spec.encode_into(&String::from(value), &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(String::from(value)));
+            assert!(a.is_empty());
+        }
+        // Options cost log2(4) bits
+        let mut a = Accumulator::new();
+        spec.encode_into(&String::from("blue"), &mut a).unwrap();
+        assert_eq!(a.to_bytes(), [2]);
+        let mut a = Accumulator::new();
+        assert_eq!(spec.encode_into(&String::from("abcdefghi"), &mut a), Err(Error::SequenceTooLong { len: 9, max: 8 }));
+        assert_eq!(spec.encode_into(&String::from("Red"), &mut a), Err(Error::Element {
+            index: 0,
+            error: Box::new(Error::CharNotInSet('R')),
+        }));
+        assert!(a.is_empty());
+        assert!(matches!(EnumWithFallback::try_new(&["a", "a"], CharSet::digits(), 4), Err(Error::DuplicateOption { .. })));
+        let values: Vec<String> = ["green", "teal", "green"].iter().map(|v| String::from(*v)).collect();
+        let sequencer = Sequencer::new(&spec, SequenceLength::Fixed(3));
+        sequencer.compress(&values, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), values);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn pattern_string() {
+        let spec = PatternStringSpec::new("AA-9999");
+        assert_eq!(spec.permutations(), 26 * 26 * 10_000);
+        // Literals cost nothing
+        assert_eq!(spec.permutations(), PatternStringSpec::new("AA9999").permutations());
+        assert_eq!(spec.encode(&String::from("AA-0000")), Ok(0));
+        assert_eq!(spec.encode(&String::from("AB-0001")), Ok(10_001));
+        assert_eq!(spec.decode(26 * 26 * 10_000 - 1), Ok(String::from("ZZ-9999")));
+        for (pattern, value) in [
+            ("AA-9999", "XK-0420"),
+            ("\\S\\N-99999", "SN-00042"),
+            ("aaa 999", "abc 123"),
+            ("**-**", "a1-B2"),
+            ("A9A 9A9", "K1A 0B1"),
+            ("(99) 999-999", "(55) 010-447"),
+        ] {
+            let spec = PatternStringSpec::new(pattern);
+            let encoded = spec.encode(&String::from(value)).unwrap();
+            assert_eq!(spec.decode(encoded), Ok(String::from(value)));
+        }
+        // Errors give the position that does not match
+        assert_eq!(spec.encode(&String::from("Aa-1234")), Err(Error::PatternMismatch { index: 1, value: 'a', pattern: 'A' }));
+        assert_eq!(spec.encode(&String::from("AB 1234")), Err(Error::PatternMismatch { index: 2, value: ' ', pattern: '-' }));
+        assert_eq!(spec.encode(&String::from("AB-12345")), Err(Error::InvalidLength { expected: 7, actual: 8 }));
+        assert!(spec.decode(26 * 26 * 10_000).is_err());
+        assert_eq!(
+            PatternStringSpec::try_new("AB-99").err(),
+            Some(Error::InvalidPattern { pattern: String::from("AB-99"), index: 1 })
+        );
+        assert!(PatternStringSpec::try_new("AA\\").is_err());
+        assert_eq!(PatternStringSpec::try_new(&"*".repeat(20)).err(), Some(Error::TooManyPermutations));
+        let spec = PatternStringSpec::new("--");
+        assert_eq!(spec.permutations(), 1);
+        assert_eq!(spec.decode(0), Ok(String::from("--")));
+    }
+
+    #[test]
+    fn hex_string() {
+        for (hex_chars, values) in [
+            (7, &["0000000", "00c0ffe", "fffffff", "a1b2c3d"][..]),
+            (16, &["0000000000000000", "0123456789abcdef", "ffffffffffffffff"][..]),
+            (17, &["00000000000000000", "10000000000000000", "fffffffffffffffff"][..]),
+            (40, &["da39a3ee5e6b4b0d3255bfef95601890afd80709", "0000000000000000000000000000000000000001"][..]),
+            (0, &[""][..]),
+        ] {
+            let spec = HexStringSpec::new(hex_chars);
+            for value in values {
+                let mut a = Accumulator::new();
+                spec.encode_into(&String::from(*value), &mut a).unwrap();
+                assert_eq!(spec.decode_from(&mut a), Ok(String::from(*value)));
+                assert!(a.is_empty());
+            }
+        }
+        // Costs exactly 4 bits per digit
+        let spec = HexStringSpec::new(16);
+        let mut a = Accumulator::new();
+        spec.encode_into(&String::from("ffffffffffffffff"), &mut a).unwrap();
+        assert_eq!(a.to_bytes().len(), 8);
+        let spec = HexStringSpec::new(7);
+        let mut a = Accumulator::new();
+        spec.encode_into(&String::from("00C0FFE"), &mut a).unwrap();
+        assert_eq!(a.to_bytes(), [0xfe, 0x0f, 0x0c]);
+        assert_eq!(spec.decode_from(&mut a), Ok(String::from("00c0ffe")));
+        assert_eq!(spec.encode_into(&String::from("00c0ff"), &mut a), Err(Error::InvalidLength { expected: 7, actual: 6 }));
+        assert_eq!(spec.encode_into(&String::from("00c0ffee"), &mut a), Err(Error::InvalidLength { expected: 7, actual: 8 }));
+        assert_eq!(spec.encode_into(&String::from("00c0ffg"), &mut a), Err(Error::CharNotInSet('g')));
+        assert_eq!(spec.encode_into(&String::from("0x0c0ff"), &mut a), Err(Error::CharNotInSet('x')));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn radix_string() {
+        let base36 = || CharSet::new("0123456789abcdefghijklmnopqrstuvwxyz");
+        let spec = RadixStringSpec::new(base36(), 6);
+        assert_eq!(spec.permutations(), 2_176_782_336);
+        assert_eq!(spec.encode(&String::from("000000")), Ok(0));
+        assert_eq!(spec.encode(&String::from("00000z")), Ok(35));
+        assert_eq!(spec.encode(&String::from("000010")), Ok(36));
+        assert_eq!(spec.decode(35), Ok(String::from("00000z")));
+        assert_eq!(spec.decode(2_176_782_335), Ok(String::from("zzzzzz")));
+        for value in ["order1", "0000ab", "zz0000", "a0b1c2"] {
+            assert_eq!(spec.decode(spec.encode(&String::from(value)).unwrap()), Ok(String::from(value)));
+        }
+        assert_eq!(spec.encode(&String::from("order")), Err(Error::InvalidLength { expected: 6, actual: 5 }));
+        assert_eq!(spec.encode(&String::from("Order1")), Err(Error::CharNotInSet('O')));
+        assert!(spec.decode(2_176_782_336).is_err());
+        // Crockford base 32, the alphabet of ULIDs
+        let spec = RadixStringSpec::new(CharSet::new("0123456789ABCDEFGHJKMNPQRSTVWXYZ"), 6);
+        assert_eq!(spec.permutations(), 1 << 30);
+        assert_eq!(spec.decode(spec.encode(&String::from("01ARZ3")).unwrap()), Ok(String::from("01ARZ3")));
+        assert_eq!(RadixStringSpec::new(base36(), 0).decode(0), Ok(String::new()));
+        #[cfg(not(feature = "digit32"))]
+        assert_eq!(RadixStringSpec::new(base36(), 12).permutations(), 4_738_381_338_321_616_896);
+        assert_eq!(RadixStringSpec::try_new(base36(), 13).err(), Some(Error::TooManyPermutations));
+        assert_eq!(RadixStringSpec::try_new(base36(), usize::MAX).err(), Some(Error::TooManyPermutations));
+        assert_eq!(RadixStringSpec::try_new(CharSet::new("0"), 4).err(), Some(Error::InvalidBase(1)));
+    }
+
+    #[test]
+    fn luhn_digits() {
+        let spec = LuhnDigitsSpec::new(10);
+        assert_eq!(spec.permutations(), 1_000_000_000);
+        for number in ["7992739875", "1234567897", "0000000000", "9999999999"] {
+            let encoded = spec.encode(&String::from(number)).unwrap();
+            assert_eq!(spec.decode(encoded), Ok(String::from(number)));
+        }
+        // Only the digits before the check digit are compressed
+        assert_eq!(spec.encode(&String::from("1234567897")), Ok(123_456_789));
+        assert_eq!(spec.decode(123_456_790), Ok(String::from("1234567905")));
+        assert_eq!(spec.encode(&String::from("1234567890")), Err(Error::InvalidCheckDigit { expected: 7, actual: 0 }));
+        assert_eq!(spec.encode(&String::from("123456789")), Err(Error::InvalidLength { expected: 10, actual: 9 }));
+        assert!(spec.decode(1_000_000_000).is_err());
+        let spec = LuhnDigitsSpec::new(1);
+        assert_eq!(spec.permutations(), 1);
+        assert_eq!(spec.decode(0), Ok(String::from("0")));
+        assert!(spec.encode(&String::from("5")).is_err());
+        assert!(LuhnDigitsSpec::try_new(0).is_err());
+        let max = Digit::MAX.ilog10() as usize + 1;
+        assert!(LuhnDigitsSpec::try_new(max).is_ok());
+        assert!(matches!(LuhnDigitsSpec::try_new(max + 1), Err(Error::ValueOutOfRange { .. })));
+    }
+
+    #[test]
+    #[cfg(not(feature = "digit32"))]
+    fn luhn_digits_long() {
+        let spec = LuhnDigitsSpec::new(16);
+        assert_eq!(spec.permutations(), 1_000_000_000_000_000);
+        for number in ["4111111111111111", "5555555555554444", "4012888888881881", "0000000000000000"] {
+            let encoded = spec.encode(&String::from(number)).unwrap();
+            assert_eq!(spec.decode(encoded), Ok(String::from(number)));
+        }
+        // Only the digits before the check digit are compressed
+        assert_eq!(spec.encode(&String::from("4111111111111111")), Ok(411_111_111_111_111));
+        assert_eq!(spec.decode(411_111_111_111_111), Ok(String::from("4111111111111111")));
+        assert_eq!(spec.decode(411_111_111_111_112), Ok(String::from("4111111111111129")));
+        assert_eq!(spec.encode(&String::from("4111111111111112")), Err(Error::InvalidCheckDigit { expected: 1, actual: 2 }));
+        assert_eq!(spec.encode(&String::from("4111111111111121")), Err(Error::InvalidCheckDigit { expected: 9, actual: 1 }));
+        assert_eq!(spec.encode(&String::from("411111111111111")), Err(Error::InvalidLength { expected: 16, actual: 15 }));
+        assert_eq!(spec.encode(&String::from("4111 1111 1111 1111")), Err(Error::CharNotInSet(' ')));
+        assert!(spec.decode(1_000_000_000_000_000).is_err());
+        // IMEI
+        let spec = LuhnDigitsSpec::new(15);
+        assert_eq!(spec.decode(spec.encode(&String::from("490154203237518")).unwrap()), Ok(String::from("490154203237518")));
+        assert_eq!(LuhnDigitsSpec::new(20).permutations(), 10_000_000_000_000_000_000);
+        assert!(matches!(LuhnDigitsSpec::try_new(21), Err(Error::ValueOutOfRange { .. })));
+    }
+}
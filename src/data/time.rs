@@ -0,0 +1,1265 @@
+//! Date and time data definitions
+
+use core::result::Result;
+use core::time::Duration;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::accum::{Accumulator, Digit};
+use crate::data::{DataSpec, SequenceLength, Sequencer, UIntRange, WideDataSpec};
+use crate::error::Error;
+
+/// Calendar date as a (year, month, day) tuple in the proleptic Gregorian
+/// calendar, with months and days starting at 1
+///
+/// Only valid dates in the years from min to max (inclusive) are counted, so
+/// no permutations are spent on dates like February 30th. Dates encode to the
+/// number of days since January 1st of the min year.
+pub struct DateSpec {
+    min: i32,
+    max: i32,
+    /// Day number of January 1st of the min year
+    first: i64,
+    permutations: Digit,
+}
+
+impl DateSpec {
+
+    pub fn new(min: i32, max: i32) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DateSpec for the years from min to max (inclusive), or an
+    /// error if the range is not valid
+    pub fn try_new(min: i32, max: i32) -> Result<Self, Error> {
+        if min > max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        let first = days_from_civil(min as i64, 1, 1);
+        let permutations = Digit::try_from(days_from_civil(max as i64 + 1, 1, 1) - first)
+            .map_err(|_| Error::TooManyPermutations)?;
+        Ok(Self { min, max, first, permutations })
+    }
+}
+
+impl DataSpec<(i32, u8, u8)> for DateSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &(i32, u8, u8)) -> Result<Digit, Error> {
+        let (year, month, day) = *input;
+        if year < self.min || year > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: year.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year as i64, month) {
+            return Err(Error::InvalidDate { year, month, day });
+        }
+        Ok((days_from_civil(year as i64, month, day) - self.first) as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(i32, u8, u8), Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let (year, month, day) = civil_from_days(self.first + input as i64);
+        Ok((year as i32, month, day))
+    }
+}
+
+/// Number of seconds in a day, not including leap seconds
+const SECONDS_PER_DAY: Digit = 86400;
+
+/// Time of day as an (hour, minute, second) tuple on a 24 hour clock
+///
+/// Times encode to the number of steps (minutes or seconds) since midnight.
+/// With `with_leap_second` one more permutation is added for 23:59:60, after
+/// every other time of the day.
+pub struct TimeSpec {
+    /// Number of seconds in a step
+    step: Digit,
+    leap_second: bool,
+}
+
+impl TimeSpec {
+
+    /// Create a new TimeSpec with minute resolution (1440 permutations), 
+    /// seconds must always be 0
+    pub fn minutes() -> Self {
+        Self { step: 60, leap_second: false }
+    }
+
+    /// Create a new TimeSpec with second resolution (86400 permutations)
+    pub fn seconds() -> Self {
+        Self { step: 1, leap_second: false }
+    }
+
+    /// Allow the leap second 23:59:60
+    pub fn with_leap_second(mut self) -> Self {
+        self.leap_second = true;
+        self
+    }
+
+    /// Number of steps in a day, not including the leap second
+    fn steps(&self) -> Digit {
+        SECONDS_PER_DAY / self.step
+    }
+}
+
+impl DataSpec<(u8, u8, u8)> for TimeSpec {
+
+    fn permutations(&self) -> Digit {
+        self.steps() + self.leap_second as Digit
+    }
+
+    fn encode(&self, input: &(u8, u8, u8)) -> Result<Digit, Error> {
+        let (hour, minute, second) = *input;
+        if self.leap_second && *input == (23, 59, 60) {
+            return Ok(self.steps());
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(Error::InvalidTime { hour, minute, second });
+        }
+        let seconds = hour as Digit * 3600 + minute as Digit * 60 + second as Digit;
+        if !seconds.is_multiple_of(self.step) {
+            return Err(Error::ValueNotOnStep {
+                value: seconds.to_string(),
+                min: 0.to_string(),
+                step: self.step.to_string(),
+            });
+        }
+        Ok(seconds / self.step)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(u8, u8, u8), Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        if input == self.steps() {
+            return Ok((23, 59, 60));
+        }
+        let seconds = input * self.step;
+        Ok(((seconds / 3600) as u8, (seconds / 60 % 60) as u8, (seconds % 60) as u8))
+    }
+}
+
+/// Resolution of a timestamp
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl Resolution {
+
+    /// Number of seconds in one step of the resolution
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Resolution::Seconds => 1,
+            Resolution::Minutes => 60,
+            Resolution::Hours => 3600,
+            Resolution::Days => 86400,
+        }
+    }
+}
+
+/// Timestamp in unix seconds (ignoring leap seconds) from min to max 
+/// (inclusive) at a resolution
+///
+/// Steps are counted from min, so min should be aligned to the resolution 
+/// (e.g. midnight for `Resolution::Days`) for steps to fall on whole minutes,
+/// hours or days. Values not on a step are rejected unless truncation is 
+/// enabled with `with_truncation`, which moves them back to the previous step.
+pub struct DateTimeSpec {
+    min: i64,
+    max: i64,
+    resolution: Resolution,
+    truncate: bool,
+}
+
+impl DateTimeSpec {
+
+    pub fn new(min: i64, max: i64, resolution: Resolution) -> Self {
+        Self::try_new(min, max, resolution).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DateTimeSpec, or an error if the range is not valid or 
+    /// has too many steps
+    pub fn try_new(min: i64, max: i64, resolution: Resolution) -> Result<Self, Error> {
+        if min >= max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        let steps = (max as i128 - min as i128) / resolution.seconds() as i128;
+        if Digit::try_from(steps + 1).is_err() {
+            return Err(Error::TooManyPermutations);
+        }
+        let max = (min as i128 + steps * resolution.seconds() as i128) as i64;
+        Ok(Self { min, max, resolution, truncate: false })
+    }
+
+    /// Truncate values to the previous step instead of returning an error for
+    /// values that are not on a step
+    pub fn with_truncation(mut self) -> Self {
+        self.truncate = true;
+        self
+    }
+}
+
+impl DataSpec<i64> for DateTimeSpec {
+
+    fn permutations(&self) -> Digit {
+        ((self.max as i128 - self.min as i128) / self.resolution.seconds() as i128) as Digit + 1
+    }
+
+    fn encode(&self, input: &i64) -> Result<Digit, Error> {
+        if *input < self.min || *input > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        let offset = *input as i128 - self.min as i128;
+        let step = self.resolution.seconds() as i128;
+        if !self.truncate && offset % step != 0 {
+            return Err(Error::ValueNotOnStep {
+                value: input.to_string(),
+                min: self.min.to_string(),
+                step: step.to_string(),
+            });
+        }
+        Ok((offset / step) as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<i64, Error> {
+        if input >= self.permutations() {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok((self.min as i128 + input as i128 * self.resolution.seconds() as i128) as i64)
+    }
+}
+
+/// Series of non-decreasing timestamps in unix seconds (e.g. the times of a
+/// burst of events), compressed as the first timestamp followed by the 
+/// difference from each timestamp to the one before it
+///
+/// Every timestamp must be in the range and on a step of the resolution, as 
+/// for a `DateTimeSpec`, and differences may be at most `max_delta` seconds. 
+/// Differences are compressed like a variable length `Sequencer` of 
+/// `UIntRange` values, so a corrupted difference is detected when decoding 
+/// in the same way.
+pub struct MonotonicDeltaSpec {
+    timestamps: DateTimeSpec,
+    resolution: Resolution,
+    /// Differences between timestamps in steps of the resolution
+    deltas: UIntRange,
+    max_length: usize,
+}
+
+impl MonotonicDeltaSpec {
+
+    pub fn new(min: i64, max: i64, resolution: Resolution, max_delta: i64, max_length: usize) -> Self {
+        Self::try_new(min, max, resolution, max_delta, max_length).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new MonotonicDeltaSpec for series of up to max_length 
+    /// timestamps, or an error if the range is not valid or max_delta is less
+    /// than one step of the resolution
+    pub fn try_new(min: i64, max: i64, resolution: Resolution, max_delta: i64, max_length: usize) -> Result<Self, Error> {
+        let timestamps = DateTimeSpec::try_new(min, max, resolution)?;
+        let max_steps = Digit::try_from(max_delta / resolution.seconds()).map_err(|_| Error::InvalidRange {
+            min: 0.to_string(),
+            max: max_delta.to_string(),
+        })?;
+        let deltas = UIntRange::try_new(0, max_steps.min(timestamps.permutations() - 1))?;
+        Ok(Self { timestamps, resolution, deltas, max_length })
+    }
+
+    /// Largest difference between two timestamps in seconds
+    pub fn max_delta(&self) -> i64 {
+        (self.deltas.permutations() - 1) as i64 * self.resolution.seconds()
+    }
+
+    fn sequencer(&self) -> Sequencer<'_, Digit> {
+        Sequencer::new(&self.deltas, SequenceLength::Variable(self.max_length.saturating_sub(1)))
+    }
+}
+
+impl WideDataSpec<Vec<i64>> for MonotonicDeltaSpec {
+
+    fn encode_into(&self, input: &Vec<i64>, accum: &mut Accumulator) -> Result<(), Error> {
+        if input.len() > self.max_length {
+            return Err(Error::SequenceTooLong { len: input.len(), max: self.max_length });
+        }
+        let indexes = input.iter()
+            .enumerate()
+            .map(|(index, value)| {
+                self.timestamps.encode(value).map_err(|error| Error::Element { index, error: error.into() })
+            })
+            .collect::<Result<Vec<Digit>, Error>>()?;
+        let mut deltas = Vec::with_capacity(indexes.len().saturating_sub(1));
+        for (index, pair) in indexes.windows(2).enumerate() {
+            let delta = pair[1].checked_sub(pair[0]).filter(|delta| *delta < self.deltas.permutations());
+            match delta {
+                Some(delta) => deltas.push(delta),
+                None => return Err(Error::Element {
+                    index: index + 1,
+                    error: Error::ValueOutOfRange {
+                        value: (input[index + 1] as i128 - input[index] as i128).to_string(),
+                        min: 0.to_string(),
+                        max: self.max_delta().to_string(),
+                    }.into(),
+                }),
+            }
+        }
+        if let Some(first) = indexes.first() {
+            self.sequencer().compress(&deltas, accum)?;
+            accum.checked_mul(self.timestamps.permutations())?;
+            accum.add(*first);
+        }
+        accum.checked_mul(2)?;
+        accum.add(!input.is_empty() as Digit);
+        Ok(())
+    }
+
+    fn decode_from(&self, accum: &mut Accumulator) -> Result<Vec<i64>, Error> {
+        if accum.checked_div(2)? == 0 {
+            return Ok(Vec::new());
+        }
+        let mut index = accum.checked_div(self.timestamps.permutations())?;
+        let mut values = Vec::new();
+        values.push(self.timestamps.decode(index)?);
+        for delta in self.sequencer().decompress(accum)? {
+            let decoded = index.checked_add(delta)
+                .ok_or(Error::DecodeOutOfBounds { value: delta, permutations: self.timestamps.permutations() - index })
+                .and_then(|next| {
+                    index = next;
+                    self.timestamps.decode(index)
+                });
+            values.push(decoded.map_err(|error| Error::DecodeElement {
+                index: values.len(),
+                value: delta,
+                error: error.into(),
+            })?);
+        }
+        Ok(values)
+    }
+}
+
+/// Duration from zero to a max (inclusive) in whole ticks (e.g. seconds)
+///
+/// Durations finer than a tick are rejected unless truncation is enabled with
+/// `with_truncation`, which rounds them down to a whole tick.
+pub struct DurationSpec {
+    /// Length of a tick in nanoseconds
+    tick: u128,
+    /// Number of ticks in the max duration
+    ticks: Digit,
+    truncate: bool,
+}
+
+impl DurationSpec {
+
+    pub fn new(max: Duration, tick: Duration) -> Self {
+        Self::try_new(max, tick).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new DurationSpec with a custom tick, or an error if the tick 
+    /// is zero or there are too many ticks up to the max
+    ///
+    /// The max is moved back to the last whole tick
+    pub fn try_new(max: Duration, tick: Duration) -> Result<Self, Error> {
+        if tick.is_zero() {
+            return Err(Error::ZeroStep);
+        }
+        let tick = tick.as_nanos();
+        let ticks = max.as_nanos() / tick;
+        if Digit::try_from(ticks).ok().and_then(|t| t.checked_add(1)).is_none() {
+            return Err(Error::TooManyPermutations);
+        }
+        Ok(Self { tick, ticks: ticks as Digit, truncate: false })
+    }
+
+    /// Create a new DurationSpec for whole seconds
+    pub fn seconds(max: Duration) -> Self {
+        Self::new(max, Duration::from_secs(1))
+    }
+
+    /// Create a new DurationSpec for whole milliseconds
+    pub fn millis(max: Duration) -> Self {
+        Self::new(max, Duration::from_millis(1))
+    }
+
+    /// Truncate durations to a whole tick instead of returning an error for 
+    /// durations finer than a tick
+    pub fn with_truncation(mut self) -> Self {
+        self.truncate = true;
+        self
+    }
+
+    /// Max duration that can be encoded
+    pub fn max(&self) -> Duration {
+        nanos_to_duration(self.ticks as u128 * self.tick)
+    }
+}
+
+impl DataSpec<Duration> for DurationSpec {
+
+    fn permutations(&self) -> Digit {
+        self.ticks + 1
+    }
+
+    fn encode(&self, input: &Duration) -> Result<Digit, Error> {
+        let nanos = input.as_nanos();
+        if nanos > self.ticks as u128 * self.tick {
+            return Err(Error::ValueOutOfRange {
+                value: format!("{:?}", input),
+                min: format!("{:?}", Duration::ZERO),
+                max: format!("{:?}", self.max()),
+            });
+        }
+        if !self.truncate && !nanos.is_multiple_of(self.tick) {
+            return Err(Error::ValueNotOnStep {
+                value: format!("{:?}", input),
+                min: format!("{:?}", Duration::ZERO),
+                step: format!("{:?}", nanos_to_duration(self.tick)),
+            });
+        }
+        Ok((nanos / self.tick) as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<Duration, Error> {
+        if input > self.ticks {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations() });
+        }
+        Ok(nanos_to_duration(input as u128 * self.tick))
+    }
+}
+
+/// Convert a number of nanoseconds to a Duration
+fn nanos_to_duration(nanos: u128) -> Duration {
+    Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+}
+
+/// Ordinal date as a (year, day of year) tuple, with days starting at 1
+///
+/// Only valid days in the years from min to max (inclusive) are counted, so 
+/// day 366 is only valid in leap years. Encodes the same as the equivalent 
+/// date of a `DateSpec` over the same years.
+pub struct OrdinalDateSpec {
+    dates: DateSpec,
+}
+
+impl OrdinalDateSpec {
+
+    pub fn new(min: i32, max: i32) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new OrdinalDateSpec for the years from min to max 
+    /// (inclusive), or an error if the range is not valid
+    pub fn try_new(min: i32, max: i32) -> Result<Self, Error> {
+        Ok(Self { dates: DateSpec::try_new(min, max)? })
+    }
+}
+
+impl DataSpec<(i32, u16)> for OrdinalDateSpec {
+
+    fn permutations(&self) -> Digit {
+        self.dates.permutations
+    }
+
+    fn encode(&self, input: &(i32, u16)) -> Result<Digit, Error> {
+        let (year, day) = *input;
+        // Check the year
+        self.dates.encode(&(year, 1, 1))?;
+        let days = if is_leap_year(year as i64) { 366 } else { 365 };
+        if day < 1 || day > days {
+            return Err(Error::ValueOutOfRange {
+                value: day.to_string(),
+                min: 1.to_string(),
+                max: days.to_string(),
+            });
+        }
+        Ok((days_from_civil(year as i64, 1, 1) + day as i64 - 1 - self.dates.first) as Digit)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(i32, u16), Error> {
+        let (year, _, _) = self.dates.decode(input)?;
+        let day = self.dates.first + input as i64 - days_from_civil(year as i64, 1, 1) + 1;
+        Ok((year, day as u16))
+    }
+}
+
+/// ISO 8601 week date as a (week numbering year, week) tuple, with weeks 
+/// starting at 1
+///
+/// Weeks start on a Monday, and week 1 of a year is the week with the first 
+/// Thursday of the year, so years have either 52 or 53 weeks. Only valid 
+/// weeks in the years from min to max (inclusive) are counted.
+pub struct IsoWeekSpec {
+    min: i32,
+    max: i32,
+    /// Day number of the first day of week 1 of the min year
+    first: i64,
+    permutations: Digit,
+}
+
+impl IsoWeekSpec {
+
+    pub fn new(min: i32, max: i32) -> Self {
+        Self::try_new(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new IsoWeekSpec for the years from min to max (inclusive), or
+    /// an error if the range is not valid
+    pub fn try_new(min: i32, max: i32) -> Result<Self, Error> {
+        if min > max {
+            return Err(Error::InvalidRange { min: min.to_string(), max: max.to_string() });
+        }
+        let first = iso_year_start(min as i64);
+        let permutations = Digit::try_from((iso_year_start(max as i64 + 1) - first) / 7)
+            .map_err(|_| Error::TooManyPermutations)?;
+        Ok(Self { min, max, first, permutations })
+    }
+
+    /// Number of ISO weeks in a year (52 or 53)
+    pub fn weeks_in_year(year: i32) -> u8 {
+        ((iso_year_start(year as i64 + 1) - iso_year_start(year as i64)) / 7) as u8
+    }
+}
+
+impl DataSpec<(i32, u8)> for IsoWeekSpec {
+
+    fn permutations(&self) -> Digit {
+        self.permutations
+    }
+
+    fn encode(&self, input: &(i32, u8)) -> Result<Digit, Error> {
+        let (year, week) = *input;
+        if year < self.min || year > self.max {
+            return Err(Error::ValueOutOfRange {
+                value: year.to_string(),
+                min: self.min.to_string(),
+                max: self.max.to_string(),
+            });
+        }
+        let weeks = Self::weeks_in_year(year);
+        if week < 1 || week > weeks {
+            return Err(Error::ValueOutOfRange {
+                value: week.to_string(),
+                min: 1.to_string(),
+                max: weeks.to_string(),
+            });
+        }
+        Ok(((iso_year_start(year as i64) - self.first) / 7) as Digit + week as Digit - 1)
+    }
+
+    fn decode(&self, input: Digit) -> Result<(i32, u8), Error> {
+        if input >= self.permutations {
+            return Err(Error::DecodeOutOfBounds { value: input, permutations: self.permutations });
+        }
+        let monday = self.first + input as i64 * 7;
+        // The week belongs to the year of its Thursday
+        let (year, _, _) = civil_from_days(monday + 3);
+        Ok((year as i32, ((monday - iso_year_start(year)) / 7) as u8 + 1))
+    }
+}
+
+/// Day number of the Monday of week 1 of an ISO week numbering year, which is
+/// the week with January 4th in it
+fn iso_year_start(year: i64) -> i64 {
+    let january_4 = days_from_civil(year, 1, 4);
+    // 1970-01-01 was a Thursday
+    january_4 - (january_4 + 3).rem_euclid(7)
+}
+
+/// English names of the days of the week, starting with Monday
+const WEEKDAY_NAMES: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// English names of the months, starting with January
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", 
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Day of the week numbered as in ISO 8601, from Monday = 1 to Sunday = 7
+pub struct Weekday {}
+
+impl Weekday {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// English name of a day of the week (e.g. 1 is "Monday")
+    pub fn name(&self, day: u8) -> Result<&'static str, Error> {
+        self.encode(&day).map(|index| WEEKDAY_NAMES[index as usize])
+    }
+
+    /// Number of a day of the week from its English name (e.g. "Monday" is 1)
+    pub fn from_name(&self, name: &str) -> Result<u8, Error> {
+        from_name(&WEEKDAY_NAMES, name)
+    }
+}
+
+impl Default for Weekday {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSpec<u8> for Weekday {
+
+    fn permutations(&self) -> Digit {
+        7
+    }
+
+    fn encode(&self, input: &u8) -> Result<Digit, Error> {
+        encode_numbered(*input, 7)
+    }
+
+    fn decode(&self, input: Digit) -> Result<u8, Error> {
+        decode_numbered(input, 7)
+    }
+}
+
+/// Month of the year, from January = 1 to December = 12
+pub struct Month {}
+
+impl Month {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// English name of a month (e.g. 1 is "January")
+    pub fn name(&self, month: u8) -> Result<&'static str, Error> {
+        self.encode(&month).map(|index| MONTH_NAMES[index as usize])
+    }
+
+    /// Number of a month from its English name (e.g. "January" is 1)
+    pub fn from_name(&self, name: &str) -> Result<u8, Error> {
+        from_name(&MONTH_NAMES, name)
+    }
+}
+
+impl Default for Month {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSpec<u8> for Month {
+
+    fn permutations(&self) -> Digit {
+        12
+    }
+
+    fn encode(&self, input: &u8) -> Result<Digit, Error> {
+        encode_numbered(*input, 12)
+    }
+
+    fn decode(&self, input: Digit) -> Result<u8, Error> {
+        decode_numbered(input, 12)
+    }
+}
+
+/// Encode a value numbered from 1 to count
+fn encode_numbered(value: u8, count: u8) -> Result<Digit, Error> {
+    if value < 1 || value > count {
+        return Err(Error::ValueOutOfRange {
+            value: value.to_string(),
+            min: 1.to_string(),
+            max: count.to_string(),
+        });
+    }
+    Ok(value as Digit - 1)
+}
+
+/// Decode a value numbered from 1 to count
+fn decode_numbered(value: Digit, count: u8) -> Result<u8, Error> {
+    if value >= count as Digit {
+        return Err(Error::DecodeOutOfBounds { value, permutations: count as Digit });
+    }
+    Ok(value as u8 + 1)
+}
+
+/// Number (starting at 1) of a name in a list of names
+fn from_name(names: &[&str], name: &str) -> Result<u8, Error> {
+    names.iter()
+        .position(|n| *n == name)
+        .map(|index| index as u8 + 1)
+        .ok_or_else(|| Error::UnknownEnumVariant(name.to_string()))
+}
+
+/// Whether a year has a February 29th
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Number of days in a month of a year
+fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days from 1970-01-01 to a date
+///
+/// Years are shifted to start in March, so the leap day is the last day of a
+/// year, then counted in 400 year eras of 146097 days.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Date of a number of days from 1970-01-01, the inverse of days_from_civil
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u8;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u8;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use alloc::string::{String, ToString};
+
+    use crate::accum::{Accumulator, Digit};
+    use core::time::Duration;
+
+    use crate::data::{Bool, DataSpec, DateSpec, DateTimeSpec, DurationSpec, Enum};
+    use crate::data::{IsoWeekSpec, Month, OrdinalDateSpec, Resolution, SequenceLength, Sequencer};
+    use crate::data::{MonotonicDeltaSpec, TimeSpec, WideDataSpec, Weekday};
+    use crate::error::Error;
+
+    use super::{civil_from_days, days_from_civil};
+
+    #[test]
+    fn civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        for days in -800_000..800_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn date() {
+        let d = DateSpec::new(1900, 2099);
+        // 200 years with 49 leap years (1900 is not, 2000 is)
+        assert_eq!(d.permutations(), 200 * 365 + 49);
+        assert_eq!(d.encode(&(1900, 1, 1)), Ok(0));
+        assert_eq!(d.encode(&(2099, 12, 31)), Ok(200 * 365 + 49 - 1));
+        assert_eq!(d.decode(0), Ok((1900, 1, 1)));
+        assert_eq!(d.decode(200 * 365 + 49 - 1), Ok((2099, 12, 31)));
+        assert!(d.decode(200 * 365 + 49).is_err());
+        for date in [(2000, 2, 29), (2000, 3, 1), (1900, 2, 28), (1900, 3, 1), (2024, 2, 29), (2023, 12, 31)] {
+            assert_eq!(d.decode(d.encode(&date).unwrap()), Ok(date));
+        }
+        // 1900 is not a leap year, so March 1st follows February 28th
+        assert_eq!(d.encode(&(1900, 3, 1)), Ok(d.encode(&(1900, 2, 28)).unwrap() + 1));
+        assert_eq!(d.encode(&(2000, 3, 1)), Ok(d.encode(&(2000, 2, 29)).unwrap() + 1));
+        // Invalid dates
+        assert_eq!(d.encode(&(1900, 2, 29)), Err(Error::InvalidDate { year: 1900, month: 2, day: 29 }));
+        assert!(d.encode(&(2023, 2, 29)).is_err());
+        assert!(d.encode(&(2024, 2, 30)).is_err());
+        assert!(d.encode(&(2024, 4, 31)).is_err());
+        assert!(d.encode(&(2024, 13, 1)).is_err());
+        assert!(d.encode(&(2024, 0, 1)).is_err());
+        assert!(d.encode(&(2024, 1, 0)).is_err());
+        assert!(matches!(d.encode(&(2100, 1, 1)), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(d.encode(&(1899, 12, 31)), Err(Error::ValueOutOfRange { .. })));
+        // Every date in a range is counted once, in order
+        let d = DateSpec::new(1999, 2001);
+        assert_eq!(d.permutations(), 365 + 366 + 365);
+        for index in 0..d.permutations() {
+            assert_eq!(d.encode(&d.decode(index).unwrap()), Ok(index));
+        }
+        assert_eq!(DateSpec::new(2024, 2024).permutations(), 366);
+        assert!(matches!(DateSpec::try_new(2024, 2023), Err(Error::InvalidRange { .. })));
+        assert_eq!(DateSpec::new(-400, -1).permutations(), 146097);
+    }
+
+    #[test]
+    fn seq_date() {
+        let mut a = Accumulator::new();
+        let dates = &[(2000, 2, 29), (2024, 12, 25), (1970, 1, 1)];
+        let spec = DateSpec::new(1970, 2100);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(10));
+        sequencer.compress(dates, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), dates);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn ordinal_date() {
+        let d = OrdinalDateSpec::new(1999, 2001);
+        let dates = DateSpec::new(1999, 2001);
+        assert_eq!(d.permutations(), 365 + 366 + 365);
+        assert_eq!(d.encode(&(1999, 1)), Ok(0));
+        assert_eq!(d.encode(&(2000, 366)), Ok(365 + 365));
+        assert_eq!(d.encode(&(2000, 366)), dates.encode(&(2000, 12, 31)));
+        assert_eq!(d.encode(&(2000, 60)), dates.encode(&(2000, 2, 29)));
+        assert_eq!(d.encode(&(2001, 365)), Ok(d.permutations() - 1));
+        for index in 0..d.permutations() {
+            assert_eq!(d.encode(&d.decode(index).unwrap()), Ok(index));
+        }
+        assert_eq!(d.decode(365 + 365), Ok((2000, 366)));
+        assert!(d.decode(d.permutations()).is_err());
+        assert_eq!(d.encode(&(1999, 366)), Err(Error::ValueOutOfRange {
+            value: String::from("366"),
+            min: String::from("1"),
+            max: String::from("365"),
+        }));
+        assert!(d.encode(&(2000, 367)).is_err());
+        assert!(d.encode(&(2000, 0)).is_err());
+        assert!(d.encode(&(2002, 1)).is_err());
+    }
+
+    #[test]
+    fn iso_week() {
+        // Years starting on a Thursday, or leap years starting on a Wednesday
+        for (year, weeks) in [(2015, 53), (2019, 52), (2020, 53), (2021, 52), (2026, 53), (2032, 53), (2000, 52), (2004, 53)] {
+            assert_eq!(IsoWeekSpec::weeks_in_year(year), weeks, "{}", year);
+        }
+        let w = IsoWeekSpec::new(2019, 2021);
+        assert_eq!(w.permutations(), 52 + 53 + 52);
+        assert_eq!(w.encode(&(2019, 1)), Ok(0));
+        assert_eq!(w.encode(&(2020, 1)), Ok(52));
+        assert_eq!(w.encode(&(2020, 53)), Ok(52 + 52));
+        assert_eq!(w.encode(&(2021, 1)), Ok(52 + 53));
+        assert_eq!(w.encode(&(2021, 52)), Ok(w.permutations() - 1));
+        for index in 0..w.permutations() {
+            assert_eq!(w.encode(&w.decode(index).unwrap()), Ok(index));
+        }
+        assert_eq!(w.decode(52 + 52), Ok((2020, 53)));
+        assert!(w.decode(w.permutations()).is_err());
+        assert_eq!(w.encode(&(2019, 53)), Err(Error::ValueOutOfRange {
+            value: String::from("53"),
+            min: String::from("1"),
+            max: String::from("52"),
+        }));
+        assert!(w.encode(&(2020, 54)).is_err());
+        assert!(w.encode(&(2020, 0)).is_err());
+        assert!(w.encode(&(2022, 1)).is_err());
+        // 400 years of weeks
+        assert_eq!(IsoWeekSpec::new(2000, 2399).permutations(), 146097 / 7);
+        assert!(matches!(IsoWeekSpec::try_new(2021, 2020), Err(Error::InvalidRange { .. })));
+    }
+
+    #[test]
+    fn seq_iso_week() {
+        let mut a = Accumulator::new();
+        let weeks = &[(2020, 53), (2021, 1), (2026, 53)];
+        let days = &[(2024, 366), (2025, 1)];
+        let week = IsoWeekSpec::new(2000, 2100);
+        let day = OrdinalDateSpec::new(2000, 2100);
+        let sequencer0 = Sequencer::new(&week, SequenceLength::Variable(8));
+        let sequencer1 = Sequencer::new(&day, SequenceLength::Variable(8));
+        sequencer0.compress(weeks, &mut a).unwrap();
+        sequencer1.compress(days, &mut a).unwrap();
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), days);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), weeks);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn time() {
+        let t = TimeSpec::seconds();
+        assert_eq!(t.permutations(), 86400);
+        assert_eq!(t.encode(&(0, 0, 0)), Ok(0));
+        assert_eq!(t.encode(&(23, 59, 59)), Ok(86399));
+        assert_eq!(t.decode(0), Ok((0, 0, 0)));
+        assert_eq!(t.decode(86399), Ok((23, 59, 59)));
+        assert_eq!(t.decode(3723), Ok((1, 2, 3)));
+        assert!(t.decode(86400).is_err());
+        assert_eq!(t.encode(&(25, 0, 0)), Err(Error::InvalidTime { hour: 25, minute: 0, second: 0 }));
+        assert!(t.encode(&(24, 0, 0)).is_err());
+        assert!(t.encode(&(10, 61, 0)).is_err());
+        assert!(t.encode(&(10, 0, 60)).is_err());
+        assert!(t.encode(&(23, 59, 60)).is_err());
+        let t = TimeSpec::minutes();
+        assert_eq!(t.permutations(), 1440);
+        assert_eq!(t.encode(&(0, 0, 0)), Ok(0));
+        assert_eq!(t.encode(&(23, 59, 0)), Ok(1439));
+        assert_eq!(t.decode(1439), Ok((23, 59, 0)));
+        assert_eq!(t.decode(90), Ok((1, 30, 0)));
+        assert!(matches!(t.encode(&(10, 30, 15)), Err(Error::ValueNotOnStep { .. })));
+        assert!(t.decode(1440).is_err());
+        // Leap second
+        let t = TimeSpec::seconds().with_leap_second();
+        assert_eq!(t.permutations(), 86401);
+        assert_eq!(t.encode(&(23, 59, 60)), Ok(86400));
+        assert_eq!(t.decode(86400), Ok((23, 59, 60)));
+        assert_eq!(t.encode(&(23, 59, 59)), Ok(86399));
+        assert!(t.encode(&(12, 0, 60)).is_err());
+        assert!(t.decode(86401).is_err());
+    }
+
+    #[test]
+    fn seq_time() {
+        // A reading every 17 minutes and 13 seconds over a day
+        let mut a = Accumulator::new();
+        let times: Vec<(u8, u8, u8)> = (0..86400u32)
+            .step_by(17 * 60 + 13)
+            .map(|s| ((s / 3600) as u8, (s / 60 % 60) as u8, (s % 60) as u8))
+            .collect();
+        let minutes: Vec<(u8, u8, u8)> = times.iter().map(|&(h, m, _)| (h, m, 0)).collect();
+        let seconds = TimeSpec::seconds();
+        let minute = TimeSpec::minutes();
+        let sequencer0 = Sequencer::new(&seconds, SequenceLength::Fixed(times.len()));
+        let sequencer1 = Sequencer::new(&minute, SequenceLength::Variable(200));
+        sequencer0.compress(&times, &mut a).unwrap();
+        sequencer1.compress(&minutes, &mut a).unwrap();
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), minutes);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), times);
+        assert!(a.is_empty());
+    }
+
+    /// 2020-01-01T00:00:00Z
+    const START_2020: i64 = 1577836800;
+    /// 2035-01-01T00:00:00Z
+    const START_2035: i64 = 2051222400;
+
+    #[test]
+    fn date_time() {
+        let t = DateTimeSpec::new(START_2020, START_2035, Resolution::Seconds);
+        assert_eq!(t.permutations(), (START_2035 - START_2020) as Digit + 1);
+        assert_eq!(t.encode(&START_2020), Ok(0));
+        assert_eq!(t.encode(&START_2035), Ok((START_2035 - START_2020) as Digit));
+        assert_eq!(t.decode(0), Ok(START_2020));
+        assert_eq!(t.decode((START_2035 - START_2020) as Digit), Ok(START_2035));
+        assert_eq!(t.decode(t.encode(&1700000000).unwrap()), Ok(1700000000));
+        assert!(matches!(t.encode(&(START_2020 - 1)), Err(Error::ValueOutOfRange { .. })));
+        assert!(matches!(t.encode(&(START_2035 + 1)), Err(Error::ValueOutOfRange { .. })));
+        assert!(t.decode(t.permutations()).is_err());
+        // Coarser resolutions need fewer bits
+        let bits = |resolution| {
+            let t = DateTimeSpec::new(START_2020, START_2035, resolution);
+            Digit::BITS - t.permutations().leading_zeros()
+        };
+        assert_eq!(bits(Resolution::Seconds), 29);
+        assert_eq!(bits(Resolution::Minutes), 23);
+        assert_eq!(bits(Resolution::Hours), 18);
+        assert_eq!(bits(Resolution::Days), 13);
+        let t = DateTimeSpec::new(START_2020, START_2035, Resolution::Days);
+        assert_eq!(t.permutations(), 5480);
+        assert_eq!(t.encode(&(START_2020 + 86400)), Ok(1));
+        assert_eq!(t.decode(5479), Ok(START_2035));
+        assert_eq!(t.encode(&(START_2020 + 86399)), Err(Error::ValueNotOnStep {
+            value: (START_2020 + 86399).to_string(),
+            min: START_2020.to_string(),
+            step: String::from("86400"),
+        }));
+        let t = DateTimeSpec::new(START_2020, START_2035, Resolution::Minutes).with_truncation();
+        assert_eq!(t.encode(&(START_2020 + 119)), Ok(1));
+        assert_eq!(t.decode(1), Ok(START_2020 + 60));
+        // Max is moved back to the last step
+        let t = DateTimeSpec::new(0, 3599, Resolution::Minutes);
+        assert_eq!(t.permutations(), 60);
+        assert!(t.encode(&3599).is_err());
+        assert!(matches!(DateTimeSpec::try_new(10, 10, Resolution::Seconds), Err(Error::InvalidRange { .. })));
+        assert_eq!(
+            DateTimeSpec::try_new(i64::MIN, i64::MAX, Resolution::Seconds).err(),
+            Some(Error::TooManyPermutations)
+        );
+    }
+
+    #[test]
+    fn seq_date_time() {
+        // Events with a timestamp, a kind and an acknowledged flag
+        let mut a = Accumulator::new();
+        let timestamps = &[START_2020, 1700000000, 1700000060, START_2035];
+        let kinds = [String::from("Info"), String::from("Error"), String::from("Info"), String::from("Warning")];
+        let acknowledged = &[true, true, false, false];
+        let timestamp = DateTimeSpec::new(START_2020, START_2035, Resolution::Seconds);
+        let kind = Enum::new(&["Info", "Warning", "Error"]);
+        let bool = Bool::new();
+        let sequencer0 = Sequencer::new(&timestamp, SequenceLength::Variable(16));
+        let sequencer1 = Sequencer::new(&kind, SequenceLength::Fixed(4));
+        let sequencer2 = Sequencer::new(&bool, SequenceLength::Fixed(4));
+        sequencer0.compress(timestamps, &mut a).unwrap();
+        sequencer1.compress(&kinds, &mut a).unwrap();
+        sequencer2.compress(acknowledged, &mut a).unwrap();
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), acknowledged);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), kinds);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), timestamps);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn duration() {
+        let day = Duration::from_secs(86400);
+        let d = DurationSpec::seconds(day);
+        assert_eq!(d.permutations(), 86401);
+        assert_eq!(d.max(), day);
+        assert_eq!(d.encode(&Duration::ZERO), Ok(0));
+        assert_eq!(d.encode(&day), Ok(86400));
+        assert_eq!(d.decode(0), Ok(Duration::ZERO));
+        assert_eq!(d.decode(86400), Ok(day));
+        assert_eq!(d.decode(90), Ok(Duration::from_secs(90)));
+        assert!(d.decode(86401).is_err());
+        assert_eq!(d.encode(&(day + Duration::from_nanos(1))), Err(Error::ValueOutOfRange {
+            value: String::from("86400.000000001s"),
+            min: String::from("0ns"),
+            max: String::from("86400s"),
+        }));
+        assert_eq!(d.encode(&Duration::from_millis(1500)), Err(Error::ValueNotOnStep {
+            value: String::from("1.5s"),
+            min: String::from("0ns"),
+            step: String::from("1s"),
+        }));
+        let d = DurationSpec::seconds(day).with_truncation();
+        assert_eq!(d.encode(&Duration::from_millis(1500)), Ok(1));
+        assert_eq!(d.encode(&Duration::from_millis(999)), Ok(0));
+        assert!(d.encode(&(day + Duration::from_nanos(1))).is_err());
+        let d = DurationSpec::millis(Duration::from_secs(10));
+        assert_eq!(d.permutations(), 10001);
+        assert_eq!(d.decode(d.encode(&Duration::from_millis(1234)).unwrap()), Ok(Duration::from_millis(1234)));
+        assert!(d.encode(&Duration::from_micros(1500)).is_err());
+        // Custom tick, with the max moved back to the last whole tick
+        let d = DurationSpec::new(Duration::from_secs(100), Duration::from_secs(15));
+        assert_eq!(d.permutations(), 7);
+        assert_eq!(d.max(), Duration::from_secs(90));
+        assert_eq!(d.encode(&Duration::from_secs(45)), Ok(3));
+        assert!(d.encode(&Duration::from_secs(100)).is_err());
+        assert_eq!(DurationSpec::try_new(day, Duration::ZERO).err(), Some(Error::ZeroStep));
+        assert_eq!(
+            DurationSpec::try_new(Duration::MAX, Duration::from_nanos(1)).err(),
+            Some(Error::TooManyPermutations)
+        );
+    }
+
+    #[test]
+    fn seq_duration() {
+        let mut a = Accumulator::new();
+        let retries: Vec<Duration> = (0..8).map(|ii| Duration::from_millis(100 << ii)).collect();
+        let spec = DurationSpec::millis(Duration::from_secs(60));
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(16));
+        sequencer.compress(&retries, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), retries);
+        assert!(a.is_empty());
+        let invalid = &[Duration::from_secs(1), Duration::from_secs(61)];
+        assert!(matches!(sequencer.compress(invalid, &mut a), Err(Error::Element { index: 1, .. })));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn weekday() {
+        let w = Weekday::new();
+        assert_eq!(w.permutations(), 7);
+        assert_eq!(w.encode(&1), Ok(0));
+        assert_eq!(w.encode(&7), Ok(6));
+        assert_eq!(w.decode(0), Ok(1));
+        assert_eq!(w.decode(6), Ok(7));
+        assert!(w.decode(7).is_err());
+        assert_eq!(w.encode(&0), Err(Error::ValueOutOfRange {
+            value: String::from("0"),
+            min: String::from("1"),
+            max: String::from("7"),
+        }));
+        assert!(w.encode(&8).is_err());
+        assert_eq!(w.name(1), Ok("Monday"));
+        assert_eq!(w.name(7), Ok("Sunday"));
+        assert!(w.name(8).is_err());
+        assert_eq!(w.from_name("Wednesday"), Ok(3));
+        assert_eq!(w.from_name("Caturday"), Err(Error::UnknownEnumVariant(String::from("Caturday"))));
+        for day in 1..=7 {
+            assert_eq!(w.from_name(w.name(day).unwrap()), Ok(day));
+        }
+    }
+
+    #[test]
+    fn month() {
+        let m = Month::new();
+        assert_eq!(m.permutations(), 12);
+        assert_eq!(m.encode(&1), Ok(0));
+        assert_eq!(m.encode(&12), Ok(11));
+        assert_eq!(m.decode(11), Ok(12));
+        assert!(m.decode(12).is_err());
+        assert!(m.encode(&0).is_err());
+        assert!(m.encode(&13).is_err());
+        assert_eq!(m.name(2), Ok("February"));
+        assert!(m.name(13).is_err());
+        assert_eq!(m.from_name("December"), Ok(12));
+        assert!(m.from_name("december").is_err());
+        for month in 1..=12 {
+            assert_eq!(m.from_name(m.name(month).unwrap()), Ok(month));
+        }
+    }
+
+    #[test]
+    fn seq_weekday_month() {
+        // Recurring events on a weekday of some months, starting on a date
+        let mut a = Accumulator::new();
+        let starts = &[(2024, 1, 1), (2024, 3, 15)];
+        let weekdays = &[1, 5];
+        let months: &[u8] = &[1, 4, 7, 10, 3];
+        let date = DateSpec::new(2000, 2099);
+        let weekday = Weekday::new();
+        let month = Month::new();
+        let sequencer0 = Sequencer::new(&date, SequenceLength::Fixed(2));
+        let sequencer1 = Sequencer::new(&weekday, SequenceLength::Fixed(2));
+        let sequencer2 = Sequencer::new(&month, SequenceLength::Variable(12));
+        sequencer0.compress(starts, &mut a).unwrap();
+        sequencer1.compress(weekdays, &mut a).unwrap();
+        sequencer2.compress(months, &mut a).unwrap();
+        assert_eq!(sequencer2.decompress(&mut a).unwrap(), months);
+        assert_eq!(sequencer1.decompress(&mut a).unwrap(), weekdays);
+        assert_eq!(sequencer0.decompress(&mut a).unwrap(), starts);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn monotonic_delta() {
+        let spec = MonotonicDeltaSpec::new(START_2020, START_2035, Resolution::Seconds, 60, 32);
+        assert_eq!(spec.max_delta(), 60);
+        let bursts = [
+            vec![],
+            vec![START_2020],
+            vec![START_2035],
+            vec![1700000000, 1700000000, 1700000001, 1700000061, 1700000121],
+            vec![START_2035 - 60, START_2035],
+        ];
+        for burst in bursts {
+            let mut a = Accumulator::new();
+            spec.encode_into(&burst, &mut a).unwrap();
+            assert_eq!(spec.decode_from(&mut a), Ok(burst));
+            assert!(a.is_empty());
+        }
+        let mut a = Accumulator::new();
+        assert_eq!(spec.encode_into(&vec![1700000000, 1700000061], &mut a), Err(Error::Element {
+            index: 1,
+            error: Error::ValueOutOfRange {
+                value: String::from("61"),
+                min: String::from("0"),
+                max: String::from("60"),
+            }.into(),
+        }));
+        assert!(matches!(
+            spec.encode_into(&vec![1700000000, 1699999999], &mut a),
+            Err(Error::Element { index: 1, .. })
+        ));
+        assert!(matches!(
+            spec.encode_into(&vec![1700000000, START_2035 + 1], &mut a),
+            Err(Error::Element { index: 1, .. })
+        ));
+        assert!(matches!(
+            spec.encode_into(&vec![START_2020; 33], &mut a),
+            Err(Error::SequenceTooLong { len: 33, max: 32 })
+        ));
+        assert!(a.is_empty());
+        // Differences past the end of the range are detected when decoding
+        let mut a = Accumulator::new();
+        spec.sequencer().compress(&[60], &mut a).unwrap();
+        a.mul(spec.timestamps.permutations());
+        a.add(spec.timestamps.permutations() - 1);
+        a.mul(2);
+        a.add(1);
+        assert!(matches!(spec.decode_from(&mut a), Err(Error::DecodeElement { index: 1, .. })));
+        // Minute resolution
+        let spec = MonotonicDeltaSpec::new(START_2020, START_2035, Resolution::Minutes, 3600, 8);
+        let mut a = Accumulator::new();
+        let burst = vec![START_2020 + 60, START_2020 + 120, START_2020 + 3720];
+        spec.encode_into(&burst, &mut a).unwrap();
+        assert_eq!(spec.decode_from(&mut a), Ok(burst));
+        assert!(spec.encode_into(&vec![START_2020 + 30], &mut a).is_err());
+        assert!(matches!(
+            MonotonicDeltaSpec::try_new(START_2020, START_2035, Resolution::Minutes, 59, 8),
+            Err(Error::InvalidRange { .. })
+        ));
+    }
+
+    #[test]
+    fn monotonic_delta_overflow() {
+        // Ranges with as many steps as fit in a Digit
+        #[cfg(not(feature = "digit32"))]
+        let (min, max, max_delta) = (i64::MIN + 1, i64::MAX, i64::MAX);
+        #[cfg(feature = "digit32")]
+        let (min, max, max_delta) = (0, i64::from(Digit::MAX) - 1, i64::from(Digit::MAX / 2));
+        let spec = MonotonicDeltaSpec::new(min, max, Resolution::Seconds, max_delta, 4);
+        let permutations = spec.timestamps.permutations();
+        let max_step = spec.deltas.permutations() - 1;
+        assert_eq!(permutations, Digit::MAX);
+        let mut a = Accumulator::new();
+        spec.encode_into(&vec![min, min + spec.max_delta(), max], &mut a).unwrap();
+        assert_eq!(spec.decode_from(&mut a), Ok(vec![min, min + spec.max_delta(), max]));
+        // Unordered timestamps at the ends of the range
+        assert_eq!(spec.encode_into(&vec![max, min], &mut a), Err(Error::Element {
+            index: 1,
+            error: Error::ValueOutOfRange {
+                value: (min as i128 - max as i128).to_string(),
+                min: String::from("0"),
+                max: spec.max_delta().to_string(),
+            }.into(),
+        }));
+        // A difference from the last timestamp overflows the index
+        let mut a = Accumulator::new();
+        spec.sequencer().compress(&[max_step], &mut a).unwrap();
+        a.mul(permutations);
+        a.add(permutations - 1);
+        a.mul(2);
+        a.add(1);
+        assert_eq!(spec.decode_from(&mut a), Err(Error::DecodeElement {
+            index: 1,
+            value: max_step,
+            error: Error::DecodeOutOfBounds { value: max_step, permutations: 1 }.into(),
+        }));
+    }
+
+    #[test]
+    fn monotonic_delta_size() {
+        let burst: Vec<i64> = (0..100).map(|ii| 1700000000 + ii * 7 + ii % 3).collect();
+        let spec = MonotonicDeltaSpec::new(START_2020, START_2035, Resolution::Seconds, 60, 100);
+        let mut a = Accumulator::new();
+        spec.encode_into(&burst, &mut a).unwrap();
+        let timestamp = DateTimeSpec::new(START_2020, START_2035, Resolution::Seconds);
+        let naive = Sequencer::new(&timestamp, SequenceLength::Variable(100));
+        let mut b = Accumulator::new();
+        naive.compress(&burst, &mut b).unwrap();
+        assert!(a.to_bytes().len() * 3 < b.to_bytes().len());
+        assert_eq!(spec.decode_from(&mut a), Ok(burst.clone()));
+        assert_eq!(naive.decompress(&mut b).unwrap(), burst);
+    }
+
+    #[test]
+    fn seq_monotonic_delta() {
+        let mut a = Accumulator::new();
+        let bursts = &[vec![1700000000, 1700000005], vec![], vec![1800000000]];
+        let spec = MonotonicDeltaSpec::new(START_2020, START_2035, Resolution::Seconds, 60, 16);
+        let sequencer = Sequencer::new(&spec, SequenceLength::Variable(4));
+        sequencer.compress(bursts, &mut a).unwrap();
+        assert_eq!(sequencer.decompress(&mut a).unwrap(), bursts);
+        assert!(a.is_empty());
+    }
+}
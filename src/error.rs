@@ -0,0 +1,363 @@
+//! Error type for everything in the crate that can fail
+
+use core::fmt;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use crate::accum::Digit;
+
+/// Error returned when data can not be compressed or decompressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Value to encode is outside of the range allowed by the spec
+    ValueOutOfRange { value: String, min: String, max: String },
+    /// Value to encode is not the value of a constant spec
+    ConstantMismatch { value: String, expected: String },
+    /// String to encode is not one of the options of an Enum
+    UnknownEnumVariant(String),
+    /// Character to encode is not part of a CharSet
+    CharNotInSet(char),
+    /// Value to decode is the index of an option that is not defined (yet)
+    UnknownVariantIndex(Digit),
+    /// Value to decode is not less than the permutations of the spec
+    DecodeOutOfBounds { value: Digit, permutations: Digit },
+    /// Value decoded by a wide spec is larger than the maximum of the spec
+    DecodeOutOfRange { value: String, max: String },
+    /// Pattern of a string spec has a character that is not a class or an
+    /// escaped literal
+    InvalidPattern { pattern: String, index: usize },
+    /// Character of a string to encode does not match its pattern
+    PatternMismatch { index: usize, value: char, pattern: char },
+    /// Check digit of a value to encode is not the one calculated from its
+    /// other digits
+    InvalidCheckDigit { expected: u8, actual: u8 },
+    /// Value to encode does not have the length required by the spec
+    InvalidLength { expected: usize, actual: usize },
+    /// Sequence to compress is longer than the maximum length of the Sequencer
+    SequenceTooLong { len: usize, max: usize },
+    /// Sequence to compress is not the length of a fixed length Sequencer
+    LengthMismatch { expected: usize, actual: usize },
+    /// Matrix to compress does not have the dimensions of a fixed size
+    /// MatrixSequencer
+    DimensionMismatch { rows: usize, cols: usize, expected_rows: usize, expected_cols: usize },
+    /// Matrix to compress has more rows or columns (the axis) than a
+    /// variable size MatrixSequencer allows
+    MatrixDimension { axis: String, value: usize, max: usize },
+    /// Attempted to divide the accumulator by zero
+    DivideByZero,
+    /// Attempted to multiply the accumulator by zero
+    MultiplyByZero,
+    /// Attempted to shift the accumulator by more than the bits in a Digit
+    ShiftTooLarge { shift: usize },
+    /// Range minimum is greater than or equal to the maximum
+    InvalidRange { min: String, max: String },
+    /// Range only has one value after min and max are quantized
+    CollapsedRange { min: String, max: String },
+    /// Range bound is beyond the limit of what the spec can represent
+    RangeExceedsLimit { value: String, limit: String },
+    /// Enum has no room left for another option
+    CapacityExceeded { capacity: Digit },
+    /// Capacity of a VersionedEnum is zero or less than its initial options
+    InsufficientCapacity { options: usize, capacity: Digit },
+    /// Spec would have more permutations than fit in a Digit
+    TooManyPermutations,
+    /// Number of binary decimals is too large for a fixed point number
+    InvalidDecimals(u32),
+    /// Number is NaN or infinite
+    NonFiniteValue,
+    /// Step of a range is zero
+    ZeroStep,
+    /// Date to encode does not exist in the calendar (e.g. February 30th)
+    InvalidDate { year: i32, month: u8, day: u8 },
+    /// Time to encode is not a valid time of day (e.g. 25:00:00)
+    InvalidTime { hour: u8, minute: u8, second: u8 },
+    /// Network address has bits set after its prefix
+    InvalidPrefix { network: String, prefix_len: u8 },
+    /// String is not a MAC address in the `aa:bb:cc:dd:ee:ff` form
+    InvalidMacAddress(String),
+    /// String is not a phone number in the E.164 `+` and 1 to 15 digits form
+    InvalidPhoneNumber(String),
+    /// String is not a color in the `#RRGGBB` or `#RRGGBBAA` form
+    InvalidHexColor(String),
+    /// Color is not opaque, but is encoded or decoded without an alpha channel
+    TranslucentColor { alpha: u8 },
+    /// UUID to encode is not a version 4, variant 1 UUID
+    InvalidUuidVersion { version: u8, variant: u8 },
+    /// Fraction to encode is not in lowest terms
+    FractionNotReduced { numerator: String, denominator: String },
+    /// Base of a number is too small or too large to be used
+    InvalidBase(Digit),
+    /// Value to encode is not a whole number of steps from the range minimum
+    ValueNotOnStep { value: String, min: String, step: String },
+    /// Value to encode or decoded value does not meet the requirement of a
+    /// validated spec, given by its description
+    FailedValidation(String),
+    /// Character appears more than once in a CharSet
+    DuplicateChar { value: char, index: usize, first: usize },
+    /// Character is the other case of a character already in a case
+    /// insensitive CharSet
+    AmbiguousCase { value: char, index: usize, first: usize },
+    /// Alias of an Enum is the same as one of its options or another alias
+    DuplicateAlias(String),
+    /// Character of a weighted CharSet has a weight of zero
+    ZeroWeight(char),
+    /// Option of a weighted Enum has a weight of zero
+    ZeroOptionWeight(String),
+    /// String appears more than once in the options of an Enum
+    DuplicateOption { value: String, index: usize, first: usize },
+    /// Value appears more than once in a set
+    DuplicateElement { value: String, index: usize, first: usize },
+    /// Error for a single element of a sequence
+    Element { index: usize, error: Box<Error> },
+    /// Error decoding a single element of a sequence from the digit taken 
+    /// from the accumulator
+    DecodeElement { index: usize, value: Digit, error: Box<Error> },
+    /// Error for a single component of a tuple or record
+    Component { index: usize, error: Box<Error> },
+    /// Error for a single field of a schema
+    Field { name: String, error: Box<Error> },
+    /// Variable length sequence did not end within its maximum length
+    UnterminatedSequence { max: usize },
+    /// Guarded accumulator ran out of data before all elements were read
+    Exhausted { index: usize },
+    /// Accumulator still holds data that was expected to be fully read
+    TrailingData { len: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ValueOutOfRange { value, min, max } => write!(
+                f, "Value to encode is outside allowed range ({} is not within {} to {})",
+                value, min, max
+            ),
+            Error::ConstantMismatch { value, expected } => write!(
+                f, "Value to encode is not the constant of the spec ({} is not {})", value, expected
+            ),
+            Error::UnknownEnumVariant(value) => write!(
+                f, "Given value not contained in this Enum type (\"{}\")", value
+            ),
+            Error::CharNotInSet(c) => write!(
+                f, "Could not encode character not defined in the character set ('{}')", c
+            ),
+            Error::UnknownVariantIndex(index) => write!(
+                f, "Cannot decode data, no option is defined for index {}", index
+            ),
+            Error::DecodeOutOfBounds { value, permutations } => write!(
+                f, "Cannot decode data, input larger than possible permutations ({} >= {})",
+                value, permutations
+            ),
+            Error::DecodeOutOfRange { value, max } => write!(
+                f, "Cannot decode data, decoded value larger than the maximum ({} > {})",
+                value, max
+            ),
+            Error::InvalidPattern { pattern, index } => write!(
+                f, "Pattern has an unknown character class at {} (\"{}\")", index, pattern
+            ),
+            Error::PatternMismatch { index, value, pattern } => write!(
+                f, "Character '{}' at {} does not match '{}' of the pattern", value, index, pattern
+            ),
+            Error::InvalidCheckDigit { expected, actual } => write!(
+                f, "Value to encode has a check digit of {} but should have {}", actual, expected
+            ),
+            Error::InvalidLength { expected, actual } => write!(
+                f, "Value to encode must have a length of {} but has a length of {}", expected, actual
+            ),
+            Error::SequenceTooLong { len, max } => write!(
+                f, "Value of length {} was not able to be compressed by Sequencer with max length {}",
+                len, max
+            ),
+            Error::LengthMismatch { expected, actual } => write!(
+                f, "Fixed length Sequencer expected {} values but was given {}", expected, actual
+            ),
+            Error::DimensionMismatch { rows, cols, expected_rows, expected_cols } => write!(
+                f, "Fixed size MatrixSequencer expected {}x{} values but was given {}x{}",
+                expected_rows, expected_cols, rows, cols
+            ),
+            Error::MatrixDimension { axis, value, max } => write!(
+                f, "Variable size MatrixSequencer allows at most {} {} but was given {}", max, axis, value
+            ),
+            Error::DivideByZero => write!(f, "Cannot divide by zero!"),
+            Error::MultiplyByZero => write!(f, "Cannot multiply accumulator by zero!"),
+            Error::ShiftTooLarge { shift } => write!(
+                f, "Can not apply shift to accumulator greater than {} ({})", Digit::BITS, shift
+            ),
+            Error::InvalidRange { min, max } => write!(
+                f, "Range min may not be greater than or equal to the max ({} >= {})", min, max
+            ),
+            Error::CollapsedRange { min, max } => write!(
+                f, "Range from {} to {} only has a single value once quantized", min, max
+            ),
+            Error::RangeExceedsLimit { value, limit } => write!(
+                f, "Range bound {} is beyond the allowable limit of {}", value, limit
+            ),
+            Error::CapacityExceeded { capacity } => write!(
+                f, "Enum already has as many options as its capacity of {}", capacity
+            ),
+            Error::InsufficientCapacity { options, capacity } => write!(
+                f, "Enum capacity of {} must be at least 1 and at least its {} options", capacity, options
+            ),
+            Error::TooManyPermutations => write!(
+                f, "Spec has more permutations than can be encoded in a Digit"
+            ),
+            Error::InvalidDecimals(decimals) => write!(
+                f, "Fixed point numbers can not have {} binary decimals", decimals
+            ),
+            Error::NonFiniteValue => write!(f, "Number is NaN or infinite"),
+            Error::ZeroStep => write!(f, "Step of a range can not be zero"),
+            Error::InvalidDate { year, month, day } => write!(
+                f, "Date to encode does not exist ({:04}-{:02}-{:02})", year, month, day
+            ),
+            Error::InvalidTime { hour, minute, second } => write!(
+                f, "Time to encode does not exist ({:02}:{:02}:{:02})", hour, minute, second
+            ),
+            Error::InvalidPrefix { network, prefix_len } => write!(
+                f, "Network address has host bits set after its prefix ({}/{})", network, prefix_len
+            ),
+            Error::InvalidMacAddress(value) => write!(
+                f, "Could not parse MAC address (\"{}\")", value
+            ),
+            Error::InvalidPhoneNumber(value) => write!(
+                f, "Could not parse E.164 phone number (\"{}\")", value
+            ),
+            Error::InvalidHexColor(value) => write!(
+                f, "Could not parse hex color (\"{}\")", value
+            ),
+            Error::TranslucentColor { alpha } => write!(
+                f, "Color with an alpha of {} can not be used without an alpha channel", alpha
+            ),
+            Error::InvalidUuidVersion { version, variant } => write!(
+                f, "UUID to encode is not a random UUID (version {}, variant bits {:02b})", version, variant
+            ),
+            Error::FractionNotReduced { numerator, denominator } => write!(
+                f, "Fraction to encode is not in lowest terms ({}/{})", numerator, denominator
+            ),
+            Error::InvalidBase(base) => write!(f, "Numbers can not use a base of {}", base),
+            Error::ValueNotOnStep { value, min, step } => write!(
+                f, "Value to encode is not on a step of the range ({} is not {} plus a multiple of {})",
+                value, min, step
+            ),
+            Error::FailedValidation(description) => write!(
+                f, "Value does not meet the requirement of the spec ({})", description
+            ),
+            Error::DuplicateChar { value, index, first } => write!(
+                f, "Attempted to add duplicate characters to CharSet data ('{}' at {} already at {})",
+                value, index, first
+            ),
+            Error::AmbiguousCase { value, index, first } => write!(
+                f, "Case insensitive CharSet has both cases of a character ('{}' at {} matches {})",
+                value, index, first
+            ),
+            Error::DuplicateAlias(alias) => write!(
+                f, "Alias of an Enum is already an option or another alias (\"{}\")", alias
+            ),
+            Error::ZeroWeight(c) => write!(
+                f, "Characters of a weighted CharSet can not have a weight of zero ('{}')", c
+            ),
+            Error::ZeroOptionWeight(option) => write!(
+                f, "Options of a weighted Enum can not have a weight of zero (\"{}\")", option
+            ),
+            Error::DuplicateOption { value, index, first } => write!(
+                f, "Attempted to add duplicate strings to Enum data (\"{}\" at {} already at {})",
+                value, index, first
+            ),
+            Error::DuplicateElement { value, index, first } => write!(
+                f, "Set has a duplicate member (\"{}\" at {} already at {})", value, index, first
+            ),
+            Error::Element { index, error } => write!(
+                f, "Sequence element {}: {}", index, error
+            ),
+            Error::DecodeElement { index, value, error } => write!(
+                f, "Sequence element {} (decoded from {}): {}", index, value, error
+            ),
+            Error::Component { index, error } => write!(
+                f, "Component {}: {}", index, error
+            ),
+            Error::Field { name, error } => write!(
+                f, "Field \"{}\": {}", name, error
+            ),
+            Error::UnterminatedSequence { max } => write!(
+                f, "Could not find the end of a variable length sequence with max length {}", max
+            ),
+            Error::Exhausted { index } => write!(
+                f, "Accumulator ran out of data while reading sequence element {}", index
+            ),
+            Error::TrailingData { len } => write!(
+                f, "Accumulator has {} digits of data that were not read", len
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Element { error, .. } => Some(error.as_ref()),
+            Error::DecodeElement { error, .. } => Some(error.as_ref()),
+            Error::Component { error, .. } => Some(error.as_ref()),
+            Error::Field { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+
+    use crate::error::Error;
+
+    #[test]
+    fn display() {
+        let e = Error::ValueOutOfRange {
+            value: String::from("11"),
+            min: String::from("-10"),
+            max: String::from("10"),
+        };
+        assert_eq!(
+            e.to_string(),
+            "Value to encode is outside allowed range (11 is not within -10 to 10)"
+        );
+        let e = Error::CharNotInSet('0');
+        assert!(e.to_string().starts_with("Could not encode character not defined in the character set"));
+        let e = Error::UnknownEnumVariant(String::from("Mango"));
+        assert!(e.to_string().starts_with("Given value not contained in this Enum type"));
+        let e = Error::DecodeOutOfBounds { value: 3, permutations: 3 };
+        assert!(e.to_string().starts_with("Cannot decode data, input larger than possible permutations"));
+        let e = Error::SequenceTooLong { len: 21, max: 20 };
+        assert_eq!(
+            e.to_string(),
+            "Value of length 21 was not able to be compressed by Sequencer with max length 20"
+        );
+        assert_eq!(
+            Error::LengthMismatch { expected: 5, actual: 3 }.to_string(),
+            "Fixed length Sequencer expected 5 values but was given 3"
+        );
+        assert_eq!(
+            Error::DimensionMismatch { rows: 2, cols: 3, expected_rows: 3, expected_cols: 2 }.to_string(),
+            "Fixed size MatrixSequencer expected 3x2 values but was given 2x3"
+        );
+        assert_eq!(
+            Error::MatrixDimension { axis: String::from("rows"), value: 5, max: 4 }.to_string(),
+            "Variable size MatrixSequencer allows at most 4 rows but was given 5"
+        );
+        assert_eq!(Error::DivideByZero.to_string(), "Cannot divide by zero!");
+        let e = Error::Element { index: 3, error: Box::new(Error::CharNotInSet('0')) };
+        assert!(e.to_string().starts_with("Sequence element 3: Could not encode character"));
+        let e = Error::DecodeElement {
+            index: 1,
+            value: 7,
+            error: Box::new(Error::DecodeOutOfBounds { value: 7, permutations: 3 }),
+        };
+        assert!(e.to_string().starts_with("Sequence element 1 (decoded from 7): Cannot decode data"));
+        let e = Error::Component { index: 2, error: Box::new(Error::CharNotInSet('0')) };
+        assert!(e.to_string().starts_with("Component 2: Could not encode character"));
+        let e = Error::Field { name: String::from("tag"), error: Box::new(Error::CharNotInSet('0')) };
+        assert!(e.to_string().starts_with("Field \"tag\": Could not encode character"));
+    }
+}
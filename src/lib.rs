@@ -1,6 +1,38 @@
 //! Adamas
 //! 
 //! Rust library for compressing small amounts of structured data
+//! 
+//! # Features
+//! 
+//! - `std` (default): Implementations that need the standard library 
+//!   (`std::error::Error` for `Error`, `std::io` streaming of an 
+//!   `Accumulator`). Without
+//!   it the crate is `no_std` and only needs `core` and `alloc`, so it can run
+//!   on embedded targets. Check it builds for one with 
+//!   `cargo check --no-default-features --target thumbv7em-none-eabihf` and 
+//!   run the unit tests without it using `cargo test --no-default-features`
+//! - `digit32`: Use 32 bit digits (`u32`/`u64`) in the accumulator instead of 
+//!   64 bit digits (`u64`/`u128`). This is much faster on targets without a 
+//!   native 64x64->128 bit multiply (e.g. Cortex-M0), but limits every spec to
+//!   at most `u32::MAX` permutations and `IntRange`/`FixedPointRange` to `i32`
+//!   values. Specs that need more permutations fail when constructed. Test 
+//!   both configurations with `cargo test` and `cargo test --features digit32`
+//! - `derive`: `#[derive(Adamas)]` to generate `compress` and `decompress`
+//!   methods for a struct from `#[adamas(...)]` attributes on its fields (see
+//!   the `adamas-derive` crate)
+
+#![no_std]
+
+extern crate alloc;
 
-mod accum;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod accum;
 pub mod data;
+mod error;
+
+pub use error::Error;
+
+#[cfg(feature = "derive")]
+pub use adamas_derive::Adamas;